@@ -20,8 +20,11 @@ pub enum Get {
     /// Gets a list of nodes contained in the working dag.
     Dag(UnitObject),
 
-    /// Gets a list of transactions contained in the transaction cache.
+    /// Gets a list of the transactions held in the node's mempool.
     TransactionMemory(UnitObject),
+
+    /// Gets the live tally of the votes on the proposal with the given ID.
+    Tally(HashableObject),
 }
 
 #[derive(Clap, Clone)]
@@ -52,6 +55,15 @@ pub enum List {
 
     /// Gets a list of pending proposals held in the working runtime.
     Proposals(UnitObject),
+
+    /// Gets a list of the parameters that may be modified by proposals in the working runtime.
+    Parameters(UnitObject),
+
+    /// Gets a list of the decisions made on proposals in the working runtime, oldest first.
+    GovernanceHistory(UnitObject),
+
+    /// Gets a list of the votes cast by the accounts held by the working runtime.
+    Votes(UnitObject),
 }
 
 #[derive(Clap, Clone)]
@@ -65,6 +77,24 @@ pub enum Publish {
     Transaction(HashableObject),
 }
 
+#[derive(Clap, Clone)]
+pub enum Cancel {
+    /// Withdraws the pending transaction with the provided hash, signing the cancellation with its sender's account
+    Transaction(HashableObject),
+}
+
+#[derive(Clap, Clone)]
+pub enum Delegate {
+    /// Delegates the votes of an account to another account, or revokes the account's delegation
+    Votes(VoteDelegation),
+}
+
+#[derive(Clap, Clone)]
+pub enum Validate {
+    /// Checks the transaction with the provided hash against the node's validation rules, without publishing it
+    Transaction(HashableObject),
+}
+
 #[derive(Clap, Clone)]
 pub struct Account {
     /// The address of the account
@@ -94,8 +124,69 @@ pub struct Transaction {
     /// The number of finks sent through the transaction
     pub amount: u128,
 
-    /// A UTF-8-encoded payload sent along with the transaction
+    /// A payload sent along with the transaction (base58-encoded, unless it is a memo or JSON document)
     pub payload: String,
+
+    /// The manner in which the payload should be interpreted (i.e. memo, binary, json, contract_call)
+    #[clap(long = "payload-type", default_value = "memo")]
+    pub payload_type: String,
+}
+
+#[derive(Clap, Clone)]
+pub struct VoteDelegation {
+    /// A hex-encoded string representing the address of the account whose votes should be delegated
+    pub delegator: String,
+
+    /// A hex-encoded string representing the address of the account that should vote on the delegator's behalf. If
+    /// none is provided, the delegator's existing delegation is revoked.
+    #[clap(long)]
+    pub to: Option<String>,
+
+    /// The name of the parameter whose proposals the delegation applies to (e.g. config::reward_per_gas). If none is
+    /// provided, the delegation applies to every parameter.
+    #[clap(long)]
+    pub param: Option<String>,
+}
+
+#[derive(Clap, Clone)]
+pub struct NewProposal {
+    /// A hex-encoded string representing the address of the account making the proposal
+    pub proposer: String,
+
+    /// A short, human-readable name for the proposal
+    pub name: String,
+
+    /// The name of the parameter that the proposal should modify (e.g. config::reward_per_gas)
+    pub param: String,
+
+    /// The operation that should be applied to the parameter (i.e. amend, remove, append)
+    pub operation: String,
+
+    /// The value that the operation should write to the parameter, if it writes one
+    pub value: Option<String>,
+
+    /// The number of finks that should be locked from the proposer's balance until the proposal is decided
+    #[clap(long)]
+    pub deposit: Option<String>,
+
+    /// The position in the ledger from which the change should take effect, either as a node count or as the hash of
+    /// a transaction that the ledger hasn't reached yet (the change takes effect as soon as the proposal passes if
+    /// none is given)
+    #[clap(long = "activate-at")]
+    pub activate_at: Option<String>,
+}
+
+#[derive(Clap, Clone)]
+pub struct NewVote {
+    /// A hex-encoded string representing the address of the account casting the vote
+    pub voter: String,
+
+    /// A hex-encoded string representing the ID of the proposal being voted on
+    pub proposal: String,
+
+    /// Votes against the proposal, rather than in favor of it
+    #[clap(long)]
+    pub against: bool,
 }
 
 #[derive(Clap, Clone)]
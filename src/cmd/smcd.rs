@@ -14,7 +14,7 @@ use summercash::{
     p2p::{
         client::Client,
         network, peers,
-        rpc::{accounts::AccountsImpl, dag::DagImpl},
+        rpc::{accounts::AccountsImpl, dag::DagImpl, runtime::RuntimeImpl},
     },
 };
 
@@ -109,6 +109,7 @@ async fn main() -> Result<(), Error> {
         // Register the accounts API
         AccountsImpl::register(&mut io, c.runtime.clone());
         DagImpl::register(&mut io, c.runtime.clone());
+        RuntimeImpl::register(&mut io, c.runtime.clone());
 
         // Create an HTTP server for the RPC API
         let server = ServerBuilder::new(io)
@@ -7,6 +7,7 @@ use clap::Clap;
 
 use summercash::{
     cmd::commands::*,
+    core::types::transaction,
     crypto::hash::Hash,
     p2p::rpc::{accounts, dag, runtime},
 };
@@ -75,6 +76,26 @@ enum SubCommand {
     /// Publishes a SummerCash object of a given type using a provided hash.
     #[clap(name = "publish")]
     Publish(Publish),
+
+    /// Validates a SummerCash object of a given type using a provided hash, without publishing it.
+    #[clap(name = "validate")]
+    Validate(Validate),
+
+    /// Cancels a pending SummerCash object of a given type using a provided hash.
+    #[clap(name = "cancel")]
+    Cancel(Cancel),
+
+    /// Delegates a SummerCash object of a given type to another account.
+    #[clap(name = "delegate")]
+    Delegate(Delegate),
+
+    /// Proposes a change to one of the network's parameters.
+    #[clap(name = "propose")]
+    Propose(NewProposal),
+
+    /// Votes on a pending proposal.
+    #[clap(name = "vote")]
+    Vote(NewVote),
 }
 
 #[tokio::main]
@@ -91,6 +112,11 @@ async fn main() -> Result<(), failure::Error> {
         SubCommand::List(l) => list(opts, l).await,
         SubCommand::Sign(s) => sign(opts, s).await,
         SubCommand::Publish(p) => publish(opts, p).await,
+        SubCommand::Validate(v) => validate(opts, v).await,
+        SubCommand::Cancel(c) => cancel(opts, c).await,
+        SubCommand::Delegate(d) => delegate(opts, d).await,
+        SubCommand::Propose(p) => propose(opts, p).await,
+        SubCommand::Vote(v) => vote(opts, v).await,
     }
 }
 
@@ -122,6 +148,7 @@ async fn create(opts: Opts, c: Create) -> Result<(), failure::Error> {
                     transaction.recipient,
                     transaction.amount,
                     transaction.payload,
+                    Some(transaction.payload_type),
                 )
                 .await
             {
@@ -133,6 +160,12 @@ async fn create(opts: Opts, c: Create) -> Result<(), failure::Error> {
                         "{}Successfully created transaction (use publish command to add to DAG): {}", Emoji::new("📋 ", ""),
                         serde_json::to_string_pretty(&tx)?
                     );
+
+                    // Render the payload according to its content type
+                    info!(
+                        "Payload ({}): {}",
+                        tx.transaction_data.payload.content_type, tx.transaction_data.payload
+                    );
                 }
                 Err(e) => error!("Failed to create transaction: {}", e),
             }
@@ -181,6 +214,11 @@ async fn get(opts: Opts, g: Get) -> Result<(), failure::Error> {
                         // Print out each of the nodes
                         for node in nodes {
                             println!("{}", serde_json::to_string_pretty(&node)?);
+                            println!(
+                                "Payload ({}): {}",
+                                node.transaction.transaction_data.payload.content_type,
+                                node.transaction.transaction_data.payload
+                            );
                         }
                     } else {
                         info!("{}It's empty tho...", Emoji::new("🤔 ", ""));
@@ -202,6 +240,15 @@ async fn get(opts: Opts, g: Get) -> Result<(), failure::Error> {
                 Err(e) => error!("Failed to load transactions stored in memory: {}", e),
             }
         }
+        Get::Tally(proposal) => {
+            // Make a client for the runtime API
+            let client = runtime::Client::new(&opts.rpc_host_url);
+
+            match client.get_tally(proposal.hash).await {
+                Ok(tally) => info!("{}Tally: {}", Emoji::new("🗳️ ", ""), tally),
+                Err(e) => error!("Failed to tally the votes on the proposal: {}", e),
+            }
+        }
     };
 
     Ok(())
@@ -401,6 +448,78 @@ async fn list(opts: Opts, l: List) -> Result<(), failure::Error> {
                 ),
             }
         }
+        List::Parameters(_) => {
+            // Make a client for the runtime API
+            let client = runtime::Client::new(&opts.rpc_host_url);
+
+            // List all of the governable parameters in the runtime
+            match client.list_parameters().await {
+                Ok(parameters) => {
+                    info!(
+                        "{}Found {} governable parameters:",
+                        Emoji::new("🔎 ", ""),
+                        parameters.len()
+                    );
+
+                    // Print out each of the parameters on its own line
+                    for param in parameters {
+                        info!("{}", param);
+                    }
+                }
+
+                // Log the error
+                Err(e) => error!(
+                    "Failed to locate all of the parameters in the runtime: {}",
+                    e
+                ),
+            }
+        }
+        List::GovernanceHistory(_) => {
+            // Make a client for the runtime API
+            let client = runtime::Client::new(&opts.rpc_host_url);
+
+            // List all of the decisions made on proposals in the runtime
+            match client.list_governance_history().await {
+                Ok(history) => {
+                    info!(
+                        "{}Found {} governance decisions:",
+                        Emoji::new("🔎 ", ""),
+                        history.len()
+                    );
+
+                    // Print out each of the decisions, along with the config that resulted from it
+                    for decision in history {
+                        info!("{}", decision);
+                        info!(
+                            "Resulting config: {}",
+                            serde_json::to_string(&decision.config)?
+                        );
+                    }
+                }
+
+                // Log the error
+                Err(e) => error!("Failed to read the governance history: {}", e),
+            }
+        }
+        List::Votes(_) => {
+            // Make a client for the runtime API
+            let client = runtime::Client::new(&opts.rpc_host_url);
+
+            // List all of the votes cast by the node's accounts
+            match client.list_own_votes().await {
+                Ok(votes) => {
+                    info!("{}Found {} votes:", Emoji::new("🔎 ", ""), votes.len());
+
+                    // Print out each of the votes on its own line
+                    for vote in votes {
+                        info!("{}", vote);
+                    }
+                }
+
+                // Log the error
+                Err(e) => error!("Failed to locate all of the node's votes: {}", e),
+            }
+        }
     }
 
     Ok(())
@@ -445,6 +564,134 @@ async fn publish(opts: Opts, p: Publish) -> Result<(), failure::Error> {
     Ok(())
 }
 
+/// Validates the object with the provided hash.
+async fn validate(opts: Opts, v: Validate) -> Result<(), failure::Error> {
+    match v {
+        Validate::Transaction(validatable) => {
+            // Make a client for the DAG API
+            let client = dag::Client::new(&opts.rpc_host_url);
+
+            // Open the transaction, which has yet to be published
+            let tx = transaction::Transaction::from_disk_at_data_directory(
+                &opts.data_dir,
+                Hash::from(validatable.hash),
+            )?;
+
+            match client.validate_tx(&tx).await {
+                Ok(report) if report.is_valid() => info!(
+                    "{}Transaction {} satisfies all {} of the node's validation rules",
+                    Emoji::new("✅ ", ""),
+                    report.tx_hash,
+                    report.checked_rules.len()
+                ),
+                Ok(report) => {
+                    warn!(
+                        "{}Transaction {} violates {} of the node's {} validation rules:",
+                        Emoji::new("🚫 ", ""),
+                        report.tx_hash,
+                        report.violations.len(),
+                        report.checked_rules.len()
+                    );
+
+                    // Print out each of the problems with the transaction
+                    for violation in report.violations {
+                        warn!("{}: {}", violation.rule, violation.reason);
+                    }
+                }
+                Err(e) => error!("Failed to validate tx: {}", e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Cancels the pending object with the provided hash.
+async fn cancel(opts: Opts, c: Cancel) -> Result<(), failure::Error> {
+    match c {
+        Cancel::Transaction(cancellable) => {
+            // Make a client for the DAG API
+            let client = dag::Client::new(&opts.rpc_host_url);
+
+            match client.cancel_tx(cancellable.hash, opts.data_dir).await {
+                Ok(_) => info!("{}Cancelling transaction!", Emoji("🛑 ", "")),
+                Err(e) => error!("Failed to cancel tx: {}", e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Delegates the object to the provided account.
+async fn delegate(opts: Opts, d: Delegate) -> Result<(), failure::Error> {
+    match d {
+        Delegate::Votes(delegation) => {
+            // Make a client for the runtime API
+            let client = runtime::Client::new(&opts.rpc_host_url);
+
+            let revoking = delegation.to.is_none();
+
+            match client
+                .delegate_votes(
+                    delegation.delegator,
+                    delegation.to,
+                    delegation.param,
+                    opts.data_dir,
+                )
+                .await
+            {
+                Ok(_) if revoking => info!("{}Publishing revocation!", Emoji("↩️ ", "")),
+                Ok(_) => info!("{}Publishing delegation!", Emoji("🗳️ ", "")),
+                Err(e) => error!("Failed to delegate votes: {}", e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Proposes the given change to one of the network's parameters.
+async fn propose(opts: Opts, p: NewProposal) -> Result<(), failure::Error> {
+    // Make a client for the runtime API
+    let client = runtime::Client::new(&opts.rpc_host_url);
+
+    match client
+        .propose(
+            p.proposer,
+            p.name,
+            p.param,
+            p.operation,
+            p.value,
+            p.deposit,
+            p.activate_at,
+            opts.data_dir,
+        )
+        .await
+    {
+        Ok(id) => info!("{}Proposed proposal {}!", Emoji("📜 ", ""), id),
+        Err(e) => error!("Failed to make the proposal: {}", e),
+    }
+
+    Ok(())
+}
+
+/// Casts the given vote on a pending proposal.
+async fn vote(opts: Opts, v: NewVote) -> Result<(), failure::Error> {
+    // Make a client for the runtime API
+    let client = runtime::Client::new(&opts.rpc_host_url);
+
+    match client
+        .vote(v.voter, v.proposal, !v.against, opts.data_dir)
+        .await
+    {
+        Ok(_) => info!("{}Cast vote!", Emoji("🗳️ ", "")),
+        Err(e) => error!("Failed to cast the vote: {}", e),
+    }
+
+    Ok(())
+}
+
 /// Applies the given options.
 fn use_options(mut opts: Opts) -> Result<Opts, failure::Error> {
     // Configure the logger
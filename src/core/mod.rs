@@ -1,2 +1,3 @@
 pub mod sys; // Export the sys module
 pub mod types; // Export the core types module
+pub mod vm; // Export the contract virtual machine module
@@ -0,0 +1,104 @@
+use ed25519_dalek; // Import the edwards25519 digital signature library
+
+use serde::{Deserialize, Serialize}; // Import serde serialization
+
+use super::super::super::{common::address::Address, crypto::hash::Hash}; // Import the address & hash primitives
+use super::super::types::{
+    encoding::Canonical,
+    signature::{self, Domain, SigningContext},
+}; // Import the signature primitive & canonical encoding
+
+use std::fmt;
+
+/// A signed request from the sender of a pending transaction to withdraw it before it is accepted.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Cancellation {
+    /// The hash of the transaction that should be withdrawn
+    pub target_tx: Hash,
+    /// The signature of the transaction's sender
+    pub signature: Option<signature::Signature>,
+}
+
+/// Cancellations are hashed and shipped between nodes in their canonical encoding.
+impl Canonical for Cancellation {}
+
+/// Implement a set of cancellation helper methods.
+impl Cancellation {
+    /// Initialize and sign a new cancellation.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash` - The hash of the transaction that should be withdrawn
+    /// * `signature_keypair` - The keypair of the transaction's sender
+    /// * `network` - The name of the network on which the transaction was published
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use summercash::{core::sys::cancellation::Cancellation, crypto::blake3, accounts::account::Account};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let acc: Account = Account::new();
+    /// let c: Cancellation = Cancellation::new(blake3::hash_slice(b"test"), acc.keypair()?, "olympia");
+    ///
+    /// assert_eq!(c.valid("olympia"), true);
+    /// assert_eq!(c.canceller_address(), Some(acc.address()?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        tx_hash: Hash,
+        signature_keypair: ed25519_dalek::Keypair,
+        network: &str,
+    ) -> Cancellation {
+        let mut cancellation = Cancellation {
+            target_tx: tx_hash,
+            signature: None,
+        };
+
+        // Sign the cancellation's hash, in the context of a cancellation on the given network
+        cancellation.signature = Some(
+            SigningContext::new(Domain::Cancellation, network)
+                .sign(&signature_keypair, &*cancellation.hash()),
+        );
+
+        cancellation
+    }
+
+    /// Ensures that the signature associated with the cancellation is authentic. This doesn't ensure that the
+    /// cancellation was signed by the sender of the target transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - The name of the network on which the cancellation should have been signed
+    pub fn valid(&self, network: &str) -> bool {
+        // The cancellation must be invalid if it doesn't even have a signature
+        self.signature.as_ref().map_or(false, |sig| {
+            sig.verify(
+                &SigningContext::new(Domain::Cancellation, network),
+                &*self.hash(),
+            )
+        })
+    }
+
+    /// Hashes the contents of the cancellation, excluding any signature.
+    pub fn hash(&self) -> Hash {
+        // Copy the cancellation since we need to remove the signature from it to ensure validity
+        let mut to_be_hashed = self.clone();
+        to_be_hashed.signature = None;
+
+        to_be_hashed.canonical_hash()
+    }
+
+    /// Derives an address from the signature associated with the cancellation.
+    pub fn canceller_address(&self) -> Option<Address> {
+        self.signature.as_ref().and_then(|sig| sig.address().ok())
+    }
+}
+
+impl fmt::Display for Cancellation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancellation of transaction {}", self.target_tx)
+    }
+}
@@ -1,10 +1,17 @@
 use num::bigint::BigUint; // Add support for large unsigned integers
 
-use std::{fs, io, io::Write}; // Import the filesystem library
+use std::{collections::BTreeMap, fmt, fs, io, io::Write, str::FromStr}; // Import the filesystem library
 
 use serde::{Deserialize, Serialize}; // Import serde serialization
 
-use super::super::super::common; // Import the io module
+use super::super::{
+    super::{
+        common,
+        crypto::hash::{Hash, HASH_SIZE},
+    },
+    types::{payload, stamp},
+}; // Import the io, hash, payload & stamp modules
+use super::threshold::Threshold; // Import the approval threshold type
 
 /// The current version of rust-summercash.
 pub const NODE_VERSION: &str = "v0.1.0";
@@ -20,6 +27,94 @@ pub struct Config {
 
     /// The name of the network
     pub network_name: String,
+
+    /// The identifier of the chain, under which every signature on the network is made. It is fixed to the network's
+    /// original name, so that renaming the network doesn't invalidate any signature (see `chain_id`)
+    #[serde(default)]
+    pub chain_id: String,
+
+    /// The maximum number of bytes that a transaction's payload may contain
+    #[serde(default = "default_max_payload_size")]
+    pub max_payload_size: usize,
+
+    /// The amount of work that must be proven by the stamp attached to each transaction (i.e. the number of leading
+    /// zero bits of the stamp's work hash)
+    #[serde(default = "default_stamp_difficulty")]
+    pub stamp_difficulty: u8,
+
+    /// The threshold that proposals targeting each parameter must meet, for parameters whose thresholds have been
+    /// changed from their defaults
+    #[serde(default)]
+    pub approval_thresholds: BTreeMap<String, Threshold>,
+
+    /// The values of the constants that govern the execution of transactions, and the positions in the ledger from
+    /// which each set of values took effect (empty if the constants have never been changed)
+    #[serde(default)]
+    pub history: Vec<ConfigEpoch>,
+}
+
+/// The values of the constants that govern the execution of transactions from some position in the ledger onwards.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ConfigEpoch {
+    /// The number of ledger nodes that precede the first transaction executed under the values
+    pub starts_at: u64,
+
+    /// The amount of finks per gas to give as a reward for validating a tx
+    pub reward_per_gas: BigUint,
+
+    /// The maximum number of bytes that a transaction's payload may contain
+    pub max_payload_size: usize,
+
+    /// The number of leading zero bits that each transaction's stamp must prove
+    pub stamp_difficulty: u8,
+}
+
+/// A position in the ledger, agreed upon by every node, from which a change to the network's parameters takes effect.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ActivationPoint {
+    /// The change takes effect once the ledger contains the given number of nodes
+    Height(u64),
+    /// The change takes effect after the transaction with the given hash has been appended to the ledger
+    Transaction(Hash),
+}
+
+impl fmt::Display for ActivationPoint {
+    /// Formats the activation point as the position that it names (e.g. height 100).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Height(height) => write!(f, "height {}", height),
+            Self::Transaction(tx_hash) => write!(f, "transaction {}", tx_hash),
+        }
+    }
+}
+
+impl FromStr for ActivationPoint {
+    type Err = String;
+
+    /// Parses an activation point from either a node count (e.g. 100), or the hash of a transaction.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(height) = s.parse::<u64>() {
+            return Ok(Self::Height(height));
+        }
+
+        match bs58::decode(s).into_vec() {
+            Ok(bytes) if bytes.len() == HASH_SIZE => Ok(Self::Transaction(Hash::new(bytes))),
+            _ => Err(format!(
+                "expected a node count or a transaction hash, found {}",
+                s
+            )),
+        }
+    }
+}
+
+/// Gets the maximum payload size of networks whose configs don't specify one.
+fn default_max_payload_size() -> usize {
+    payload::DEFAULT_MAX_PAYLOAD_SIZE
+}
+
+/// Gets the stamp difficulty of networks whose configs don't specify one.
+fn default_stamp_difficulty() -> u8 {
+    stamp::DEFAULT_STAMP_DIFFICULTY
 }
 
 /// Implement a set of config helper methods.
@@ -53,6 +148,70 @@ impl Config {
         Ok(()) // All good!
     }
 
+    /// Gets the immutable identifier of the chain, to which every signature on the network is bound. Configs
+    /// persisted before the identifier was recorded are identified by the network's name, which can't have changed
+    /// yet, since renaming the network records it.
+    pub fn chain_id(&self) -> &str {
+        if self.chain_id.is_empty() {
+            &self.network_name
+        } else {
+            &self.chain_id
+        }
+    }
+
+    /// Gets the values of the constants that govern the execution of transactions under the current config.
+    ///
+    /// # Arguments
+    ///
+    /// * `starts_at` - The position in the ledger from which the values take effect
+    pub fn epoch(&self, starts_at: u64) -> ConfigEpoch {
+        ConfigEpoch {
+            starts_at,
+            reward_per_gas: self.reward_per_gas.clone(),
+            max_payload_size: self.max_payload_size,
+            stamp_difficulty: self.stamp_difficulty,
+        }
+    }
+
+    /// Gets the values of the constants that govern the execution of the transaction at the given position in the
+    /// ledger, so that replaying the ledger applies the rules that were in effect at each point.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The number of ledger nodes that precede the transaction
+    pub fn epoch_at(&self, position: u64) -> ConfigEpoch {
+        self.history
+            .iter()
+            .rev()
+            .find(|epoch| epoch.starts_at <= position)
+            .cloned()
+            .unwrap_or_else(|| self.epoch(position))
+    }
+
+    /// Records that the current values of the constants took effect at the given position in the ledger, replacing
+    /// the given values. Positions never move backwards, so a change recorded after a later one takes effect from the
+    /// later one's position.
+    ///
+    /// # Arguments
+    ///
+    /// * `starts_at` - The position in the ledger from which the current values take effect
+    /// * `previous` - The values that were in effect before the change
+    pub fn record_epoch(&mut self, starts_at: u64, previous: ConfigEpoch) {
+        // The values in effect before the first recorded change had been in effect since the ledger began
+        if self.history.is_empty() {
+            self.history.push(ConfigEpoch {
+                starts_at: 0,
+                ..previous
+            });
+        }
+
+        let starts_at = starts_at.max(self.history.last().map_or(0, |epoch| epoch.starts_at));
+
+        // Any earlier change made at the same position has been superseded by this one
+        self.history.retain(|epoch| epoch.starts_at < starts_at);
+        self.history.push(self.epoch(starts_at));
+    }
+
     /// Read a persisted config form the disk.
     pub fn read_from_disk(network_name: &str) -> io::Result<Config> {
         let file = fs::File::open(common::io::format_config_dir(&format!(
@@ -103,6 +262,11 @@ mod tests {
         let config = Config {
             reward_per_gas: BigUint::from_str("10000000000000000000000000000000000000000").unwrap(), // Venezuela style
             network_name: "olympia1".to_owned(),
+            chain_id: "olympia1".to_owned(),
+            max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+            stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+            approval_thresholds: BTreeMap::new(),
+            history: Vec::new(),
         }; // Initialize network config
 
         config.write_to_disk().unwrap(); // Panic if not Ok()
@@ -116,6 +280,11 @@ mod tests {
         let config = Config {
             reward_per_gas: BigUint::from_str("10000000000000000000000000000000000000000").unwrap(), // Venezuela style
             network_name: "olympia".to_owned(),
+            chain_id: "olympia".to_owned(),
+            max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+            stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+            approval_thresholds: BTreeMap::new(),
+            history: Vec::new(),
         }; // Initialize network config
 
         config.write_to_disk().unwrap(); // Panic if not Ok()
@@ -127,4 +296,63 @@ mod tests {
         // Delete the test config file
         fs::remove_file(common::io::format_config_dir("network_olympia.json")).unwrap();
     }
+
+    #[test]
+    fn test_epoch_at() {
+        let mut config = Config {
+            reward_per_gas: BigUint::from(10u64),
+            network_name: "olympia_epochs".to_owned(),
+            chain_id: "olympia_epochs".to_owned(),
+            max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+            stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+            approval_thresholds: BTreeMap::new(),
+            history: Vec::new(),
+        }; // Initialize network config
+
+        // Configs that have never been changed apply their values everywhere
+        assert_eq!(config.epoch_at(5).reward_per_gas, BigUint::from(10u64));
+
+        let previous = config.epoch(0);
+        config.reward_per_gas = BigUint::from(20u64);
+        config.record_epoch(5, previous);
+
+        // Transactions before the change are executed under the old values, and the rest under the new ones
+        assert_eq!(config.epoch_at(4).reward_per_gas, BigUint::from(10u64));
+        assert_eq!(config.epoch_at(5).reward_per_gas, BigUint::from(20u64));
+        assert_eq!(config.epoch_at(100).reward_per_gas, BigUint::from(20u64));
+
+        // Changes can't take effect before the last recorded change
+        let previous = config.epoch(0);
+        config.stamp_difficulty += 1;
+        config.record_epoch(3, previous);
+        assert_eq!(config.history.len(), 2);
+        assert_eq!(config.epoch_at(5).stamp_difficulty, config.stamp_difficulty);
+        assert_eq!(config.epoch_at(5).reward_per_gas, BigUint::from(20u64));
+
+        // The history survives a round trip through the disk
+        config.write_to_disk().unwrap();
+        assert_eq!(
+            Config::read_from_disk("olympia_epochs").unwrap().history,
+            config.history
+        );
+
+        // Delete the test config file
+        fs::remove_file(common::io::format_config_dir("network_olympia_epochs.json")).unwrap();
+    }
+
+    #[test]
+    fn test_activation_point() {
+        assert_eq!(
+            ActivationPoint::from_str("100"),
+            Ok(ActivationPoint::Height(100))
+        );
+
+        let tx_hash = Hash::new(vec![7; HASH_SIZE]);
+        assert_eq!(
+            ActivationPoint::from_str(&tx_hash.to_str()),
+            Ok(ActivationPoint::Transaction(tx_hash))
+        );
+
+        assert!(ActivationPoint::from_str("soon").is_err());
+    }
 }
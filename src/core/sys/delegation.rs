@@ -0,0 +1,157 @@
+use ed25519_dalek; // Import the edwards25519 digital signature library
+
+use serde::{Deserialize, Serialize}; // Import serde serialization
+
+use super::super::super::{common::address::Address, crypto::hash::Hash}; // Import the address & hash primitives
+use super::super::types::{
+    encoding::Canonical,
+    payload::ContentType,
+    signature::{self, Domain, SigningContext},
+    transaction::Transaction,
+}; // Import the signature primitive, canonical encoding & transaction types
+
+use std::fmt;
+
+/// A signed request from an account to have another account vote with its weight, or to stop doing so. Delegations
+/// are carried by transactions sent by their delegators, and take effect once appended to the ledger.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Delegation {
+    /// The account that should vote on the delegator's behalf, or none if the delegator is revoking its delegation
+    pub delegate: Option<Address>,
+    /// The name of the parameter whose proposals the delegation applies to, or none if it applies to every parameter
+    pub param_name: Option<String>,
+    /// The time at which the delegation was made. Delegations are ordered by their positions in the ledger, rather
+    /// than by this time, which only keeps otherwise identical delegations apart.
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    /// The signature of the delegator
+    pub signature: Option<signature::Signature>,
+}
+
+/// Delegations are hashed and shipped between nodes in their canonical encoding.
+impl Canonical for Delegation {}
+
+/// Implement a set of delegation helper methods.
+impl Delegation {
+    /// Initialize and sign a new delegation.
+    ///
+    /// # Arguments
+    ///
+    /// * `delegate` - The account that should vote on the delegator's behalf, or none to revoke a delegation
+    /// * `param_name` - The name of the parameter that the delegation applies to, or none for every parameter
+    /// * `signature_keypair` - The keypair of the delegator
+    /// * `network` - The name of the network on which the delegation is made
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use summercash::{core::sys::delegation::Delegation, accounts::account::Account};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let delegator: Account = Account::new();
+    /// let delegate: Account = Account::new();
+    /// let d: Delegation = Delegation::new(Some(delegate.address()?), None, delegator.keypair()?, "olympia");
+    ///
+    /// assert_eq!(d.valid("olympia"), true);
+    /// assert_eq!(d.delegator_address(), Some(delegator.address()?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        delegate: Option<Address>,
+        param_name: Option<String>,
+        signature_keypair: ed25519_dalek::Keypair,
+        network: &str,
+    ) -> Delegation {
+        let mut delegation = Delegation {
+            delegate,
+            param_name,
+            issued_at: chrono::Utc::now(),
+            signature: None,
+        };
+
+        // Sign the delegation's hash, in the context of a delegation on the given network
+        delegation.signature = Some(
+            SigningContext::new(Domain::Delegation, network)
+                .sign(&signature_keypair, &*delegation.hash()),
+        );
+
+        delegation
+    }
+
+    /// Ensures that the signature associated with the delegation is authentic.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - The name of the network on which the delegation should have been signed
+    pub fn valid(&self, network: &str) -> bool {
+        // The delegation must be invalid if it doesn't even have a signature
+        self.signature.as_ref().map_or(false, |sig| {
+            sig.verify(
+                &SigningContext::new(Domain::Delegation, network),
+                &*self.hash(),
+            )
+        })
+    }
+
+    /// Hashes the contents of the delegation, excluding any signature.
+    pub fn hash(&self) -> Hash {
+        // Copy the delegation since we need to remove the signature from it to ensure validity
+        let mut to_be_hashed = self.clone();
+        to_be_hashed.signature = None;
+
+        to_be_hashed.canonical_hash()
+    }
+
+    /// Derives the address of the delegator from the signature associated with the delegation.
+    pub fn delegator_address(&self) -> Option<Address> {
+        self.signature.as_ref().and_then(|sig| sig.address().ok())
+    }
+
+    /// Extracts the delegation carried by the given transaction, ensuring that it was made by the transaction's
+    /// sender. Returns none if the transaction doesn't carry a delegation.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction whose payload may contain a delegation
+    /// * `network` - The name of the network on which the delegation should have been signed
+    pub fn from_transaction(tx: &Transaction, network: &str) -> Option<Result<Delegation, String>> {
+        if tx.transaction_data.payload.content_type != ContentType::Delegation {
+            return None;
+        }
+
+        Some(
+            Delegation::decode(&tx.transaction_data.payload.data)
+                .map_err(|e| e.to_string())
+                .and_then(|delegation| {
+                    // Only the delegator can delegate its own votes
+                    if !delegation.valid(network) {
+                        return Err("signature is invalid".to_owned());
+                    }
+                    if delegation.delegator_address() != Some(tx.transaction_data.sender) {
+                        return Err("not made by the transaction's sender".to_owned());
+                    }
+
+                    if delegation.delegate == Some(tx.transaction_data.sender) {
+                        return Err("accounts can't delegate their votes to themselves".to_owned());
+                    }
+
+                    Ok(delegation)
+                }),
+        )
+    }
+}
+
+impl fmt::Display for Delegation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = self
+            .param_name
+            .as_ref()
+            .map_or("every parameter".to_owned(), |param| format!("'{}'", param));
+
+        match &self.delegate {
+            Some(delegate) => write!(f, "delegation of votes on {} to {}", scope, delegate),
+            None => write!(f, "revocation of the delegation of votes on {}", scope),
+        }
+    }
+}
@@ -0,0 +1,557 @@
+use num::{
+    bigint::{BigInt, BigUint},
+    Zero,
+}; // Add support for large integers
+
+use serde::{Deserialize, Serialize}; // Import serde serialization
+
+use super::{
+    super::super::{common::address::Address, crypto::hash::Hash},
+    config::Config,
+    proposal::{Operation, Proposal},
+    threshold::Threshold,
+}; // Import the address, hash, config, proposal & threshold types
+
+use std::{collections::HashMap, fmt};
+
+/// The number of seconds for which a proposal is remembered after it has been closed.
+pub const CLOSED_PROPOSAL_RETENTION_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// The name of the tree in which each decision made on a proposal is stored, in the order in which it was made.
+pub const HISTORY_TREE: &str = "history";
+
+/// The name of the tree in which the total deposit burned from each account is stored.
+pub const BURNED_DEPOSITS_TREE: &str = "burned_deposits";
+
+/// The factor by which the weight of the votes against a rejected proposal must exceed that of the votes in its favor
+/// for the proposer's deposit to be burned, rather than refunded.
+pub const DEPOSIT_BURN_RATIO: u8 = 4;
+
+/// The stage of its lifecycle that a proposal is in.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ProposalState {
+    /// The proposal may still be voted on
+    Open,
+    /// The proposal won a majority of the vote, but couldn't be executed
+    Passed,
+    /// The proposal lost a majority of the vote, was invalid, or was withdrawn
+    Rejected,
+    /// The proposal's voting deadline passed before it won or lost a majority of the vote
+    Expired,
+    /// The proposal won a majority of the vote, and was executed
+    Executed,
+    /// The proposal won a majority of the vote, and will be executed once the ledger reaches its activation point
+    Scheduled,
+}
+
+impl ProposalState {
+    /// Checks whether or not the proposal can still be voted on.
+    pub fn is_open(self) -> bool {
+        self == Self::Open
+    }
+}
+
+impl fmt::Display for ProposalState {
+    /// Formats the state according to its name (e.g. open).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Open => "open",
+                Self::Passed => "passed",
+                Self::Rejected => "rejected",
+                Self::Expired => "expired",
+                Self::Executed => "executed",
+                Self::Scheduled => "scheduled",
+            }
+        )
+    }
+}
+
+/// A proposal, along with the votes that have been cast on it, and the stage of its lifecycle that it is in.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProposalRecord {
+    /// The proposal
+    pub proposal: Proposal,
+
+    /// The stage of its lifecycle that the proposal is in
+    pub state: ProposalState,
+
+    /// The total weight of the votes in favor of the proposal, less that of the votes against it
+    pub tally: BigInt,
+
+    /// Each of the addresses that have voted on the proposal
+    pub voters: Vec<Address>,
+
+    /// The weight counted toward the tally from each address, whether it voted itself or through a delegate
+    /// (negative if counted against the proposal)
+    pub weights: HashMap<Address, BigInt>,
+
+    /// The time at which the proposal stopped being open, if it has
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ProposalRecord {
+    /// Initializes a new record for a proposal that hasn't yet been voted on.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal
+    pub fn new(proposal: Proposal) -> Self {
+        Self {
+            proposal,
+            state: ProposalState::Open,
+            tally: BigInt::zero(),
+            voters: Vec::new(),
+            weights: HashMap::new(),
+            closed_at: None,
+        }
+    }
+
+    /// Moves the proposal into the given state, closing it at the given time if the state isn't open.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The proposal's new state
+    /// * `now` - The current time
+    pub fn transition(mut self, state: ProposalState, now: chrono::DateTime<chrono::Utc>) -> Self {
+        self.state = state;
+        self.closed_at = if state.is_open() { None } else { Some(now) };
+
+        self
+    }
+}
+
+/// Splits the weight counted toward a proposal's tally from each address into the total weight in favor of the
+/// proposal, and the total weight against it.
+///
+/// # Arguments
+///
+/// * `weights` - The signed weight counted from each address
+pub fn split_weights(weights: &HashMap<Address, BigInt>) -> (BigInt, BigInt) {
+    weights.values().fold(
+        (BigInt::zero(), BigInt::zero()),
+        |(in_favor, against), weight| {
+            if *weight > BigInt::zero() {
+                (in_favor + weight, against)
+            } else {
+                (in_favor, against - weight)
+            }
+        },
+    )
+}
+
+/// The live tally of the votes on a proposal, along with the weight that it must reach to be decided.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProposalTally {
+    /// The ID of the proposal
+    pub proposal_id: Hash,
+
+    /// The stage of its lifecycle that the proposal is in
+    pub state: ProposalState,
+
+    /// The total weight of the votes in favor of the proposal
+    pub in_favor: BigInt,
+
+    /// The total weight of the votes against the proposal
+    pub against: BigInt,
+
+    /// The threshold that the proposal must meet to pass
+    pub threshold: Threshold,
+
+    /// The number of coins in circulation, against which the proposal's quorum is measured
+    pub issuance: BigUint,
+
+    /// The number of addresses that have voted on the proposal
+    pub voters: usize,
+
+    /// The time after which votes on the proposal are no longer accepted
+    pub voting_deadline: chrono::DateTime<chrono::Utc>,
+}
+
+impl ProposalTally {
+    /// Initializes a new tally from the record of a proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record of the proposal
+    /// * `threshold` - The threshold that the proposal must meet to pass
+    /// * `issuance` - The number of coins in circulation
+    pub fn new(record: &ProposalRecord, threshold: Threshold, issuance: BigUint) -> Self {
+        let (in_favor, against) = split_weights(&record.weights);
+
+        Self {
+            proposal_id: record.proposal.proposal_id,
+            state: record.state,
+            in_favor,
+            against,
+            threshold,
+            issuance,
+            voters: record.voters.len(),
+            voting_deadline: record.proposal.voting_deadline,
+        }
+    }
+}
+
+impl fmt::Display for ProposalTally {
+    /// Formats the tally as a single line (e.g. proposal ... is open: 10 in favor, 2 against ...).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "proposal {} is {}: {} in favor, {} against from {} voters (needs a quorum of {} and more than {} of {} stake, of {} issued; voting closes at {})",
+            self.proposal_id,
+            self.state,
+            self.in_favor,
+            self.against,
+            self.voters,
+            self.threshold.quorum,
+            self.threshold.approval,
+            self.threshold.base,
+            self.issuance,
+            self.voting_deadline
+        )
+    }
+}
+
+/// A durable record of the decision made on a proposal, along with the network config that resulted from it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GovernanceDecision {
+    /// The ID of the proposal
+    pub proposal_id: Hash,
+
+    /// The name of the proposal
+    pub proposal_name: String,
+
+    /// The name of the parameter that the proposal targeted
+    pub param_name: String,
+
+    /// The change that the proposal would make to the parameter
+    pub operation: Operation,
+
+    /// The state in which the proposal was closed
+    pub outcome: ProposalState,
+
+    /// The total weight of the votes in favor of the proposal, less that of the votes against it
+    pub tally: BigInt,
+
+    /// Each of the addresses that voted on the proposal
+    pub voters: Vec<Address>,
+
+    /// The network config in effect once the decision was made
+    pub config: Config,
+
+    /// The time at which the decision was made
+    pub decided_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl GovernanceDecision {
+    /// Initializes a new decision from the record of a closed proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record of the closed proposal
+    /// * `config` - The network config in effect once the proposal was closed
+    pub fn new(record: &ProposalRecord, config: Config) -> Self {
+        Self {
+            proposal_id: record.proposal.proposal_id,
+            proposal_name: record.proposal.proposal_name.clone(),
+            param_name: record.proposal.proposal_data.param_name.clone(),
+            operation: record.proposal.proposal_data.operation.clone(),
+            outcome: record.state,
+            tally: record.tally.clone(),
+            voters: record.voters.clone(),
+            config,
+            decided_at: record.closed_at.unwrap_or_else(chrono::Utc::now),
+        }
+    }
+}
+
+impl fmt::Display for GovernanceDecision {
+    /// Formats the decision as a single line (e.g. 2020-09-13 12:26:40 UTC: proposal ... was executed).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: proposal {} ('{}') to {} '{}' was {} with a tally of {} from {} voters",
+            self.decided_at,
+            self.proposal_id,
+            self.proposal_name,
+            self.operation,
+            self.param_name,
+            self.outcome,
+            self.tally,
+            self.voters.len()
+        )
+    }
+}
+
+/// A persistent collection of proposal records, keyed by proposal ID.
+pub struct GovernanceStore {
+    /// The database in which the records are stored
+    db: sled::Db,
+}
+
+impl GovernanceStore {
+    /// Opens the governance store kept at the given path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory in which the store's database is kept
+    pub fn open(path: &str) -> Result<Self, sled::Error> {
+        Ok(Self {
+            db: sled::open(path)?,
+        })
+    }
+
+    /// Opens the governance store kept for the given network in the given data directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - The directory in which the node's data is stored
+    /// * `network` - The name of the network that the proposals belong to
+    pub fn open_at_data_directory(data_dir: &str, network: &str) -> Result<Self, sled::Error> {
+        Self::open(&format!("{}/governance/network_{}", data_dir, network))
+    }
+
+    /// Opens a governance store whose records are discarded once it is dropped.
+    pub fn temporary() -> Result<Self, sled::Error> {
+        Ok(Self {
+            db: sled::Config::new().temporary(true).open()?,
+        })
+    }
+
+    /// Stores the provided record, replacing any record of the same proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record that should be stored
+    pub fn insert(&self, record: &ProposalRecord) -> Result<(), sled::Error> {
+        self.db.insert(
+            record.proposal.proposal_id.to_vec(),
+            bincode::serialize(record).unwrap_or_default(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Gets the record of the proposal with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The ID of the proposal
+    pub fn get(&self, proposal_id: &Hash) -> Result<Option<ProposalRecord>, sled::Error> {
+        Ok(self
+            .db
+            .get(proposal_id.to_vec())?
+            .and_then(|encoded| bincode::deserialize(&encoded).ok()))
+    }
+
+    /// Gets each of the stored records.
+    pub fn records(&self) -> Result<Vec<ProposalRecord>, sled::Error> {
+        let mut records = Vec::new();
+
+        for pair in self.db.iter() {
+            // Skip any records that can't be read, rather than losing the rest
+            if let Ok(record) = bincode::deserialize::<ProposalRecord>(&pair?.1) {
+                records.push(record);
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Removes the records of each of the proposals that were closed before the given time, and returns their IDs.
+    /// Scheduled proposals are kept until they are executed, however long ago they were closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutoff` - The time before which closed proposals should be forgotten
+    pub fn remove_closed_before(
+        &self,
+        cutoff: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Hash>, sled::Error> {
+        let mut removed = Vec::new();
+
+        for record in self.records()? {
+            if record.state != ProposalState::Scheduled
+                && record
+                    .closed_at
+                    .map_or(false, |closed_at| closed_at < cutoff)
+            {
+                self.db.remove(record.proposal.proposal_id.to_vec())?;
+                removed.push(record.proposal.proposal_id);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Appends the provided decision to the governance history.
+    ///
+    /// # Arguments
+    ///
+    /// * `decision` - The decision that should be recorded
+    pub fn append_decision(&self, decision: &GovernanceDecision) -> Result<(), sled::Error> {
+        // Key each decision by a monotonically increasing ID, so that the history stays in order
+        let key = self.db.generate_id()?.to_be_bytes();
+
+        self.db
+            .open_tree(HISTORY_TREE)?
+            .insert(key, bincode::serialize(decision).unwrap_or_default())?;
+
+        Ok(())
+    }
+
+    /// Gets each of the recorded decisions, in the order in which they were made.
+    pub fn history(&self) -> Result<Vec<GovernanceDecision>, sled::Error> {
+        let mut history = Vec::new();
+
+        for pair in self.db.open_tree(HISTORY_TREE)?.iter() {
+            // Skip any decisions that can't be read, rather than losing the rest
+            if let Ok(decision) = bincode::deserialize::<GovernanceDecision>(&pair?.1) {
+                history.push(decision);
+            }
+        }
+
+        Ok(history)
+    }
+
+    /// Records the total deposit that has been burned from the given account.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account whose deposits were burned
+    /// * `burned` - The total number of finks burned from the account
+    pub fn insert_burned_deposit(
+        &self,
+        account: &Address,
+        burned: &BigUint,
+    ) -> Result<(), sled::Error> {
+        self.db.open_tree(BURNED_DEPOSITS_TREE)?.insert(
+            account.to_vec(),
+            bincode::serialize(&(account, burned)).unwrap_or_default(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Gets the total deposit that has been burned from each account.
+    pub fn burned_deposits(&self) -> Result<HashMap<Address, BigUint>, sled::Error> {
+        let mut burned = HashMap::new();
+
+        for pair in self.db.open_tree(BURNED_DEPOSITS_TREE)?.iter() {
+            // Skip any amounts that can't be read, rather than losing the rest
+            if let Ok((account, amount)) = bincode::deserialize::<(Address, BigUint)>(&pair?.1) {
+                burned.insert(account, amount);
+            }
+        }
+
+        Ok(burned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            super::types::{payload, stamp},
+            config,
+            proposal::ProposalData,
+        },
+        *,
+    };
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_proposal_store() {
+        let store = GovernanceStore::temporary().unwrap();
+        let now = chrono::Utc::now();
+
+        let open = ProposalRecord::new(Proposal::new(
+            "open".to_owned(),
+            ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+        ));
+        let executed = ProposalRecord::new(Proposal::new(
+            "executed".to_owned(),
+            ProposalData::new("config::stamp_difficulty".to_owned(), Operation::Remove),
+        ))
+        .transition(ProposalState::Executed, now);
+        let scheduled = ProposalRecord::new(Proposal::new(
+            "scheduled".to_owned(),
+            ProposalData::new("config::max_payload_size".to_owned(), Operation::Remove),
+        ))
+        .transition(ProposalState::Scheduled, now);
+
+        store.insert(&open).unwrap();
+        store.insert(&executed).unwrap();
+        store.insert(&scheduled).unwrap();
+
+        // Each record should survive a round trip
+        assert_eq!(store.records().unwrap().len(), 3);
+        assert_eq!(
+            store
+                .get(&executed.proposal.proposal_id)
+                .unwrap()
+                .unwrap()
+                .state,
+            ProposalState::Executed
+        );
+
+        // Only closed proposals that won't be executed later may be forgotten
+        assert_eq!(
+            store
+                .remove_closed_before(now + chrono::Duration::seconds(1))
+                .unwrap(),
+            vec![executed.proposal.proposal_id]
+        );
+        assert!(store.get(&executed.proposal.proposal_id).unwrap().is_none());
+        assert!(store.get(&open.proposal.proposal_id).unwrap().is_some());
+        assert!(store
+            .get(&scheduled.proposal.proposal_id)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_governance_history() {
+        let store = GovernanceStore::temporary().unwrap();
+        let config = Config {
+            reward_per_gas: BigUint::from(config::DEFAULT_REWARD_PER_GAS),
+            network_name: "olympia".to_owned(),
+            chain_id: "olympia".to_owned(),
+            max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+            stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+            approval_thresholds: BTreeMap::new(),
+            history: Vec::new(),
+        };
+
+        for (name, outcome) in &[
+            ("first", ProposalState::Rejected),
+            ("second", ProposalState::Executed),
+        ] {
+            let record = ProposalRecord::new(Proposal::new(
+                (*name).to_owned(),
+                ProposalData::new("config::stamp_difficulty".to_owned(), Operation::Remove),
+            ))
+            .transition(*outcome, chrono::Utc::now());
+
+            store
+                .append_decision(&GovernanceDecision::new(&record, config.clone()))
+                .unwrap();
+        }
+
+        // Decisions should be kept in the order in which they were made
+        let history = store.history().unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|decision| (decision.proposal_name.as_str(), decision.outcome))
+                .collect::<Vec<_>>(),
+            vec![
+                ("first", ProposalState::Rejected),
+                ("second", ProposalState::Executed)
+            ]
+        );
+    }
+}
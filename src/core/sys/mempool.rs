@@ -0,0 +1,734 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, Write},
+}; // Import collections & the filesystem library
+
+use serde::{de::DeserializeOwned, Serialize}; // Import serde serialization
+
+use super::super::{
+    super::{common::address::Address, crypto::hash::Hash},
+    types::transaction::Transaction,
+}; // Import the address, hash & transaction types
+
+/// The default maximum number of transactions that may be held in a mempool.
+pub const DEFAULT_MEMPOOL_CAPACITY: usize = 4096;
+
+/// The default number of seconds for which a transaction may remain in a mempool after it was admitted.
+pub const DEFAULT_MEMPOOL_TTL_SECS: i64 = 60 * 60;
+
+/// An error encountered while admitting a transaction to a mempool.
+#[derive(Debug, Fail)]
+pub enum MempoolError {
+    #[fail(display = "transaction {} is already in the mempool", tx_hash)]
+    AlreadyKnown {
+        tx_hash: String, // The hash of the duplicate transaction
+    },
+    #[fail(
+        display = "transaction {} conflicts with transaction {}, which uses the same nonce ({})",
+        tx_hash, existing_tx_hash, nonce
+    )]
+    NonceConflict {
+        tx_hash: String,          // The hash of the rejected transaction
+        existing_tx_hash: String, // The hash of the transaction already using the nonce
+        nonce: u64,               // The nonce used by both transactions
+    },
+    #[fail(display = "transaction {} has expired", tx_hash)]
+    Expired {
+        tx_hash: String, // The hash of the expired transaction
+    },
+    #[fail(display = "the mempool is full ({} transactions)", capacity)]
+    Full {
+        capacity: usize, // The maximum number of transactions in the mempool
+    },
+    #[fail(display = "transaction {} has been replaced or cancelled", tx_hash)]
+    Withdrawn {
+        tx_hash: String, // The hash of the withdrawn transaction
+    },
+    #[fail(
+        display = "transaction {} isn't newer than transaction {}, which it would replace",
+        tx_hash, existing_tx_hash
+    )]
+    StaleReplacement {
+        tx_hash: String,          // The hash of the rejected replacement
+        existing_tx_hash: String, // The hash of the transaction that it would replace
+    },
+    #[fail(
+        display = "transaction {} doesn't replace a pending transaction",
+        tx_hash
+    )]
+    NothingToReplace {
+        tx_hash: String, // The hash of the rejected replacement
+    },
+    #[fail(
+        display = "transaction {} is time-locked beyond the time of its parents, so it can never mature",
+        tx_hash
+    )]
+    NeverMatures {
+        tx_hash: String, // The hash of the rejected transaction
+    },
+    #[fail(display = "transaction {} isn't signed by its sender", tx_hash)]
+    Unsigned {
+        tx_hash: String, // The hash of the rejected transaction
+    },
+}
+
+/// Persists the given value to the disk, in a file kept alongside the mempool and named after the kind of data that
+/// the value holds.
+///
+/// # Arguments
+///
+/// * `data_dir` - The data directory in which the mempool is stored
+/// * `kind` - The kind of data held by the value (e.g. network, for the mempool's transactions)
+/// * `network_name` - The name of the network that the value belongs to
+/// * `value` - The value that should be persisted
+pub fn write_alongside_mempool<T: Serialize + ?Sized>(
+    data_dir: &str,
+    kind: &str,
+    network_name: &str,
+    value: &T,
+) -> io::Result<()> {
+    // Make the mempool directory
+    fs::create_dir_all(format!("{}/mempool", data_dir))?;
+
+    let mut file = fs::File::create(format!(
+        "{}/mempool/{}_{}.json",
+        data_dir, kind, network_name
+    ))?; // Initialize file
+
+    file.write_all(serde_json::to_vec_pretty(value)?.as_slice()) // Serialize
+}
+
+/// Reads a value persisted alongside the mempool from the disk.
+///
+/// # Arguments
+///
+/// * `data_dir` - The data directory in which the mempool is stored
+/// * `kind` - The kind of data held by the value (e.g. network, for the mempool's transactions)
+/// * `network_name` - The name of the network that the value belongs to
+pub fn read_alongside_mempool<T: DeserializeOwned>(
+    data_dir: &str,
+    kind: &str,
+    network_name: &str,
+) -> io::Result<T> {
+    let file = fs::File::open(format!(
+        "{}/mempool/{}_{}.json",
+        data_dir, kind, network_name
+    ))?; // Open the file
+
+    Ok(serde_json::from_reader(file)?)
+}
+
+/// A bounded set of pending transactions, queued per sender in order of their nonces.
+pub struct Mempool {
+    /// The maximum number of transactions that may be held in the mempool
+    capacity: usize,
+
+    /// The number of seconds for which a transaction may remain in the mempool after it was admitted
+    ttl: chrono::Duration,
+
+    /// Each of the transactions in the mempool
+    transactions: HashMap<Hash, Transaction>,
+
+    /// The time at which each of the transactions in the mempool was admitted. Transactions expire according to the
+    /// time at which they were admitted, rather than the timestamps set by their senders.
+    admitted: HashMap<Hash, chrono::DateTime<chrono::Utc>>,
+
+    /// The hashes of each sender's transactions, ordered by nonce
+    senders: HashMap<Address, BTreeMap<u64, Hash>>,
+
+    /// The hashes of transactions that have been replaced or cancelled, and the times at which they were withdrawn,
+    /// so that they aren't admitted again
+    withdrawn: HashMap<Hash, chrono::DateTime<chrono::Utc>>,
+}
+
+/// Implement a set of mempool helper methods.
+impl Mempool {
+    /// Initializes a new, empty mempool that can hold the given number of transactions.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of transactions that may be held in the mempool
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ttl: chrono::Duration::seconds(DEFAULT_MEMPOOL_TTL_SECS),
+            transactions: HashMap::new(),
+            admitted: HashMap::new(),
+            senders: HashMap::new(),
+            withdrawn: HashMap::new(),
+        }
+    }
+
+    /// Sets the amount of time for which transactions may remain in the mempool after they were admitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The lifetime of a transaction in the mempool
+    pub fn with_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.ttl = ttl;
+
+        self
+    }
+
+    /// Adds the given transaction to the back of its sender's queue. If the mempool is full, the transaction with the
+    /// highest nonce in the longest queue is evicted to make room for it, as long as that queue would remain at least
+    /// as long as the one the transaction would join. Returns any transactions that were evicted.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be added to the mempool
+    /// * `now` - The current time, at which the transaction is admitted
+    pub fn insert(
+        &mut self,
+        tx: Transaction,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        // Don't hold the same transaction twice
+        if self.transactions.contains_key(&tx.hash) {
+            return Err(MempoolError::AlreadyKnown {
+                tx_hash: tx.hash.to_str(),
+            });
+        }
+
+        // Don't take back transactions that have been withdrawn
+        if self.withdrawn.contains_key(&tx.hash) {
+            return Err(MempoolError::Withdrawn {
+                tx_hash: tx.hash.to_str(),
+            });
+        }
+
+        // Don't accept transactions that were made too long ago to still be pending
+        if tx.transaction_data.timestamp + self.ttl < now {
+            return Err(MempoolError::Expired {
+                tx_hash: tx.hash.to_str(),
+            });
+        }
+
+        let sender = tx.transaction_data.sender;
+        let nonce = tx.transaction_data.nonce;
+
+        // Each of the sender's transactions must use a distinct nonce
+        if let Some(existing) = self
+            .senders
+            .get(&sender)
+            .and_then(|queue| queue.get(&nonce))
+        {
+            return Err(MempoolError::NonceConflict {
+                tx_hash: tx.hash.to_str(),
+                existing_tx_hash: existing.to_str(),
+                nonce,
+            });
+        }
+
+        let mut evicted = Vec::new();
+
+        // Make room for the transaction by evicting expired transactions, and then by trimming the longest queue
+        if self.transactions.len() >= self.capacity {
+            evicted = self.evict_expired(now);
+        }
+
+        if self.transactions.len() >= self.capacity {
+            let queue_len = self.senders.get(&sender).map_or(0, |queue| queue.len());
+
+            // Find the transaction at the back of the longest queue
+            let victim = self
+                .senders
+                .values()
+                .max_by_key(|queue| queue.len())
+                .filter(|queue| queue.len() > queue_len + 1)
+                .and_then(|queue| queue.values().next_back().copied());
+
+            if let Some(victim) = victim.and_then(|victim| self.remove(&victim)) {
+                evicted.push(victim);
+            } else {
+                return Err(MempoolError::Full {
+                    capacity: self.capacity,
+                });
+            }
+        }
+
+        // Add the transaction to its sender's queue
+        self.senders
+            .entry(sender)
+            .or_insert_with(BTreeMap::new)
+            .insert(nonce, tx.hash);
+        self.admitted.insert(tx.hash, now);
+        self.transactions.insert(tx.hash, tx);
+
+        Ok(evicted)
+    }
+
+    /// Removes the transaction with the given hash from the mempool, and returns it.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the transaction that should be removed
+    pub fn remove(&mut self, hash: &Hash) -> Option<Transaction> {
+        let tx = self.transactions.remove(hash)?;
+        let sender = tx.transaction_data.sender;
+        self.admitted.remove(hash);
+
+        // Remove the transaction from its sender's queue, and forget about the sender if it has nothing else queued
+        if let Some(queue) = self.senders.get_mut(&sender) {
+            queue.remove(&tx.transaction_data.nonce);
+
+            if queue.is_empty() {
+                self.senders.remove(&sender);
+            }
+        }
+
+        Some(tx)
+    }
+
+    /// Replaces the sender's pending transaction that uses the same nonce as the given transaction with it, and
+    /// returns the replaced transaction, which won't be admitted again. Only newer transactions may replace pending
+    /// ones, so every node settles on the same replacement, regardless of the order in which they arrive.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should replace the pending transaction
+    /// * `now` - The current time
+    pub fn replace(
+        &mut self,
+        tx: Transaction,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Transaction, MempoolError> {
+        let existing = self
+            .senders
+            .get(&tx.transaction_data.sender)
+            .and_then(|queue| queue.get(&tx.transaction_data.nonce))
+            .and_then(|hash| self.transactions.get(hash))
+            .ok_or_else(|| MempoolError::NothingToReplace {
+                tx_hash: tx.hash.to_str(),
+            })?;
+
+        if tx.hash == existing.hash {
+            return Err(MempoolError::AlreadyKnown {
+                tx_hash: tx.hash.to_str(),
+            });
+        }
+
+        if tx.transaction_data.timestamp <= existing.transaction_data.timestamp {
+            return Err(MempoolError::StaleReplacement {
+                tx_hash: tx.hash.to_str(),
+                existing_tx_hash: existing.hash.to_str(),
+            });
+        }
+
+        let existing_hash = existing.hash;
+
+        // Withdraw the pending transaction, and take its place. If the replacement can't be admitted, the pending
+        // transaction stays withdrawn, since its sender no longer wants it.
+        let replaced =
+            self.withdraw(&existing_hash, now)
+                .ok_or_else(|| MempoolError::NothingToReplace {
+                    tx_hash: tx.hash.to_str(),
+                })?;
+        self.insert(tx, now)?;
+
+        Ok(replaced)
+    }
+
+    /// Removes the transaction with the given hash from the mempool, and makes sure that it isn't admitted again.
+    /// Returns the withdrawn transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the transaction that should be withdrawn
+    /// * `now` - The current time
+    pub fn withdraw(
+        &mut self,
+        hash: &Hash,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<Transaction> {
+        let tx = self.remove(hash)?;
+        self.withdrawn.insert(*hash, now);
+
+        Some(tx)
+    }
+
+    /// Checks whether or not the transaction with the given hash has been replaced or cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the transaction
+    pub fn is_withdrawn(&self, hash: &Hash) -> bool {
+        self.withdrawn.contains_key(hash)
+    }
+
+    /// Removes each of the transactions matching the given predicate from the mempool, and returns them.
+    ///
+    /// # Arguments
+    ///
+    /// * `should_evict` - Whether or not a transaction should be evicted
+    pub fn evict<F: Fn(&Transaction) -> bool>(&mut self, should_evict: F) -> Vec<Transaction> {
+        let evicted: Vec<Hash> = self
+            .transactions
+            .values()
+            .filter(|tx| should_evict(tx))
+            .map(|tx| tx.hash)
+            .collect();
+
+        evicted
+            .iter()
+            .filter_map(|hash| self.remove(hash))
+            .collect()
+    }
+
+    /// Removes each of the transactions that have outlived their time in the mempool, and returns them. Withdrawn
+    /// transactions are forgotten once they have been withdrawn for as long.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    pub fn evict_expired(&mut self, now: chrono::DateTime<chrono::Utc>) -> Vec<Transaction> {
+        let ttl = self.ttl;
+
+        self.withdrawn
+            .retain(|_, withdrawn_at| *withdrawn_at + ttl >= now);
+
+        let expired: Vec<Hash> = self
+            .transactions
+            .keys()
+            .filter(|hash| self.is_expired(hash, now))
+            .copied()
+            .collect();
+
+        expired
+            .iter()
+            .filter_map(|hash| self.remove(hash))
+            .collect()
+    }
+
+    /// Checks whether or not the transaction with the given hash has outlived its time in the mempool.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the transaction that should be checked
+    /// * `now` - The current time
+    pub fn is_expired(&self, hash: &Hash, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.admitted
+            .get(hash)
+            .map_or(false, |admitted_at| *admitted_at + self.ttl < now)
+    }
+
+    /// Gets the transaction with the given hash, if it is in the mempool.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the transaction
+    pub fn get(&self, hash: &Hash) -> Option<&Transaction> {
+        self.transactions.get(hash)
+    }
+
+    /// Checks whether or not the transaction with the given hash is in the mempool.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the transaction
+    pub fn contains(&self, hash: &Hash) -> bool {
+        self.transactions.contains_key(hash)
+    }
+
+    /// Gets each of the given sender's transactions, in order of their nonces.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - The sender of the transactions
+    pub fn transactions_from(&self, sender: &Address) -> Vec<&Transaction> {
+        self.senders.get(sender).map_or_else(Vec::new, |queue| {
+            queue
+                .values()
+                .filter_map(|hash| self.transactions.get(hash))
+                .collect()
+        })
+    }
+
+    /// Gets the given sender's transaction with the highest nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - The sender of the transaction
+    pub fn last_from(&self, sender: &Address) -> Option<&Transaction> {
+        self.senders
+            .get(sender)
+            .and_then(|queue| queue.values().next_back())
+            .and_then(|hash| self.transactions.get(hash))
+    }
+
+    /// Gets the hashes of each of the transactions in the mempool, ordered by sender and nonce.
+    pub fn hashes(&self) -> Vec<Hash> {
+        let mut senders: Vec<&Address> = self.senders.keys().collect();
+        senders.sort_by_key(|sender| sender.to_str());
+
+        senders
+            .into_iter()
+            .flat_map(|sender| self.senders[sender].values().copied())
+            .collect()
+    }
+
+    /// Gets the number of transactions in the mempool.
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Checks whether or not the mempool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Persists each of the transactions in the mempool to the disk, along with the times at which they were admitted,
+    /// and the transactions that have been withdrawn from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - The data directory in which the mempool should be stored
+    /// * `network_name` - The name of the network that the transactions belong to
+    pub fn write_to_disk_at_data_directory(
+        &self,
+        data_dir: &str,
+        network_name: &str,
+    ) -> io::Result<()> {
+        // Store the transactions in order, so that each sender's queue can be rebuilt in the same order
+        let transactions: Vec<&Transaction> = self
+            .hashes()
+            .iter()
+            .filter_map(|hash| self.transactions.get(hash))
+            .collect();
+
+        write_alongside_mempool(data_dir, "network", network_name, &transactions)?;
+
+        // Keep transactions from outliving their time in the mempool by being persisted
+        let admitted: Vec<(&Hash, &chrono::DateTime<chrono::Utc>)> = self.admitted.iter().collect();
+        write_alongside_mempool(data_dir, "admitted", network_name, &admitted)?;
+
+        // Keep withdrawn transactions from being admitted again after a restart
+        let withdrawn: Vec<(&Hash, &chrono::DateTime<chrono::Utc>)> =
+            self.withdrawn.iter().collect();
+
+        write_alongside_mempool(data_dir, "withdrawn", network_name, &withdrawn)
+    }
+
+    /// Reads a persisted mempool from the disk, dropping any transactions that no longer fit in it, that have expired
+    /// since it was persisted, or that have been withdrawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - The data directory in which the mempool is stored
+    /// * `network_name` - The name of the network that the transactions belong to
+    /// * `capacity` - The maximum number of transactions that may be held in the mempool
+    pub fn read_from_disk_at_data_directory(
+        data_dir: &str,
+        network_name: &str,
+        capacity: usize,
+    ) -> io::Result<Self> {
+        let transactions: Vec<Transaction> =
+            read_alongside_mempool(data_dir, "network", network_name)?;
+
+        // Restore the withdrawn transactions first, so that none of them are admitted again
+        let mut mempool = Self::new(capacity);
+        mempool.withdrawn = read_alongside_mempool::<Vec<(Hash, chrono::DateTime<chrono::Utc>)>>(
+            data_dir,
+            "withdrawn",
+            network_name,
+        )
+        .map(|withdrawn| withdrawn.into_iter().collect())
+        .unwrap_or_default();
+
+        let admitted: HashMap<Hash, chrono::DateTime<chrono::Utc>> =
+            read_alongside_mempool::<Vec<(Hash, chrono::DateTime<chrono::Utc>)>>(
+                data_dir,
+                "admitted",
+                network_name,
+            )
+            .map(|admitted| admitted.into_iter().collect())
+            .unwrap_or_default();
+
+        // Re-admit each of the transactions at the time at which it was first admitted
+        let now = chrono::Utc::now();
+
+        for tx in transactions {
+            let admitted_at = admitted.get(&tx.hash).copied().unwrap_or(now);
+            let _ = mempool.insert(tx, admitted_at);
+        }
+
+        Ok(mempool)
+    }
+}
+
+impl Default for Mempool {
+    /// Initializes a new, empty mempool with the default capacity.
+    fn default() -> Self {
+        Self::new(DEFAULT_MEMPOOL_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{super::common, types::transaction::TransactionBuilder};
+    use super::*; // Import names from the parent module
+
+    use num::bigint::BigUint; // Add support for large unsigned integers
+
+    /// Makes an unsigned transaction from the given sender with the given nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - The sender of the transaction
+    /// * `nonce` - The nonce of the transaction
+    /// * `value` - The number of finks sent through the transaction
+    fn tx_from(sender: Address, nonce: u64, value: u8) -> Transaction {
+        TransactionBuilder::new()
+            .nonce(nonce)
+            .sender(sender)
+            .recipient(Address::default())
+            .value(BigUint::from(value))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_insert() {
+        let now = chrono::Utc::now();
+        let alice = Address::from(&[1; 32][..]);
+        let bob = Address::from(&[2; 32][..]);
+
+        let mut mempool = Mempool::new(3); // Make a mempool that can only hold 3 transactions
+
+        // Queue transactions out of order
+        mempool.insert(tx_from(alice, 1, 1), now).unwrap();
+        mempool.insert(tx_from(alice, 0, 1), now).unwrap();
+        mempool.insert(tx_from(alice, 2, 1), now).unwrap();
+
+        // Ensure the queue is ordered by nonce
+        assert_eq!(
+            mempool
+                .last_from(&alice)
+                .map(|tx| tx.transaction_data.nonce),
+            Some(2)
+        );
+        let nonces: Vec<u64> = mempool
+            .transactions_from(&alice)
+            .iter()
+            .map(|tx| tx.transaction_data.nonce)
+            .collect();
+        assert_eq!(nonces, vec![0, 1, 2]);
+
+        // Ensure duplicates and conflicting nonces are rejected
+        let duplicate = mempool.transactions_from(&alice)[0].clone();
+        assert!(mempool.insert(duplicate, now).is_err());
+        assert!(mempool.insert(tx_from(alice, 0, 2), now).is_err());
+
+        // Ensure the back of the longest queue is evicted to make room for another sender
+        let evicted = mempool.insert(tx_from(bob, 0, 1), now).unwrap();
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(mempool.len(), 3);
+        assert_eq!(mempool.transactions_from(&alice).len(), 2);
+
+        // Ensure queues aren't trimmed in favor of an equally long queue
+        assert!(mempool.insert(tx_from(bob, 1, 1), now).is_err());
+
+        // Ensure expired transactions are rejected, and evicted
+        let later = now + chrono::Duration::seconds(DEFAULT_MEMPOOL_TTL_SECS + 1);
+        assert!(mempool.insert(tx_from(bob, 1, 1), later).is_err());
+        assert_eq!(mempool.evict_expired(later).len(), 3);
+        assert!(mempool.is_empty());
+
+        // Ensure transactions expire once they've been in the mempool for too long, whatever their timestamps
+        let postdated = TransactionBuilder::new()
+            .nonce(0)
+            .sender(alice)
+            .recipient(Address::default())
+            .timestamp(now + chrono::Duration::weeks(52))
+            .build()
+            .unwrap();
+        mempool.insert(postdated, now).unwrap();
+        assert_eq!(mempool.evict_expired(later).len(), 1);
+    }
+
+    #[test]
+    fn test_replace() {
+        let now = chrono::Utc::now();
+        let alice = Address::from(&[1; 32][..]);
+
+        let mut mempool = Mempool::default();
+
+        let original = tx_from(alice, 0, 1);
+        mempool.insert(original.clone(), now).unwrap();
+
+        // Ensure replacements must be newer than the transaction that they replace
+        let stale = TransactionBuilder::new()
+            .nonce(0)
+            .sender(alice)
+            .recipient(Address::default())
+            .value(BigUint::from(2u8))
+            .timestamp(original.transaction_data.timestamp)
+            .build()
+            .unwrap();
+        assert!(mempool.replace(stale, now).is_err());
+
+        // Ensure the newer transaction takes the original's place
+        let replacement = TransactionBuilder::new()
+            .nonce(0)
+            .sender(alice)
+            .recipient(Address::default())
+            .value(BigUint::from(2u8))
+            .timestamp(original.transaction_data.timestamp + chrono::Duration::seconds(1))
+            .build()
+            .unwrap();
+        assert_eq!(
+            mempool.replace(replacement.clone(), now).unwrap().hash,
+            original.hash
+        );
+        assert_eq!(mempool.transactions_from(&alice)[0].hash, replacement.hash);
+
+        // Ensure the original can't come back, until it has been forgotten
+        assert!(mempool.is_withdrawn(&original.hash));
+        assert!(mempool.insert(original.clone(), now).is_err());
+        mempool.evict_expired(now + chrono::Duration::seconds(DEFAULT_MEMPOOL_TTL_SECS + 1));
+        assert!(!mempool.is_withdrawn(&original.hash));
+
+        // Ensure transactions without a pending counterpart can't replace anything
+        assert!(mempool.replace(tx_from(alice, 1, 1), now).is_err());
+    }
+
+    #[test]
+    fn test_persistence() {
+        let now = chrono::Utc::now();
+        let alice = Address::from(&[1; 32][..]);
+        let data_dir = common::io::format_data_dir(".tests/mempool");
+
+        // Queue some transactions a while ago, withdraw one of them, and persist them
+        let admitted_at = now - chrono::Duration::seconds(DEFAULT_MEMPOOL_TTL_SECS / 2);
+        let mut mempool = Mempool::default();
+        mempool.insert(tx_from(alice, 1, 1), admitted_at).unwrap();
+        mempool.insert(tx_from(alice, 0, 1), admitted_at).unwrap();
+        let withdrawn = tx_from(alice, 2, 1);
+        mempool.insert(withdrawn.clone(), admitted_at).unwrap();
+        mempool.withdraw(&withdrawn.hash, now).unwrap();
+        mempool
+            .write_to_disk_at_data_directory(&data_dir, "olympia")
+            .unwrap();
+
+        // Ensure the queues are rebuilt after reading the mempool back
+        let restored = Mempool::read_from_disk_at_data_directory(&data_dir, "olympia", 1).unwrap();
+        assert_eq!(restored.len(), 1); // Ensure the capacity is respected
+        assert_eq!(
+            restored.transactions_from(&alice)[0].transaction_data.nonce,
+            0
+        );
+        assert_eq!(
+            restored.hashes(),
+            vec![mempool.transactions_from(&alice)[0].hash]
+        );
+
+        // Ensure the withdrawn transaction still can't come back
+        assert!(restored.is_withdrawn(&withdrawn.hash));
+
+        // Ensure the transactions expire when they would have if they had never been persisted
+        let mut restored = restored;
+        let expiry = admitted_at + chrono::Duration::seconds(DEFAULT_MEMPOOL_TTL_SECS + 1);
+        assert_eq!(restored.evict_expired(expiry).len(), 1);
+    }
+}
@@ -1,4 +1,11 @@
+pub mod cancellation; // Export the cancellation type
 pub mod config; // Export the config module
+pub mod delegation; // Export the vote delegation type
+pub mod governance; // Export the proposal lifecycle & delegation store types
+pub mod mempool; // Export the mempool module
+pub mod parameter; // Export the governable parameter registry
+pub mod policy; // Export the automatic voting policy types
 pub mod proposal; // Export the proposal type
 pub mod system; // Export the system type
+pub mod threshold; // Export the approval threshold types
 pub mod vote; // Export the vote type
@@ -0,0 +1,914 @@
+use num::{bigint::BigUint, Zero}; // Add support for large unsigned integers
+
+use serde::{Deserialize, Serialize}; // Import serde serialization
+
+use super::{
+    super::{
+        super::validator::Validator,
+        types::{encoding::Canonical, stamp, transaction::Transaction},
+    },
+    config::Config,
+    proposal::Operation,
+    system::{ExecutionError, System},
+    threshold::{ParameterThreshold, Share, Threshold},
+}; // Import the proposal, system & threshold types
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// The type of the value held by a governable parameter, which determines how the values in proposals targeting the
+/// parameter must be encoded.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ParameterType {
+    /// An unsigned integer of any size, in little-endian byte order
+    UnsignedInteger,
+    /// A single unsigned byte
+    Byte,
+    /// A UTF-8 encoded string
+    Text,
+    /// A transaction, in its canonical encoding
+    Transaction,
+    /// The threshold that proposals targeting a parameter must meet, in its canonical encoding
+    Threshold,
+}
+
+impl ParameterType {
+    /// Ensures that the provided value is a valid encoding of a value of this type.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The encoded value that should be checked
+    pub fn check(self, value: &[u8]) -> Result<(), String> {
+        match self {
+            // Any sequence of bytes is a valid little-endian integer
+            Self::UnsignedInteger => Ok(()),
+            Self::Byte if value.len() == 1 => Ok(()),
+            Self::Byte => Err(format!("expected exactly 1 byte, found {}", value.len())),
+            Self::Text => std::str::from_utf8(value)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Self::Transaction => Transaction::from_bytes(value)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Self::Threshold => match ParameterThreshold::decode(value) {
+                Ok(change) if change.threshold.is_valid() => Ok(()),
+                Ok(change) => Err(format!("threshold {} can never be met", change.threshold)),
+                Err(e) => Err(e.to_string()),
+            },
+        }
+    }
+
+    /// Encodes a value of this type written in a human-readable form (e.g. "10" for an unsigned integer).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The human-readable value
+    pub fn parse(self, value: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Self::UnsignedInteger => BigUint::from_str(value)
+                .map(|n| n.to_bytes_le())
+                .map_err(|e| e.to_string()),
+            Self::Byte => u8::from_str(value)
+                .map(|n| vec![n])
+                .map_err(|e| e.to_string()),
+            Self::Text => Ok(value.as_bytes().to_vec()),
+            // Transactions are proposed by publishing them
+            Self::Transaction => Err("transactions can't be written by hand".to_owned()),
+            Self::Threshold => ParameterThreshold::from_str(value).map(|change| change.encode()),
+        }
+    }
+}
+
+impl fmt::Display for ParameterType {
+    /// Formats the type according to the kind of value that it describes (e.g. unsigned integer).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::UnsignedInteger => "unsigned integer",
+                Self::Byte => "byte",
+                Self::Text => "text",
+                Self::Transaction => "transaction",
+                Self::Threshold => "threshold",
+            }
+        )
+    }
+}
+
+/// The kind of an operation, regardless of the value that it carries.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum OperationKind {
+    /// Replaces the value of a parameter
+    Amend,
+    /// Resets the value of a parameter
+    Remove,
+    /// Adds a value to a parameter
+    Append,
+}
+
+impl From<&Operation> for OperationKind {
+    /// Gets the kind of the provided operation.
+    fn from(operation: &Operation) -> Self {
+        match operation {
+            Operation::Amend { .. } => Self::Amend,
+            Operation::Remove => Self::Remove,
+            Operation::Append { .. } => Self::Append,
+        }
+    }
+}
+
+impl FromStr for OperationKind {
+    type Err = String;
+
+    /// Parses a kind of operation from its name (i.e. amend, remove, append).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "amend" => Ok(Self::Amend),
+            "remove" => Ok(Self::Remove),
+            "append" => Ok(Self::Append),
+            _ => Err(format!("unknown operation: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for OperationKind {
+    /// Formats the kind of operation in the same manner as the operation itself (i.e. amend, remove, append).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Amend => "amend",
+                Self::Remove => "remove",
+                Self::Append => "append",
+            }
+        )
+    }
+}
+
+/// A function determining whether or not a well-formed operation may be performed on a parameter, considering the
+/// current state of the system.
+pub type ValidateFn = fn(&System, &Operation) -> Result<(), ExecutionError>;
+
+/// A function performing a well-formed operation on a parameter of the system.
+pub type ApplyFn = fn(&mut System, Operation) -> Result<(), ExecutionError>;
+
+/// A function reading the current value of a parameter from the network's config, encoded as the parameter's type.
+pub type ValueFn = fn(&Config) -> Vec<u8>;
+
+/// A parameter of the system that may be modified by a proposal.
+pub struct Parameter {
+    /// The name of the parameter (e.g. "config::reward_per_gas")
+    pub name: String,
+
+    /// A short, human-readable explanation of the parameter
+    pub description: String,
+
+    /// The type of the parameter's value
+    pub param_type: ParameterType,
+
+    /// The operations that proposals may perform on the parameter
+    pub allowed_operations: Vec<OperationKind>,
+
+    /// The threshold that proposals targeting the parameter must meet, unless the network's config overrides it
+    pub threshold: Threshold,
+
+    /// Determines whether or not an operation may be performed on the parameter
+    pub validate: ValidateFn,
+
+    /// Performs an operation on the parameter
+    pub apply: ApplyFn,
+
+    /// Reads the parameter's current value, if the parameter holds a single value
+    pub value: Option<ValueFn>,
+
+    /// Whether or not changes to the parameter must be scheduled to take effect at a position in the ledger, since
+    /// they change the rules for executing transactions, or the network that nodes take part in
+    pub requires_activation: bool,
+}
+
+impl Parameter {
+    /// Initializes a new governable parameter, which proposals pass by winning votes from more than half of the
+    /// coins in circulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the parameter (e.g. "config::reward_per_gas")
+    /// * `description` - A short, human-readable explanation of the parameter
+    /// * `param_type` - The type of the parameter's value
+    /// * `allowed_operations` - The operations that proposals may perform on the parameter
+    /// * `validate` - Determines whether or not an operation may be performed on the parameter
+    /// * `apply` - Performs an operation on the parameter
+    pub fn new(
+        name: &str,
+        description: &str,
+        param_type: ParameterType,
+        allowed_operations: Vec<OperationKind>,
+        validate: ValidateFn,
+        apply: ApplyFn,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            description: description.to_owned(),
+            param_type,
+            allowed_operations,
+            threshold: Threshold::default(),
+            validate,
+            apply,
+            value: None,
+            requires_activation: false,
+        }
+    }
+
+    /// Sets the threshold that proposals targeting the parameter must meet by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The threshold
+    pub fn with_threshold(mut self, threshold: Threshold) -> Self {
+        self.threshold = threshold;
+
+        self
+    }
+
+    /// Allows the parameter's current value to be read from the network's config.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Reads the parameter's current value
+    pub fn with_value(mut self, value: ValueFn) -> Self {
+        self.value = Some(value);
+
+        self
+    }
+
+    /// Requires that changes to the parameter be scheduled to take effect at a position in the ledger, so that every
+    /// node executes each transaction under the same value, no matter when the change passed on that node.
+    pub fn with_required_activation(mut self) -> Self {
+        self.requires_activation = true;
+
+        self
+    }
+
+    /// Gets the parameter's current value under the given config, encoded as the parameter's type, if the parameter
+    /// holds a single value.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The network config
+    pub fn current_value(&self, config: &Config) -> Option<Vec<u8>> {
+        self.value.map(|value| value(config))
+    }
+
+    /// Gets the threshold that proposals targeting the parameter must meet under the given config.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The network config, which may override the parameter's default threshold
+    pub fn threshold_in(&self, config: &Config) -> Threshold {
+        config
+            .approval_thresholds
+            .get(&self.name)
+            .copied()
+            .unwrap_or(self.threshold)
+    }
+
+    /// Ensures that the provided operation is allowed on the parameter, and that any value it carries is of the
+    /// parameter's type.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation that should be checked
+    pub fn check_operation(&self, operation: &Operation) -> Result<(), ExecutionError> {
+        // The parameter must allow this kind of operation
+        if !self
+            .allowed_operations
+            .contains(&OperationKind::from(operation))
+        {
+            return Err(ExecutionError::InvalidOperation {
+                operation: operation.to_string(),
+                proposal_param: self.name.clone(),
+            });
+        }
+
+        // Check the value carried by the operation, if there is one
+        let value = match operation {
+            Operation::Amend { amended_value } => amended_value,
+            Operation::Append { value_to_append } => value_to_append,
+            Operation::Remove => return Ok(()),
+        };
+
+        self.param_type
+            .check(value)
+            .map_err(|reason| ExecutionError::InvalidParameterValue {
+                proposal_param: self.name.clone(),
+                param_type: self.param_type.to_string(),
+                reason,
+            })
+    }
+
+    /// Builds an operation of the given kind on the parameter, carrying the given human-readable value, if the
+    /// operation carries one.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of operation
+    /// * `value` - The human-readable value that an amendment or addition should carry
+    pub fn operation(&self, kind: OperationKind, value: Option<&str>) -> Result<Operation, String> {
+        let encode = || {
+            value
+                .ok_or_else(|| format!("a value is required to {} {}", kind, self.name))
+                .and_then(|value| self.param_type.parse(value))
+        };
+
+        let operation = match kind {
+            OperationKind::Amend => Operation::Amend {
+                amended_value: encode()?,
+            },
+            OperationKind::Remove => Operation::Remove,
+            OperationKind::Append => Operation::Append {
+                value_to_append: encode()?,
+            },
+        };
+
+        self.check_operation(&operation)
+            .map(|_| operation)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Describes the parameter in a serializable form.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The network config, which determines the parameter's threshold
+    pub fn descriptor(&self, config: &Config) -> ParameterDescriptor {
+        ParameterDescriptor {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            param_type: self.param_type,
+            allowed_operations: self.allowed_operations.clone(),
+            threshold: self.threshold_in(config),
+        }
+    }
+}
+
+/// A serializable description of a governable parameter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ParameterDescriptor {
+    /// The name of the parameter
+    pub name: String,
+
+    /// A short, human-readable explanation of the parameter
+    pub description: String,
+
+    /// The type of the parameter's value
+    pub param_type: ParameterType,
+
+    /// The operations that proposals may perform on the parameter
+    pub allowed_operations: Vec<OperationKind>,
+
+    /// The threshold that proposals targeting the parameter must meet
+    pub threshold: Threshold,
+}
+
+impl fmt::Display for ParameterDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}; {}; quorum {}, more than {} of {} stake): {}",
+            self.name,
+            self.param_type,
+            self.allowed_operations
+                .iter()
+                .map(|op| op.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            self.threshold.quorum,
+            self.threshold.approval,
+            self.threshold.base,
+            self.description
+        )
+    }
+}
+
+/// Each of the parameters of a system that may be modified by proposals, by name.
+pub struct ParameterRegistry {
+    /// The registered parameters
+    parameters: BTreeMap<String, Parameter>,
+}
+
+impl ParameterRegistry {
+    /// Initializes a new registry without any parameters.
+    pub fn new() -> Self {
+        Self {
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the provided parameter, returning any parameter that was previously registered under the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameter` - The parameter that should be registered
+    pub fn register(&mut self, parameter: Parameter) -> Option<Parameter> {
+        self.parameters.insert(parameter.name.clone(), parameter)
+    }
+
+    /// Gets the parameter registered with the provided name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the parameter
+    pub fn get(&self, name: &str) -> Option<&Parameter> {
+        self.parameters.get(name)
+    }
+
+    /// Describes each of the registered parameters, in order of their names.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The network config, which determines the parameters' thresholds
+    pub fn descriptors(&self, config: &Config) -> Vec<ParameterDescriptor> {
+        self.parameters
+            .values()
+            .map(|param| param.descriptor(config))
+            .collect()
+    }
+
+    /// Gets the number of registered parameters.
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    /// Checks whether or not any parameters have been registered.
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+}
+
+impl Default for ParameterRegistry {
+    /// Initializes a registry containing each of the parameters built into the system.
+    fn default() -> Self {
+        let mut registry = Self::new();
+
+        registry.register(
+            Parameter::new(
+                "config::reward_per_gas",
+                "The number of finks per unit of gas rewarded for validating a transaction",
+                ParameterType::UnsignedInteger,
+                vec![
+                    OperationKind::Amend,
+                    OperationKind::Remove,
+                    OperationKind::Append,
+                ],
+                accept,
+                apply_reward_per_gas,
+            )
+            .with_threshold(Threshold::of_issuance(Share::new(2, 3)))
+            .with_value(|config| config.reward_per_gas.to_bytes_le())
+            .with_required_activation(),
+        );
+        registry.register(
+            Parameter::new(
+                "config::network_name",
+                "The name of the network",
+                ParameterType::Text,
+                vec![
+                    OperationKind::Amend,
+                    OperationKind::Remove,
+                    OperationKind::Append,
+                ],
+                accept,
+                apply_network_name,
+            )
+            .with_threshold(Threshold::of_issuance(Share::new(2, 3)))
+            .with_value(|config| config.network_name.as_bytes().to_vec())
+            .with_required_activation(),
+        );
+        registry.register(
+            Parameter::new(
+                "config::stamp_difficulty",
+                "The number of leading zero bits that each transaction's stamp must prove",
+                ParameterType::Byte,
+                vec![
+                    OperationKind::Amend,
+                    OperationKind::Remove,
+                    OperationKind::Append,
+                ],
+                validate_stamp_difficulty,
+                apply_stamp_difficulty,
+            )
+            .with_threshold(Threshold::of_issuance(Share::new(2, 3)))
+            .with_value(|config| vec![config.stamp_difficulty])
+            .with_required_activation(),
+        );
+        registry.register(
+            Parameter::new(
+                "ledger::transactions",
+                "The transactions in the network's ledger",
+                ParameterType::Transaction,
+                vec![OperationKind::Append],
+                validate_transaction,
+                apply_transaction,
+            )
+            .with_threshold(Threshold::simple_majority(Share::new(1, 10))),
+        );
+        registry.register(
+            Parameter::new(
+                "governance::thresholds",
+                "The threshold that proposals targeting each parameter must meet",
+                ParameterType::Threshold,
+                vec![OperationKind::Amend],
+                validate_threshold,
+                apply_threshold,
+            )
+            .with_threshold(Threshold::of_issuance(Share::new(3, 4))),
+        );
+
+        registry
+    }
+}
+
+/// Allows any well-formed operation on a parameter.
+fn accept(_: &System, _: &Operation) -> Result<(), ExecutionError> {
+    Ok(())
+}
+
+/// Persists the configuration of the provided system, after one of its parameters has been changed.
+fn persist_config(system: &System) -> Result<(), ExecutionError> {
+    system
+        .config
+        .write_to_disk()
+        .map_err(|e| ExecutionError::Miscellaneous {
+            error: e.to_string(),
+        })
+}
+
+/// Performs the provided operation on the reward_per_gas config field.
+fn apply_reward_per_gas(system: &mut System, operation: Operation) -> Result<(), ExecutionError> {
+    match operation {
+        // Is updating reward_per_gas
+        Operation::Amend { amended_value } => {
+            system.config.reward_per_gas = BigUint::from_bytes_le(&amended_value)
+        }
+        // Is setting reward_per_gas to zero
+        Operation::Remove => system.config.reward_per_gas = BigUint::zero(),
+        // Is adding a value to the reward_per_gas
+        Operation::Append { value_to_append } => {
+            system.config.reward_per_gas =
+                system.config.reward_per_gas.clone() + BigUint::from_bytes_le(&value_to_append)
+        }
+    }
+
+    persist_config(system)
+}
+
+/// Performs the provided operation on the network_name config field. Signatures are bound to the chain's identifier,
+/// which is recorded before the network is first renamed, so that they stay valid under the new name.
+fn apply_network_name(system: &mut System, operation: Operation) -> Result<(), ExecutionError> {
+    system.config.chain_id = system.config.chain_id().to_owned();
+
+    match operation {
+        // Is updating network_name
+        Operation::Amend { amended_value } => {
+            system.config.network_name = String::from_utf8_lossy(&amended_value).into_owned()
+        }
+        // Is setting network_name to ""
+        Operation::Remove => system.config.network_name = "".to_owned(),
+        // Is appending a substring to the network_name
+        Operation::Append { value_to_append } => system
+            .config
+            .network_name
+            .push_str(&String::from_utf8_lossy(&value_to_append)),
+    }
+
+    persist_config(system)
+}
+
+/// Determines the stamp difficulty that the provided operation would leave the network with. Difficulties above the
+/// maximum are refused, since nodes would effectively never finish minting stamps for them.
+///
+/// # Arguments
+///
+/// * `current` - The network's current stamp difficulty
+/// * `operation` - The operation on the stamp_difficulty config field
+fn stamp_difficulty_after(current: u8, operation: &Operation) -> Result<u8, ExecutionError> {
+    let invalid = |reason: String| ExecutionError::InvalidParameterValue {
+        proposal_param: "config::stamp_difficulty".to_owned(),
+        param_type: ParameterType::Byte.to_string(),
+        reason,
+    };
+
+    let difficulty = match operation {
+        // Is updating stamp_difficulty
+        Operation::Amend { amended_value } => ParameterType::Byte
+            .check(amended_value)
+            .map(|_| amended_value[0])
+            .map_err(invalid)?,
+        // Is no longer requiring any work
+        Operation::Remove => 0,
+        // Is adding to the stamp_difficulty
+        Operation::Append { value_to_append } => ParameterType::Byte
+            .check(value_to_append)
+            .map(|_| current.saturating_add(value_to_append[0]))
+            .map_err(invalid)?,
+    };
+
+    if difficulty > stamp::MAX_STAMP_DIFFICULTY {
+        Err(invalid(format!(
+            "difficulty {} exceeds the maximum of {}",
+            difficulty,
+            stamp::MAX_STAMP_DIFFICULTY
+        )))
+    } else {
+        Ok(difficulty)
+    }
+}
+
+/// Ensures that the provided operation leaves the stamp_difficulty config field within its bounds.
+fn validate_stamp_difficulty(system: &System, operation: &Operation) -> Result<(), ExecutionError> {
+    stamp_difficulty_after(system.config.stamp_difficulty, operation).map(|_| ())
+}
+
+/// Performs the provided operation on the stamp_difficulty config field.
+fn apply_stamp_difficulty(system: &mut System, operation: Operation) -> Result<(), ExecutionError> {
+    system.config.stamp_difficulty =
+        stamp_difficulty_after(system.config.stamp_difficulty, &operation)?;
+
+    persist_config(system)
+}
+
+/// Ensures that the threshold set by the provided operation is for a registered parameter.
+fn validate_threshold(system: &System, operation: &Operation) -> Result<(), ExecutionError> {
+    if let Operation::Amend { amended_value } = operation {
+        let change = ParameterThreshold::decode(amended_value).map_err(|e| {
+            ExecutionError::InvalidParameterValue {
+                proposal_param: "governance::thresholds".to_owned(),
+                param_type: ParameterType::Threshold.to_string(),
+                reason: e.to_string(),
+            }
+        })?;
+
+        if system.parameters.get(&change.param_name).is_some() {
+            Ok(())
+        } else {
+            Err(ExecutionError::InvalidTargetProposalParam {
+                proposal_param: change.param_name,
+            })
+        }
+    } else {
+        Err(ExecutionError::InvalidOperation {
+            operation: operation.to_string(),
+            proposal_param: "governance::thresholds".to_owned(),
+        })
+    }
+}
+
+/// Sets the threshold of the parameter named by the provided operation to the threshold that it carries.
+fn apply_threshold(system: &mut System, operation: Operation) -> Result<(), ExecutionError> {
+    validate_threshold(system, &operation)?;
+
+    if let Operation::Amend { amended_value } = operation {
+        if let Ok(change) = ParameterThreshold::decode(&amended_value) {
+            system
+                .config
+                .approval_thresholds
+                .insert(change.param_name, change.threshold);
+        }
+    }
+
+    persist_config(system)
+}
+
+/// Ensures that the transaction appended by the provided operation satisfies each of the system's validation rules.
+fn validate_transaction(system: &System, operation: &Operation) -> Result<(), ExecutionError> {
+    if let Operation::Append { value_to_append } = operation {
+        // Deserialize the transaction
+        let tx = Transaction::from_bytes(value_to_append).map_err(|e| {
+            ExecutionError::Miscellaneous {
+                error: format!("Invalid transaction encoding: {}", e),
+            }
+        })?;
+
+        // Validate the transaction
+        system
+            .validator()
+            .transaction_is_valid(&tx)
+            .map_err(|e| ExecutionError::Miscellaneous {
+                error: format!("Invalid transaction: {}", e),
+            })
+    } else {
+        Err(ExecutionError::InvalidOperation {
+            operation: operation.to_string(),
+            proposal_param: "ledger::transactions".to_owned(),
+        })
+    }
+}
+
+/// Appends the transaction carried by the provided operation to the system's ledger.
+fn apply_transaction(system: &mut System, operation: Operation) -> Result<(), ExecutionError> {
+    if let Operation::Append { value_to_append } = operation {
+        // Deserialize the transaction
+        let tx = Transaction::from_bytes(&value_to_append).map_err(|e| {
+            ExecutionError::Miscellaneous {
+                error: format!("Invalid transaction encoding: {}", e),
+            }
+        })?;
+
+        system.append_transaction(tx)
+    } else {
+        // Ledger operations cannot be reverted
+        Err(ExecutionError::InvalidOperation {
+            operation: operation.to_string(),
+            proposal_param: "ledger::transactions".to_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        super::types::{payload, stamp},
+        config,
+        threshold::ThresholdBase,
+    };
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_check_operation() {
+        let registry = ParameterRegistry::default();
+
+        // The stamp difficulty is a single byte, which may be amended
+        let difficulty = registry.get("config::stamp_difficulty").unwrap();
+        assert!(difficulty
+            .check_operation(&Operation::Amend {
+                amended_value: vec![4]
+            })
+            .is_ok());
+        assert!(difficulty
+            .check_operation(&Operation::Amend {
+                amended_value: vec![4, 2]
+            })
+            .is_err());
+
+        // Network names must be valid UTF-8
+        assert!(registry
+            .get("config::network_name")
+            .unwrap()
+            .check_operation(&Operation::Append {
+                value_to_append: vec![0xff, 0xfe]
+            })
+            .is_err());
+
+        // Transactions can only ever be appended to the ledger
+        assert!(registry
+            .get("ledger::transactions")
+            .unwrap()
+            .check_operation(&Operation::Remove)
+            .is_err());
+
+        // Operations can be written by hand
+        match difficulty.operation(OperationKind::from_str("amend").unwrap(), Some("4")) {
+            Ok(Operation::Amend { amended_value }) => assert_eq!(amended_value, vec![4]),
+            _ => panic!("expected an amendment"),
+        }
+        assert!(difficulty
+            .operation(OperationKind::Amend, Some("256"))
+            .is_err());
+        assert!(difficulty.operation(OperationKind::Amend, None).is_err());
+
+        // Thresholds are written as a parameter name, a quorum, an approval share and a base
+        let thresholds = registry.get("governance::thresholds").unwrap();
+        match thresholds.operation(
+            OperationKind::Amend,
+            Some("config::reward_per_gas 1/10 1/2 participating"),
+        ) {
+            Ok(Operation::Amend { amended_value }) => assert_eq!(
+                ParameterThreshold::decode(&amended_value).unwrap(),
+                ParameterThreshold {
+                    param_name: "config::reward_per_gas".to_owned(),
+                    threshold: Threshold::simple_majority(Share::new(1, 10)),
+                }
+            ),
+            _ => panic!("expected an amendment"),
+        }
+        assert!(thresholds
+            .operation(
+                OperationKind::Amend,
+                Some("config::reward_per_gas 1/10 1/1 issuance")
+            )
+            .is_err());
+        assert!(thresholds.operation(OperationKind::Remove, None).is_err());
+
+        // Unknown parameters aren't governable
+        assert!(registry.get("config::unknown").is_none());
+    }
+
+    #[test]
+    fn test_stamp_difficulty_bounds() {
+        // The difficulty may be raised as far as the maximum, but no further
+        assert_eq!(
+            stamp_difficulty_after(
+                4,
+                &Operation::Amend {
+                    amended_value: vec![stamp::MAX_STAMP_DIFFICULTY]
+                }
+            )
+            .unwrap(),
+            stamp::MAX_STAMP_DIFFICULTY
+        );
+        assert!(stamp_difficulty_after(
+            4,
+            &Operation::Amend {
+                amended_value: vec![255]
+            }
+        )
+        .is_err());
+        assert!(stamp_difficulty_after(
+            stamp::MAX_STAMP_DIFFICULTY,
+            &Operation::Append {
+                value_to_append: vec![1]
+            }
+        )
+        .is_err());
+
+        // Empty values don't reset the difficulty
+        assert!(stamp_difficulty_after(
+            4,
+            &Operation::Amend {
+                amended_value: vec![]
+            }
+        )
+        .is_err());
+        assert_eq!(stamp_difficulty_after(4, &Operation::Remove).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_descriptors() {
+        let mut registry = ParameterRegistry::default();
+        let n_builtin = registry.len();
+
+        // Register a new parameter without touching any of the built-in ones
+        registry.register(Parameter::new(
+            "config::test",
+            "A parameter that does nothing",
+            ParameterType::Byte,
+            vec![OperationKind::Amend],
+            accept,
+            |_, _| Ok(()),
+        ));
+
+        // Override the threshold of one of the built-in parameters
+        let mut config = config::Config {
+            reward_per_gas: BigUint::from(config::DEFAULT_REWARD_PER_GAS),
+            network_name: "olympia".to_owned(),
+            chain_id: "olympia".to_owned(),
+            max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+            stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+            approval_thresholds: BTreeMap::new(),
+            history: Vec::new(),
+        };
+        config.approval_thresholds.insert(
+            "config::network_name".to_owned(),
+            Threshold::of_issuance(Share::new(9, 10)),
+        );
+
+        let descriptors = registry.descriptors(&config);
+        assert_eq!(descriptors.len(), n_builtin + 1);
+
+        // Parameters are listed in order of their names
+        let names: Vec<&str> = descriptors.iter().map(|d| d.name.as_str()).collect();
+        let mut sorted_names = names.clone();
+        sorted_names.sort();
+        assert_eq!(names, sorted_names);
+
+        assert_eq!(
+            descriptors
+                .iter()
+                .find(|d| d.name == "config::test")
+                .unwrap()
+                .allowed_operations,
+            vec![OperationKind::Amend]
+        );
+
+        // Each parameter is described with the threshold in effect under the config
+        let threshold_of = |name: &str| {
+            descriptors
+                .iter()
+                .find(|d| d.name == name)
+                .unwrap()
+                .threshold
+        };
+        assert_eq!(threshold_of("config::test"), Threshold::default());
+        assert_eq!(
+            threshold_of("config::network_name").approval,
+            Share::new(9, 10)
+        );
+        assert_eq!(
+            threshold_of("ledger::transactions").base,
+            ThresholdBase::Participating
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use num::{bigint::BigUint, Zero}; // Add support for large unsigned integers
+
+use serde::{Deserialize, Serialize}; // Import serde serialization
+
+use super::{
+    super::super::common::address::Address,
+    config::Config,
+    parameter::{Parameter, ParameterType},
+    proposal::{Operation, Proposal},
+    system::System,
+    threshold::Share,
+}; // Import the address, config, parameter, proposal, system & share types
+
+use std::{collections::BTreeMap, fmt, fs, io, io::Write};
+
+/// The manner in which a node automatically votes on the proposals targeting a parameter.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum VotingAction {
+    /// Vote in favor of every proposal
+    Yes,
+    /// Vote against every proposal
+    No,
+    /// Don't vote on any proposal
+    Abstain,
+    /// Vote in favor of the proposals that the parameter permits, and against the rest (transactions must also pass
+    /// the node's validator)
+    Validate,
+    /// Vote in favor of the proposals that the parameter permits, and that change its numeric value by at most the given
+    /// share of its current value (e.g. 1/10 for a change of at most 10%), and against the rest
+    MaxChange(Share),
+}
+
+impl Default for VotingAction {
+    /// Nodes don't vote on anything that their policies don't mention.
+    fn default() -> Self {
+        Self::Abstain
+    }
+}
+
+impl fmt::Display for VotingAction {
+    /// Formats the action as a short description of the votes that it casts (e.g. validate).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Yes => write!(f, "yes"),
+            Self::No => write!(f, "no"),
+            Self::Abstain => write!(f, "abstain"),
+            Self::Validate => write!(f, "validate"),
+            Self::MaxChange(share) => write!(f, "change by at most {}", share),
+        }
+    }
+}
+
+/// The vote that a node's policy casts on a proposal, and why.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VotingDecision {
+    /// Whether or not the vote is in favor of the proposal
+    pub in_favor: bool,
+
+    /// Why the vote was cast this way
+    pub reason: String,
+}
+
+impl VotingDecision {
+    /// Initializes a new decision.
+    ///
+    /// # Arguments
+    ///
+    /// * `in_favor` - Whether or not the vote is in favor of the proposal
+    /// * `reason` - Why the vote was cast this way
+    pub fn new(in_favor: bool, reason: String) -> Self {
+        Self { in_favor, reason }
+    }
+}
+
+/// A node-side policy determining how, and with which of the node's accounts, proposals are automatically voted on.
+/// The policy for a network is read from voting_policy_<network>.json in the config directory of the node's data
+/// directory.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct VotingPolicy {
+    /// The local accounts that vote automatically, or none if every unlocked account should
+    #[serde(default)]
+    pub voting_accounts: Option<Vec<Address>>,
+
+    /// The action taken on proposals targeting parameters that aren't listed in the policy, besides transactions,
+    /// which are validated unless the policy lists them
+    #[serde(default)]
+    pub default_action: VotingAction,
+
+    /// The action taken on proposals targeting each parameter, by name
+    #[serde(default)]
+    pub actions: BTreeMap<String, VotingAction>,
+}
+
+impl VotingPolicy {
+    /// Sets the action taken on proposals targeting the given parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `param_name` - The name of the parameter (e.g. "config::reward_per_gas")
+    /// * `action` - The action that should be taken on proposals targeting the parameter
+    pub fn with_action(mut self, param_name: &str, action: VotingAction) -> Self {
+        self.actions.insert(param_name.to_owned(), action);
+
+        self
+    }
+
+    /// Limits the accounts that vote automatically to the given accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `voting_accounts` - The addresses of the accounts that should vote
+    pub fn with_voting_accounts(mut self, voting_accounts: Vec<Address>) -> Self {
+        self.voting_accounts = Some(voting_accounts);
+
+        self
+    }
+
+    /// Gets the action taken on proposals targeting the given parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `param_name` - The name of the parameter
+    pub fn action_for(&self, param_name: &str) -> VotingAction {
+        self.actions.get(param_name).copied().unwrap_or_else(|| {
+            // Transactions can't be appended to the ledger unless somebody votes on them
+            if param_name == "ledger::transactions" {
+                VotingAction::Validate
+            } else {
+                self.default_action
+            }
+        })
+    }
+
+    /// Checks whether or not the given local account votes automatically.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account
+    pub fn includes(&self, account: &Address) -> bool {
+        self.voting_accounts
+            .as_ref()
+            .map_or(true, |accounts| accounts.contains(account))
+    }
+
+    /// Determines the vote that the policy casts on the given proposal, which has been pushed to the given system, or
+    /// none if the policy abstains from voting on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - The system that the proposal has been pushed to
+    /// * `proposal` - The proposal
+    pub fn evaluate(&self, system: &System, proposal: &Proposal) -> Option<VotingDecision> {
+        let param_name = &proposal.proposal_data.param_name;
+
+        match self.action_for(param_name) {
+            VotingAction::Yes => Some(VotingDecision::new(
+                true,
+                format!("the policy favors every change to {}", param_name),
+            )),
+            VotingAction::No => Some(VotingDecision::new(
+                false,
+                format!("the policy opposes every change to {}", param_name),
+            )),
+            VotingAction::Abstain => None,
+            VotingAction::Validate => Some(match proposal.proposed_transaction() {
+                // See if the transaction is valid or not, and why
+                Some(tx) => {
+                    let report = system.validator().first_violation_report(&tx);
+
+                    VotingDecision::new(report.is_valid(), report.to_string())
+                }
+                None => {
+                    let valid = system.validate_proposal(&proposal.proposal_id);
+
+                    VotingDecision::new(
+                        valid,
+                        format!(
+                            "the change to {} is {}",
+                            param_name,
+                            if valid { "valid" } else { "invalid" }
+                        ),
+                    )
+                }
+            }),
+            VotingAction::MaxChange(share) => {
+                // Only parameters holding a single number can be changed by some share of their values
+                let (current, proposed) = system.parameters.get(param_name).and_then(|param| {
+                    numeric_change(param, &system.config, &proposal.proposal_data.operation)
+                })?;
+
+                // Changes that the parameter doesn't permit are opposed, however small they are
+                if !system.validate_proposal(&proposal.proposal_id) {
+                    return Some(VotingDecision::new(
+                        false,
+                        format!("the change to {} is invalid", param_name),
+                    ));
+                }
+
+                let change = if proposed > current {
+                    &proposed - &current
+                } else {
+                    &current - &proposed
+                };
+                let in_favor = !share.exceeded_by(&change, &current);
+
+                Some(VotingDecision::new(
+                    in_favor,
+                    format!(
+                        "changing {} from {} to {} is {} the limit of {} of its value",
+                        param_name,
+                        current,
+                        proposed,
+                        if in_favor { "within" } else { "beyond" },
+                        share
+                    ),
+                ))
+            }
+        }
+    }
+
+    /// Persists the policy for the given network to the given data directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - The data directory in which the policy should be stored
+    /// * `network_name` - The name of the network that the policy applies to
+    pub fn write_to_disk_at_data_directory(
+        &self,
+        data_dir: &str,
+        network_name: &str,
+    ) -> io::Result<()> {
+        // Make the config directory
+        fs::create_dir_all(format!("{}/config", data_dir))?;
+
+        let mut file = fs::File::create(format!(
+            "{}/config/voting_policy_{}.json",
+            data_dir, network_name
+        ))?; // Initialize file
+
+        file.write_all(serde_json::to_vec_pretty(self)?.as_slice())?; // Serialize
+
+        Ok(()) // All good!
+    }
+
+    /// Reads the policy persisted for the given network from the given data directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - The data directory in which the policy is stored
+    /// * `network_name` - The name of the network that the policy applies to
+    pub fn read_from_disk_at_data_directory(
+        data_dir: &str,
+        network_name: &str,
+    ) -> io::Result<Self> {
+        let file = fs::File::open(format!(
+            "{}/config/voting_policy_{}.json",
+            data_dir, network_name
+        ))?; // Open policy file
+
+        Ok(serde_json::from_reader(file)?) // Return read policy
+    }
+}
+
+/// Determines the current value of the given parameter under the given config, and the value that it would hold after
+/// the given operation, if the parameter holds a single number.
+///
+/// # Arguments
+///
+/// * `param` - The parameter
+/// * `config` - The network config
+/// * `operation` - The operation that would be performed on the parameter
+fn numeric_change(
+    param: &Parameter,
+    config: &Config,
+    operation: &Operation,
+) -> Option<(BigUint, BigUint)> {
+    match param.param_type {
+        ParameterType::UnsignedInteger | ParameterType::Byte => (),
+        _ => return None,
+    }
+
+    let current = BigUint::from_bytes_le(&param.current_value(config)?);
+
+    let proposed = match operation {
+        Operation::Amend { amended_value } => BigUint::from_bytes_le(amended_value),
+        Operation::Remove => BigUint::zero(),
+        Operation::Append { value_to_append } => &current + BigUint::from_bytes_le(value_to_append),
+    };
+
+    Some((current, proposed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        config::{self, ActivationPoint},
+        parameter::OperationKind,
+        proposal::ProposalData,
+        system::tests::{remove_test_system, test_data_dir, test_system},
+    };
+    use super::*; // Import names from the parent module
+
+    /// Makes a proposal to set the reward per gas to the given value, and pushes it to the system.
+    fn reward_proposal(system: &mut System, reward_per_gas: u64) -> Proposal {
+        let operation = system
+            .parameters
+            .get("config::reward_per_gas")
+            .unwrap()
+            .operation(OperationKind::Amend, Some(&reward_per_gas.to_string()))
+            .unwrap();
+
+        let proposal = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new("config::reward_per_gas".to_owned(), operation),
+        )
+        .with_activation(ActivationPoint::Height(10));
+        system.push_proposal(proposal.clone());
+
+        proposal
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let mut system = test_system();
+        let small = reward_proposal(
+            &mut system,
+            u64::from(config::DEFAULT_REWARD_PER_GAS) * 21 / 20,
+        );
+        let large = reward_proposal(&mut system, u64::from(config::DEFAULT_REWARD_PER_GAS) * 2);
+
+        // Nodes only vote on transactions by default
+        let policy = VotingPolicy::default();
+        assert_eq!(
+            policy.action_for("ledger::transactions"),
+            VotingAction::Validate
+        );
+        assert_eq!(policy.evaluate(&system, &small), None);
+
+        // Changes within the limit are favored, and the rest opposed
+        let policy = policy.with_action(
+            "config::reward_per_gas",
+            VotingAction::MaxChange(Share::new(1, 10)),
+        );
+        assert_eq!(
+            policy.evaluate(&system, &small).map(|d| d.in_favor),
+            Some(true)
+        );
+        assert_eq!(
+            policy.evaluate(&system, &large).map(|d| d.in_favor),
+            Some(false)
+        );
+
+        // Changes within the limit are still opposed if the parameter doesn't permit them
+        let unscheduled = Proposal::new("test".to_owned(), small.proposal_data);
+        system.push_proposal(unscheduled.clone());
+        assert_eq!(
+            policy.evaluate(&system, &unscheduled).map(|d| d.in_favor),
+            Some(false)
+        );
+
+        // Limits can't be applied to parameters that don't hold numbers
+        let rename = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+        );
+        let policy = policy.with_action(
+            "config::network_name",
+            VotingAction::MaxChange(Share::new(1, 10)),
+        );
+        assert_eq!(policy.evaluate(&system, &rename), None);
+
+        let policy = policy.with_action("config::network_name", VotingAction::No);
+        assert_eq!(
+            policy.evaluate(&system, &rename).map(|d| d.in_favor),
+            Some(false)
+        );
+
+        remove_test_system(system);
+    }
+
+    #[test]
+    fn test_voting_accounts() {
+        let included = Address::new(vec![1; 32]);
+
+        // Every account votes unless the policy names some
+        assert!(VotingPolicy::default().includes(&included));
+
+        let policy = VotingPolicy::default().with_voting_accounts(vec![included]);
+        assert!(policy.includes(&included));
+        assert!(!policy.includes(&Address::new(vec![2; 32])));
+    }
+
+    #[test]
+    fn test_read_from_disk() {
+        let system = test_system();
+        let data_dir = test_data_dir(&system).to_owned();
+
+        let policy = VotingPolicy::default()
+            .with_action("config::stamp_difficulty", VotingAction::Yes)
+            .with_action(
+                "config::reward_per_gas",
+                VotingAction::MaxChange(Share::new(1, 10)),
+            );
+        policy
+            .write_to_disk_at_data_directory(&data_dir, "olympia")
+            .unwrap();
+
+        assert_eq!(
+            VotingPolicy::read_from_disk_at_data_directory(&data_dir, "olympia").unwrap(),
+            policy
+        );
+
+        // Policies written by hand may leave out anything that they don't change
+        fs::write(
+            format!("{}/config/voting_policy_olympia.json", data_dir),
+            r#"{"actions": {"config::reward_per_gas": "No"}}"#,
+        )
+        .unwrap();
+        let policy = VotingPolicy::read_from_disk_at_data_directory(&data_dir, "olympia").unwrap();
+        assert_eq!(
+            policy.action_for("config::reward_per_gas"),
+            VotingAction::No
+        );
+        assert_eq!(
+            policy.action_for("config::network_name"),
+            VotingAction::Abstain
+        );
+        assert!(policy.includes(&Address::default()));
+
+        remove_test_system(system);
+    }
+}
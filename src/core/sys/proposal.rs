@@ -1,9 +1,25 @@
-use super::super::super::crypto::{blake3, hash}; // Import the blake3 hashing module
+use ed25519_dalek; // Import the edwards25519 digital signature library
+
+use num::{bigint::BigUint, Zero}; // Add support for large unsigned integers
+
+use super::super::super::{common::address::Address, crypto::hash}; // Import the address & hash modules
+use super::super::types::{
+    encoding::Canonical,
+    signature::{Domain, Signature, SigningContext},
+    transaction::Transaction,
+}; // Import the canonical encoding, signature & transaction types
+use super::config::ActivationPoint; // Import the activation point type
 
 use serde::{Deserialize, Serialize}; // Import serde serialization
 
 use std::fmt;
 
+/// The number of seconds for which a new proposal may be voted on, by default.
+pub const DEFAULT_VOTING_PERIOD_SECS: i64 = 24 * 60 * 60;
+
+/// The maximum number of seconds for which any proposal may be voted on.
+pub const MAX_VOTING_PERIOD_SECS: i64 = 7 * 24 * 60 * 60;
+
 /// A proposal regarding a network-wide action.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Proposal {
@@ -13,8 +29,27 @@ pub struct Proposal {
     /// The body of the proposal
     pub proposal_data: ProposalData,
 
-    /// The hash of the proposal
+    /// The ID of the proposal, derived from its contents and its proposer
     pub proposal_id: hash::Hash,
+
+    /// The time at which the proposal was made
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    /// The time after which votes on the proposal are no longer accepted
+    pub voting_deadline: chrono::DateTime<chrono::Utc>,
+
+    /// The hash of the ledger node at whose state the weight of each vote on the proposal is measured
+    pub snapshot: Option<hash::Hash>,
+
+    /// The number of finks locked from the proposer's balance until the proposal is closed
+    pub deposit: BigUint,
+
+    /// The position in the ledger from which the proposed change takes effect, if it shouldn't take effect as soon
+    /// as the proposal passes
+    pub activation: Option<ActivationPoint>,
+
+    /// The signature of the proposer
+    pub signature: Option<Signature>,
 }
 
 /// The body of a proposal.
@@ -60,6 +95,13 @@ pub struct ProposalList {
     pub proposals: Vec<Proposal>,
 }
 
+/// Proposals are shipped between nodes in their canonical encoding.
+impl Canonical for Proposal {}
+
+/// The ID of a proposal is the hash of the canonical encoding of its contents, along with the public key of its
+/// proposer, so that every copy of a change that is proposed again, or by somebody else, is voted on separately.
+impl Canonical for (hash::Hash, Vec<u8>) {}
+
 /// Implement a set of proposal helper methods.
 impl Proposal {
     /// Initialize a new Proposal instance with the given parameters.
@@ -78,24 +120,217 @@ impl Proposal {
     /// let proposal = proposal::Proposal::new("test_proposal".to_owned(), proposal::ProposalData::new("reward_per_gas".to_owned(), operation)); // Initialize proposal
     /// ```
     pub fn new(proposal_name: String, proposal_data: ProposalData) -> Proposal {
+        let created_at = chrono::Utc::now(); // Get the current time
+
+        Proposal::with_voting_period(
+            proposal_name,
+            proposal_data,
+            created_at,
+            created_at + chrono::Duration::seconds(DEFAULT_VOTING_PERIOD_SECS),
+        ) // Return proposal
+    }
+
+    /// Initialize a new Proposal instance that may be voted on between the given times.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_name` - The name of the proposal
+    /// * `proposal_data` - The body of the proposal
+    /// * `created_at` - The time at which the proposal was made
+    /// * `voting_deadline` - The time after which votes on the proposal are no longer accepted
+    pub fn with_voting_period(
+        proposal_name: String,
+        proposal_data: ProposalData,
+        created_at: chrono::DateTime<chrono::Utc>,
+        voting_deadline: chrono::DateTime<chrono::Utc>,
+    ) -> Proposal {
         let mut proposal = Proposal {
             proposal_name,                                          // Set proposal name
             proposal_data,                                          // Set proposal data
             proposal_id: hash::Hash::new(vec![0; hash::HASH_SIZE]), // Set id to empty hash
+            created_at,                                             // Set creation time
+            voting_deadline,                                        // Set voting deadline
+            snapshot: None,                                         // Set no snapshot
+            deposit: BigUint::zero(),                               // Set no deposit
+            activation: None,                                       // Take effect once passed
+            signature: None,                                        // Set no signature
         }; // Initialize proposal
 
-        proposal.proposal_id = blake3::hash_slice(
-            serde_json::to_vec_pretty(&proposal.proposal_data.clone())
-                .unwrap()
-                .as_slice(),
-        ); // Set proposal id
+        proposal.proposal_id = proposal.derive_id(); // Set proposal id
 
         proposal // Return proposal
     }
 
-    /// Encode &self to a byte vector via serde_json.
+    /// Fixes the state at which the weight of each vote on the proposal is measured to that of the given ledger node.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The hash of the ledger node
+    pub fn with_snapshot(mut self, snapshot: hash::Hash) -> Self {
+        self.snapshot = Some(snapshot);
+        self.proposal_id = self.derive_id();
+
+        self
+    }
+
+    /// Locks the given number of finks from the proposer's balance until the proposal is closed. The deposit is
+    /// refunded once the proposal is closed, unless it is rejected by a large margin.
+    ///
+    /// # Arguments
+    ///
+    /// * `deposit` - The number of finks that should be locked
+    pub fn with_deposit(mut self, deposit: BigUint) -> Self {
+        self.deposit = deposit;
+        self.proposal_id = self.derive_id();
+
+        self
+    }
+
+    /// Schedules the proposed change to take effect from the given position in the ledger, rather than as soon as the
+    /// proposal passes.
+    ///
+    /// # Arguments
+    ///
+    /// * `activation` - The position in the ledger from which the change takes effect
+    pub fn with_activation(mut self, activation: ActivationPoint) -> Self {
+        self.activation = Some(activation);
+        self.proposal_id = self.derive_id();
+
+        self
+    }
+
+    /// Signs the proposal with the given keypair, attributing it to the keypair's account. The proposal must be
+    /// signed after its snapshot, deposit and activation point have been set, since they are part of its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature_keypair` - The keypair of the proposer
+    /// * `network` - The name of the network on which the proposal is made
+    pub fn sign(mut self, signature_keypair: &ed25519_dalek::Keypair, network: &str) -> Self {
+        self.signature = Some(
+            SigningContext::new(Domain::Proposal, network).sign(signature_keypair, &*self.hash()),
+        );
+        self.proposal_id = self.derive_id();
+
+        self
+    }
+
+    /// Hashes the contents of the proposal, excluding its ID and any signature.
+    pub fn hash(&self) -> hash::Hash {
+        // Copy the proposal since we need to remove the ID and signature from it to ensure validity
+        let mut to_be_hashed = self.clone();
+        to_be_hashed.proposal_id = hash::Hash::new(vec![0; hash::HASH_SIZE]);
+        to_be_hashed.signature = None;
+
+        to_be_hashed.canonical_hash()
+    }
+
+    /// Derives the ID of the proposal from its contents and the public key of its proposer.
+    pub fn derive_id(&self) -> hash::Hash {
+        let proposer = self
+            .signature
+            .as_ref()
+            .map_or_else(Vec::new, |sig| sig.public_key_bytes.clone());
+
+        (self.hash(), proposer).canonical_hash()
+    }
+
+    /// Ensures that the proposal's ID was derived from its contents and its proposer, so that a copy of the proposal
+    /// can't take the ID of another.
+    pub fn id_is_valid(&self) -> bool {
+        self.proposal_id == self.derive_id()
+    }
+
+    /// Ensures that the signature associated with the proposal is authentic.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - The name of the network on which the proposal should have been signed
+    pub fn valid(&self, network: &str) -> bool {
+        // The proposal must be invalid if it doesn't even have a signature
+        self.signature.as_ref().map_or(false, |sig| {
+            sig.verify(
+                &SigningContext::new(Domain::Proposal, network),
+                &*self.hash(),
+            )
+        })
+    }
+
+    /// Derives the address of the account that signed the proposal from its signature, without verifying it.
+    pub fn proposer_address(&self) -> Option<Address> {
+        self.signature.as_ref().and_then(|sig| sig.address().ok())
+    }
+
+    /// Derives the transaction that the proposal would append to the ledger, if it is a transaction proposal.
+    pub fn proposed_transaction(&self) -> Option<Transaction> {
+        match &self.proposal_data.operation {
+            Operation::Append { value_to_append }
+                if self.proposal_data.param_name == "ledger::transactions" =>
+            {
+                Transaction::from_bytes(value_to_append).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether or not votes on the proposal only count once they've been appended to the ledger, so that every
+    /// node tallies the same votes. Transaction proposals are how anything, votes included, reaches the ledger, so
+    /// votes on them are counted as they're gossiped between nodes.
+    pub fn is_voted_on_ledger(&self) -> bool {
+        self.proposal_data.param_name != "ledger::transactions"
+    }
+
+    /// Determines the account that the proposal can be attributed to, if any. Proposals are attributed to their
+    /// signers. Transaction proposals are attributed to the senders of the transactions that they would append, whose
+    /// signatures vouch for them, so they needn't be signed themselves; any that are must be signed by the sender.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - The name of the network on which the proposal should have been signed
+    pub fn verified_proposer(&self, network: &str) -> Option<Address> {
+        if self.proposal_data.param_name == "ledger::transactions" {
+            // Only transactions signed by their senders can be proposed
+            let sender = self
+                .proposed_transaction()
+                .filter(|tx| {
+                    tx.hash == tx.transaction_data.canonical_hash() && tx.verify_signature(network)
+                })
+                .map(|tx| tx.transaction_data.sender)?;
+
+            // Nobody but the sender may sign for the transaction
+            return match self.signature {
+                Some(_) if !self.valid(network) || self.proposer_address() != Some(sender) => None,
+                _ => Some(sender),
+            };
+        }
+
+        // Nobody can be held accountable for an unsigned proposal
+        if !self.valid(network) {
+            return None;
+        }
+
+        self.proposer_address()
+    }
+
+    /// Ensures that the proposal's voting deadline comes after its creation, but not after the maximum voting period.
+    pub fn voting_period_is_valid(&self) -> bool {
+        self.voting_deadline > self.created_at
+            && self.voting_deadline - self.created_at
+                <= chrono::Duration::seconds(MAX_VOTING_PERIOD_SECS)
+    }
+
+    /// Checks whether or not the proposal's voting deadline has passed at the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    pub fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        now >= self.voting_deadline
+    }
+
+    /// Encode &self to a byte vector via its canonical encoding.
     pub fn to_bytes(&self) -> Vec<u8> {
-        serde_json::to_vec_pretty(self).unwrap() // Return serialized
+        self.encode() // Return serialized
     }
 }
 
@@ -131,3 +366,137 @@ impl ProposalList {
         serde_json::to_vec_pretty(self).unwrap() // Return serialized
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voting_period() {
+        let data = ProposalData::new("config::network_name".to_owned(), Operation::Remove);
+        let created_at = chrono::Utc::now();
+
+        // New proposals can be voted on for the default period
+        let proposal = Proposal::new("test".to_owned(), data.clone());
+        assert!(proposal.voting_period_is_valid());
+        assert!(!proposal.is_expired(proposal.created_at));
+        assert!(proposal.is_expired(
+            proposal.created_at + chrono::Duration::seconds(DEFAULT_VOTING_PERIOD_SECS)
+        ));
+
+        // Proposals can't close before they open, or stay open for longer than the maximum period
+        assert!(!Proposal::with_voting_period(
+            "test".to_owned(),
+            data.clone(),
+            created_at,
+            created_at
+        )
+        .voting_period_is_valid());
+        assert!(!Proposal::with_voting_period(
+            "test".to_owned(),
+            data,
+            created_at,
+            created_at + chrono::Duration::seconds(MAX_VOTING_PERIOD_SECS + 1)
+        )
+        .voting_period_is_valid());
+    }
+
+    #[test]
+    fn test_sign() {
+        let mut csprng = rand::rngs::OsRng {}; // Generate source of randomness
+        let keypair = ed25519_dalek::Keypair::generate(&mut csprng);
+
+        let proposal = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+        );
+
+        // Unsigned proposals can't be attributed to anyone
+        assert_eq!(proposal.verified_proposer("olympia"), None);
+
+        let signed = proposal
+            .with_deposit(BigUint::from(10u64))
+            .sign(&keypair, "olympia");
+        assert_eq!(
+            signed.verified_proposer("olympia"),
+            Some(Address::from_key_pair(&keypair))
+        );
+        assert_eq!(signed.verified_proposer("andromeda"), None);
+
+        // The deposit can't be changed once the proposal has been signed
+        assert_eq!(
+            signed
+                .clone()
+                .with_deposit(BigUint::zero())
+                .verified_proposer("olympia"),
+            None
+        );
+
+        // Signatures survive a round trip
+        assert!(Proposal::decode(&signed.encode()).unwrap().valid("olympia"));
+    }
+
+    #[test]
+    fn test_proposal_id() {
+        let mut csprng = rand::rngs::OsRng {}; // Generate source of randomness
+        let (alice, bob) = (
+            ed25519_dalek::Keypair::generate(&mut csprng),
+            ed25519_dalek::Keypair::generate(&mut csprng),
+        );
+
+        let proposal = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+        );
+        let signed = proposal
+            .clone()
+            .with_deposit(BigUint::from(10u64))
+            .sign(&alice, "olympia");
+        assert!(signed.id_is_valid());
+
+        // Unsigned copies, and the same change made by somebody else, don't share the signed proposal's ID
+        assert_ne!(proposal.proposal_id, signed.proposal_id);
+        assert_ne!(
+            proposal
+                .clone()
+                .with_deposit(BigUint::from(10u64))
+                .sign(&bob, "olympia")
+                .proposal_id,
+            signed.proposal_id
+        );
+
+        // Copies that claim the ID of another proposal are caught
+        let mut forged = proposal.with_deposit(BigUint::zero());
+        forged.proposal_id = signed.proposal_id;
+        assert!(!forged.id_is_valid());
+    }
+
+    #[test]
+    fn test_activation() {
+        let data = ProposalData::new(
+            "config::reward_per_gas".to_owned(),
+            Operation::Amend {
+                amended_value: BigUint::from(10u64).to_bytes_le(),
+            },
+        );
+        let proposal = Proposal::new("test".to_owned(), data);
+
+        // The same change scheduled for different points is a different proposal
+        let scheduled = proposal
+            .clone()
+            .with_activation(ActivationPoint::Height(10));
+        assert_ne!(scheduled.proposal_id, proposal.proposal_id);
+        assert_ne!(
+            scheduled.proposal_id,
+            proposal
+                .clone()
+                .with_activation(ActivationPoint::Height(11))
+                .proposal_id
+        );
+
+        // Activation points survive a round trip
+        let decoded = Proposal::decode(&scheduled.encode()).unwrap();
+        assert_eq!(decoded.activation, Some(ActivationPoint::Height(10)));
+        assert_eq!(decoded.proposal_id, scheduled.proposal_id);
+    }
+}
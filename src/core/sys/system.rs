@@ -8,24 +8,60 @@ use std::{
 }; // Import collections
 
 use num::{
-    bigint::{self, BigInt, BigUint, ToBigInt},
+    bigint::{BigInt, BigUint, ToBigInt},
     Zero,
 }; // Add support for large unsigned integers
 
 use super::{
     super::{
         super::{
-            common::address::Address,
+            common::{address::Address, io},
             crypto::hash::Hash,
-            validator::{GraphBoundValidator, Validator},
+            validator::{
+                rules::{RecencyRule, ValidationRule},
+                GraphBoundValidator, Validator,
+            },
         },
-        types::{graph::Graph, transaction::Transaction},
+        types::{
+            encoding::Canonical,
+            graph::Graph,
+            payload::ContentType,
+            signature::{self, BatchVerificationError, Domain, SigningContext},
+            transaction::Transaction,
+        },
+    },
+    cancellation::Cancellation,
+    config::{self, ActivationPoint},
+    delegation::Delegation,
+    governance::{
+        self, GovernanceDecision, GovernanceStore, ProposalRecord, ProposalState, ProposalTally,
     },
-    config,
-    proposal::{Operation, Proposal},
+    mempool::{self, Mempool, MempoolError},
+    parameter::{Parameter, ParameterRegistry},
+    proposal::{Operation, Proposal, ProposalData},
+    threshold::{ParameterThreshold, Threshold},
     vote::Vote,
 }; // Import hash types
 
+/// The number of seconds between each removal of long-closed proposals from the governance store.
+pub const PROPOSAL_CLEANUP_INTERVAL_SECS: i64 = 60;
+
+/// The number of seconds for which a nonce handed out by `System::next_nonce` stays reserved while its transaction is
+/// waiting to be published.
+pub const NONCE_RESERVATION_TTL_SECS: i64 = 5 * 60;
+
+/// The maximum number of nodes by which the snapshot of a proposal received from a peer may trail the newest node in
+/// the ledger.
+pub const MAX_SNAPSHOT_DEPTH: usize = 64;
+
+/// The delegate named by each of an account's delegations for a single scope, along with the index of the ledger node
+/// that carried the delegation, in ledger order.
+type DelegationHistory = Vec<(usize, Option<Address>)>;
+
+/// The votes on a single proposal that have been appended to the ledger, along with the time of the transaction that
+/// carried each vote, in ledger order.
+type RecordedVotes = Vec<(chrono::DateTime<chrono::Utc>, Vote)>;
+
 /// An error encountered while executing a proposal.
 #[derive(Debug, Fail)]
 pub enum ExecutionError {
@@ -45,6 +81,74 @@ pub enum ExecutionError {
         operation: String,      // The operation
         proposal_param: String, // The target param
     },
+    #[fail(
+        display = "param {} must be a valid {}: {}",
+        proposal_param, param_type, reason
+    )]
+    InvalidParameterValue {
+        proposal_param: String, // The target param
+        param_type: String,     // The type of the target param
+        reason: String,         // Why the value isn't of the param's type
+    },
+    #[fail(display = "transaction {} is not pending", tx_hash)]
+    TransactionNotPending {
+        tx_hash: String, // The hash of the transaction
+    },
+    #[fail(
+        display = "proposal {} has been superseded by its sender, and can no longer be voted on",
+        proposal_id
+    )]
+    ProposalSuperseded {
+        proposal_id: String, // The hash of the superseded proposal
+    },
+    #[fail(
+        display = "proposal {} is {}, and can no longer be voted on",
+        proposal_id, state
+    )]
+    ProposalClosed {
+        proposal_id: String, // The hash of the closed proposal
+        state: String,       // The state of the closed proposal
+    },
+    #[fail(
+        display = "proposer {} can't lock a deposit of {} finks for proposal {}, since only {} finks are available",
+        proposer, deposit, proposal_id, available
+    )]
+    InsufficientBalanceForDeposit {
+        proposal_id: String, // The hash of the proposal
+        proposer: String,    // The address of the proposer
+        deposit: String,     // The number of finks that the proposal would lock
+        available: String,   // The number of finks that the proposer could still lock
+    },
+    #[fail(
+        display = "votes on proposal {} can't be weighed: {}",
+        proposal_id, reason
+    )]
+    InvalidSnapshot {
+        proposal_id: String, // The hash of the proposal
+        reason: String,      // Why the proposal's snapshot can't be used
+    },
+    #[fail(
+        display = "changes to param {} must be scheduled to take effect at a position in the ledger",
+        proposal_param
+    )]
+    ActivationRequired {
+        proposal_param: String, // The target param
+    },
+    #[fail(
+        display = "changes to param {} can't take effect at {}, which the ledger has already reached",
+        proposal_param, activation
+    )]
+    ActivationPassed {
+        proposal_param: String, // The target param
+        activation: String,     // The activation point that the ledger has reached
+    },
+    #[fail(
+        display = "votes on proposal {} only count once they've been appended to the ledger",
+        proposal_id
+    )]
+    VoteNotOnLedger {
+        proposal_id: String, // The hash of the proposal
+    },
     #[fail(display = "{}", error)]
     Miscellaneous {
         error: String, // The error lol
@@ -61,26 +165,90 @@ impl From<sled::Error> for ExecutionError {
     }
 }
 
+/// A nonce handed out to a sender for a transaction that hasn't been published yet.
+struct NonceReservation {
+    /// The reserved nonce
+    nonce: u64,
+
+    /// The hash of the transaction that was built with the nonce
+    tx_hash: Hash,
+
+    /// The time at which the nonce was reserved
+    reserved_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// System is a virtual proposal execution machine.
 pub struct System {
     /// The system configuration
     pub config: config::Config,
 
+    /// The parameters of the system that may be modified by proposals
+    pub parameters: ParameterRegistry,
+
     /// Known pending proposals
     pub pending_proposals: HashMap<Hash, Proposal>,
 
+    /// The proposals that have passed, but whose changes won't take effect until the ledger reaches their activation
+    /// points
+    pub scheduled_proposals: HashMap<Hash, Proposal>,
+
     /// The set of proposals that have been registered, but not published
     pub localized_proposals: HashMap<Hash, Proposal>,
 
+    /// The cancellations that have been registered, but not published
+    pub localized_cancellations: Vec<Cancellation>,
+
+    /// The votes that have been cast through this node, but not published
+    pub localized_votes: Vec<Vote>,
+
     /// The ledger
     pub ledger: Graph,
 
+    /// Transactions that have been published locally, but that cannot be proposed until their time lock or
+    /// dependency has been satisfied
+    pub held_transactions: HashMap<Hash, Transaction>,
+
+    /// Each of the pending transactions known to the system, whether proposed or held
+    pub mempool: Mempool,
+
+    /// The directory in which the system's data is stored
+    data_dir: String,
+
+    /// The last nonce handed out to each sender for a transaction that hasn't been published yet
+    nonce_reservations: HashMap<Address, NonceReservation>,
+
+    /// The proposals for transactions that have been replaced or cancelled by their senders, and the times at which
+    /// they were superseded
+    superseded_proposals: HashMap<Hash, chrono::DateTime<chrono::Utc>>,
+
+    /// The records of each of the proposals known to the system, including those that have been closed
+    governance_store: GovernanceStore,
+
+    /// The last time at which the records of long-closed proposals were removed from the governance store
+    last_proposal_cleanup: chrono::DateTime<chrono::Utc>,
+
+    /// The delegations that each account has appended to the ledger for each parameter (or for every parameter)
+    delegations: HashMap<Address, HashMap<Option<String>, DelegationHistory>>,
+
+    /// The total deposit burned from each account by proposals that were rejected by a large margin
+    burned_deposits: HashMap<Address, BigUint>,
+
+    /// The votes on each proposal that have been appended to the ledger
+    recorded_votes: HashMap<Hash, RecordedVotes>,
+
     /// The total weight of each proposed vote
     votes: HashMap<Hash, BigInt>,
 
+    /// The weight counted toward each proposal's tally from each address, whether it voted itself or through a
+    /// delegate
+    weights: HashMap<Hash, HashMap<Address, BigInt>>,
+
     /// Whether or not a user has voted for a particular proposal
     voted: HashMap<Hash, HashMap<Address, bool>>,
 
+    /// The votes cast through this node on each open proposal
+    own_votes: HashMap<Hash, Vec<Vote>>,
+
     /// Whether or not new proposals have been added to the system
     new_tx_ctx: Arc<AtomicBool>,
 }
@@ -92,15 +260,40 @@ impl System {
         // Copy the network name, since we'll have to move the configuration into the system
         let network_name = &config.network_name.clone();
 
-        System {
+        let mut system = System {
             config,                                              // Set config
+            parameters: ParameterRegistry::default(), // Set the governable parameters to the built-in ones
             pending_proposals: HashMap::new(), // set pending proposals to empty initialized hash map
+            scheduled_proposals: HashMap::new(), // a set of proposals that have passed, but haven't taken effect yet
             localized_proposals: HashMap::new(), // a set of proposals that have been registered, but not yet published
+            localized_cancellations: Vec::new(), // a set of cancellations that have been registered, but not yet published
+            localized_votes: Vec::new(), // a set of votes that have been cast, but not yet published
             ledger: Graph::read_partial_from_disk(network_name), // Set ledger
+            held_transactions: HashMap::new(), // Set held transactions to an empty map
+            mempool: System::read_mempool(&io::data_dir(), network_name),
+            data_dir: io::data_dir(),
+            nonce_reservations: HashMap::new(),
+            superseded_proposals: System::read_superseded_proposals(&io::data_dir(), network_name),
+            governance_store: System::open_governance_store(&io::data_dir(), network_name),
+            last_proposal_cleanup: chrono::Utc::now(),
             new_tx_ctx: Arc::new(AtomicBool::new(false)),
+            delegations: HashMap::new(),
+            burned_deposits: HashMap::new(),
+            recorded_votes: HashMap::new(),
             voted: HashMap::new(),
+            own_votes: HashMap::new(),
             votes: HashMap::new(),
-        } // Return initialized system
+            weights: HashMap::new(),
+        }; // Initialize system
+
+        system.restore_delegations(); // Load the delegations made so far
+        system.restore_recorded_votes(); // Load the votes appended to the ledger so far
+        system.restore_burned_deposits(); // Load the deposits burned so far
+        system.restore_open_proposals(); // Resume voting on any proposals left open by the last run
+        system.restore_scheduled_proposals(); // Resume waiting on any changes scheduled by the last run
+        system.propose_restored_transactions(); // Propose any transactions left over from the last run
+
+        system // Return initialized system
     }
 
     /// Initialize a new proposal execution system with the given data directory.
@@ -108,338 +301,3085 @@ impl System {
         // Copy the network name, sine we'll have to move the configuration into the system
         let network_name = &config.network_name.clone();
 
-        System {
+        let mut system = System {
             config,
+            parameters: ParameterRegistry::default(),
             pending_proposals: HashMap::new(),
+            scheduled_proposals: HashMap::new(),
             localized_proposals: HashMap::new(),
+            localized_cancellations: Vec::new(),
+            localized_votes: Vec::new(),
             ledger: Graph::read_partial_from_disk_with_data_dir(data_dir, network_name),
+            held_transactions: HashMap::new(),
+            mempool: System::read_mempool(data_dir, network_name),
+            data_dir: data_dir.to_owned(),
+            nonce_reservations: HashMap::new(),
+            superseded_proposals: System::read_superseded_proposals(data_dir, network_name),
+            governance_store: System::open_governance_store(data_dir, network_name),
+            last_proposal_cleanup: chrono::Utc::now(),
             new_tx_ctx: Arc::new(AtomicBool::new(false)),
+            delegations: HashMap::new(),
+            burned_deposits: HashMap::new(),
+            recorded_votes: HashMap::new(),
             voted: HashMap::new(),
+            own_votes: HashMap::new(),
             votes: HashMap::new(),
-        }
-    }
+            weights: HashMap::new(),
+        };
 
-    /// Add a given proposal to the system's localized proposals list.
-    pub fn register_proposal(&mut self, proposal: Proposal) {
-        // Check proposal not already registered
-        self.localized_proposals
-            .entry(proposal.proposal_id)
-            .or_insert(proposal); // Add proposal
+        system.restore_delegations();
+        system.restore_recorded_votes();
+        system.restore_burned_deposits();
+        system.restore_open_proposals();
+        system.restore_scheduled_proposals();
+        system.propose_restored_transactions();
 
-        // Make sure that we're going to broadcast the new proposals
-        self.new_tx_ctx.store(true, Ordering::SeqCst);
+        system
     }
 
-    /// Add a given proposal to the system's pending proposals list without alerting any listeners to this change.
-    pub fn push_proposal(&mut self, proposal: Proposal) {
-        // Register the proposal without altering the new_tx context
-        self.pending_proposals
-            .insert(proposal.proposal_id, proposal);
+    /// Reads the mempool persisted for the given network, or makes an empty one if none was persisted.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - The directory in which the mempool is stored
+    /// * `network_name` - The name of the network that the mempool belongs to
+    fn read_mempool(data_dir: &str, network_name: &str) -> Mempool {
+        Mempool::read_from_disk_at_data_directory(
+            data_dir,
+            network_name,
+            mempool::DEFAULT_MEMPOOL_CAPACITY,
+        )
+        .unwrap_or_default()
     }
 
-    /// Clears the list of localized proposals contained inside the system.
-    pub fn clear_localized_proposals(&mut self) {
-        // Reset both state measures of localized prop.
-        self.localized_proposals.clear();
-        self.new_tx_ctx.store(false, Ordering::SeqCst);
+    /// Persists the system's mempool to the disk, so that pending transactions survive a restart.
+    fn persist_mempool(&self) {
+        if let Err(e) = self
+            .mempool
+            .write_to_disk_at_data_directory(&self.data_dir, &self.config.network_name)
+        {
+            warn!("Failed to persist the mempool: {}", e);
+        }
     }
 
-    /// Move a proposal from the system's set of localized proposals to its general proposals.
+    /// Reads the proposals for superseded transactions persisted for the given network alongside its mempool.
     ///
     /// # Arguments
     ///
-    /// * `proposal_id` - The hash of the proposal that should be proposed
-    pub fn propose_proposal(&mut self, proposal_id: &Hash) -> Result<(), ExecutionError> {
-        // Ensure that the proposal exists. Otherwise, return a suitable error
-        if let Some(prop) = self.localized_proposals.remove(proposal_id) {
-            self.pending_proposals.insert(*proposal_id, prop);
+    /// * `data_dir` - The directory in which the mempool is stored
+    /// * `network_name` - The name of the network that the proposals belong to
+    fn read_superseded_proposals(
+        data_dir: &str,
+        network_name: &str,
+    ) -> HashMap<Hash, chrono::DateTime<chrono::Utc>> {
+        mempool::read_alongside_mempool::<Vec<(Hash, chrono::DateTime<chrono::Utc>)>>(
+            data_dir,
+            "superseded",
+            network_name,
+        )
+        .map(|superseded| superseded.into_iter().collect())
+        .unwrap_or_default()
+    }
 
-            Ok(())
-        } else {
-            Err(ExecutionError::ProposalDoesNotExist {
-                proposal_id: proposal_id.to_str(),
-            })
+    /// Persists the proposals for superseded transactions to the disk alongside the mempool, so that they aren't
+    /// voted for again after a restart.
+    fn persist_superseded_proposals(&self) {
+        let superseded: Vec<(&Hash, &chrono::DateTime<chrono::Utc>)> =
+            self.superseded_proposals.iter().collect();
+
+        if let Err(e) = mempool::write_alongside_mempool(
+            &self.data_dir,
+            "superseded",
+            &self.config.network_name,
+            &superseded,
+        ) {
+            warn!("Failed to persist the superseded proposals: {}", e);
         }
     }
 
-    /// Gets an atomic reference to the system's current new_tx state variable.
-    pub(crate) fn get_state_ref(&self) -> Arc<AtomicBool> {
-        // Clone the system's new_tx ctx reference variable
-        self.new_tx_ctx.clone()
+    /// Persists the transactions being held until they mature to the disk alongside the mempool, so that they
+    /// survive a restart, even if they leave the mempool.
+    fn persist_held_transactions(&self) {
+        let held: Vec<&Transaction> = self.held_transactions.values().collect();
+
+        if let Err(e) = mempool::write_alongside_mempool(
+            &self.data_dir,
+            "held",
+            &self.config.network_name,
+            &held,
+        ) {
+            warn!("Failed to persist the held transactions: {}", e);
+        }
     }
 
-    /// Registers a provided vote as in favor or against a particular proposal.
+    /// Opens the governance store kept for the given network, or a temporary one if the store can't be opened.
     ///
     /// # Arguments
     ///
-    /// * `proposal_id` - The hash of the proposal that the vote is targeting
-    /// * `vote` - The vote primitive instance
-    pub fn register_vote_for_proposal(
-        &mut self,
-        proposal_id: Hash,
-        vote: &Vote,
-    ) -> Result<(), ExecutionError> {
-        // Ensure that the proposal exists in the runtime
-        if self.pending_proposals.contains_key(&proposal_id) {
-            // The vote must have a signature in order to be registered
-            let sig = if let Some(sig) = vote.clone().signature.take() {
-                sig
-            } else {
-                return Err(ExecutionError::Miscellaneous {
-                    error: "vote signature is nil".to_owned(),
-                });
-            };
-
-            // Try to get a public key from the vote's signature. If there is no public key
-            // associated with the signature, it must be invalid.
-            if let Ok(public_key) = sig.public_key() {
-                // The signature must be valid
-                if !sig.verify(&*vote.hash()) {
-                    return Err(ExecutionError::Miscellaneous {
-                        error: "vote signature is invalid".to_owned(),
-                    });
-                }
+    /// * `data_dir` - The directory in which the governance store is kept
+    /// * `network_name` - The name of the network that the proposals & delegations belong to
+    fn open_governance_store(data_dir: &str, network_name: &str) -> GovernanceStore {
+        GovernanceStore::open_at_data_directory(data_dir, network_name)
+            .or_else(|e| {
+                warn!(
+                    "Failed to open the governance store; proposals & delegations won't survive a restart: {}",
+                    e
+                );
 
-                // Get the set of users that have voted for the proposal so that we can ensure
-                // this person isn't voting twice
-                let voters = self.voted.entry(proposal_id).or_insert_with(HashMap::new);
+                GovernanceStore::temporary()
+            })
+            .expect("unable to open a temporary governance store")
+    }
 
-                // Ensure the voter is unique
-                let has_voted = voters
-                    .entry(Address::from_public_key(&public_key))
-                    .or_insert(false);
+    /// Loads each of the delegations carried by transactions in the ledger, in the order in which they were appended.
+    fn restore_delegations(&mut self) {
+        let carriers: Vec<(usize, Transaction)> = self
+            .ledger
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| {
+                node.transaction.transaction_data.payload.content_type == ContentType::Delegation
+            })
+            .map(|(position, node)| (position, node.transaction.clone()))
+            .collect();
 
-                if !*has_voted {
-                    // We've voted now
-                    *has_voted = true;
-
-                    // Calculate the weight of the vote
-                    let vote_value =
-                        if let Some(bigint) = self.determine_vote_weight(vote).to_bigint() {
-                            bigint
-                        } else {
-                            BigInt::zero()
-                        };
-
-                    *self.votes.entry(proposal_id).or_insert_with(BigInt::zero) += if vote.in_favor
-                    {
-                        vote_value
-                    } else {
-                        -vote_value
-                    };
-                }
-            } else {
-                return Err(ExecutionError::Miscellaneous {
-                    error: "vote signature public key is nil".to_owned(),
-                });
-            }
+        for (position, tx) in carriers {
+            self.record_delegation(&tx, position);
+        }
+    }
 
-            Ok(())
-        } else {
-            Err(ExecutionError::ProposalDoesNotExist {
-                proposal_id: proposal_id.to_str(),
+    /// Loads each of the votes carried by transactions in the ledger, in the order in which they were appended.
+    fn restore_recorded_votes(&mut self) {
+        let carriers: Vec<Transaction> = self
+            .ledger
+            .nodes
+            .iter()
+            .filter(|node| {
+                node.transaction.transaction_data.payload.content_type == ContentType::Vote
             })
+            .map(|node| node.transaction.clone())
+            .collect();
+
+        for tx in carriers {
+            self.record_vote(&tx);
         }
     }
 
-    /// Execute a proposal in the pending proposals set with the given hash.
-    pub fn execute_proposal(&mut self, proposal_id: Hash) -> Result<(), ExecutionError> {
-        // Try to pull the proposal out of the proposal queue. If this fails, that means that the proposal doesn't actually exist
-        let target_proposal = if let Some(prop) = self.pending_proposals.remove(&proposal_id) {
-            prop
-        } else {
-            return Err(ExecutionError::ProposalDoesNotExist {
-                proposal_id: proposal_id.to_str(),
-            }); // Return error
-        };
+    /// Loads the total deposit burned from each account from the governance store.
+    fn restore_burned_deposits(&mut self) {
+        match self.governance_store.burned_deposits() {
+            Ok(burned) => self.burned_deposits = burned,
+            Err(e) => warn!("Failed to read the governance store: {}", e),
+        }
+    }
 
-        // Handle different target system parameters
-        match target_proposal.proposal_data.param_name.as_str() {
-            // Proposal is targeting the reward_per_gas config field
-            "config::reward_per_gas" => {
-                // Handle different operations
-                match target_proposal.proposal_data.operation {
-                    // Is updating reward_per_gas
-                    Operation::Amend { amended_value } => {
-                        self.config.reward_per_gas = bigint::BigUint::from_bytes_le(&amended_value)
-                    } // Set reward_per_gas
-                    // Is setting reward_per_gas to zero
-                    Operation::Remove => {
-                        self.config.reward_per_gas = bigint::BigUint::from(0 as u16)
-                    }
-                    // Is adding a value to the reward_per_gas
-                    Operation::Append { value_to_append } => {
-                        self.config.reward_per_gas = self.config.reward_per_gas.clone()
-                            + bigint::BigUint::from_bytes_le(&value_to_append)
-                    } // Add to reward_per_gas
-                }
+    /// Resumes tallying the votes on each of the proposals that were still open when the system was last stopped.
+    fn restore_open_proposals(&mut self) {
+        let records = match self.governance_store.records() {
+            Ok(records) => records,
+            Err(e) => {
+                warn!("Failed to read the governance store: {}", e);
 
-                let operation_result = self.config.write_to_disk(); // Write config to disk
-                                                                    // Check for errors
-                if let Err(e) = operation_result {
-                    Err(ExecutionError::Miscellaneous {
-                        error: e.to_string(),
-                    })
-                } else {
-                    Ok(()) // Mhm
-                }
+                return;
             }
-            // Proposal is targeting the network_name config field
-            "config::network_name" => {
-                // Handle different operations
-                match target_proposal.proposal_data.operation {
-                    // Is updating network_name
-                    Operation::Amend { amended_value } => {
-                        self.config.network_name =
-                            String::from_utf8_lossy(&amended_value).into_owned()
-                    } // Set network_name
-                    // Is setting network_name to ""
-                    Operation::Remove => self.config.network_name = "".to_owned(), // Set network_name to empty string
-                    // Is appending a substring to the network_name
-                    Operation::Append { value_to_append } => {
-                        self.config.network_name = format!(
-                            "{}{}",
-                            self.config.network_name,
-                            String::from_utf8_lossy(&value_to_append).into_owned()
-                        )
-                    } // Append to network_name
-                }
+        };
 
-                let operation_result = self.config.write_to_disk(); // Write config to disk
-                if let Err(e) = operation_result {
-                    // Check for errors
-                    Err(ExecutionError::Miscellaneous {
-                        error: e.to_string(),
-                    }) // Return error
-                } else {
-                    Ok(()) // Mhm
-                }
-            }
-            // Proposal is targeting the ledger
-            "ledger::transactions" => {
-                // Handle different operations
-                match target_proposal.proposal_data.operation {
-                    // Targeted amend, despite the fact that ledger operations cannot be reverted
-                    Operation::Amend { .. } => Err(ExecutionError::InvalidOperation {
-                        operation: "amend".to_owned(),
-                        proposal_param: "ledger::transactions".to_owned(),
-                    }),
-                    // Targeted remove, despite the fact that ledger operations cannot be reverted
-                    Operation::Remove => Err(ExecutionError::InvalidOperation {
-                        operation: "remove".to_owned(),
-                        proposal_param: "ledger::transactions".to_owned(),
-                    }),
-                    // Is appending a transaction to the network ledger
-                    Operation::Append { value_to_append } => {
-                        let tx = Transaction::from_bytes(&value_to_append); // Deserialize transaction
-
-                        // Get the index of the submitted transaction entry
-                        let entry_index = self.ledger.push(tx.clone(), None);
-
-                        // Execute the parent transactions, get the overall hash
-                        let parent_tx_state = self.ledger.execute_parent_nodes(entry_index)?;
-
-                        // Get the hash of the parent state that the transaction THINKS is right
-                        let asserted_parent_state_hash = if let Some(parent_state_hash) =
-                            tx.transaction_data.parent_state_hash
-                        {
-                            parent_state_hash
-                        } else {
-                            // Remove the head tx, since it's invalid
-                            self.ledger.rollback_head();
-
-                            // Return the error
-                            return Err(ExecutionError::Miscellaneous {
-                                error: "Invalid transaction: must have parent state hash."
-                                    .to_owned(),
-                            });
-                        };
-
-                        // UWU WHAT'S THIS I SEE?
-                        if parent_tx_state.hash != asserted_parent_state_hash {
-                            // Remove the head tx, since it's invalid
-                            self.ledger.rollback_head();
-
-                            // Return the error
-                            return Err(ExecutionError::Miscellaneous{error: format!("Invalid transaction: merged parent states must have a hash matching that which is asserted by the transaction (found {}, tx asserted {}).", parent_tx_state.hash, asserted_parent_state_hash)});
-                        };
-
-                        //if let Ok(prev_state_entry) = self
-                        //    .ledger
-                        //    .execute_parent_nodes(self.ledger.nodes.len() - 1)
-                        //{
-                        //    let index = self.ledger.nodes.len() - 1; // Get index of pushed tx
-
-                        // Get previous state entry
-                        //self.ledger.nodes[index].state_entry =
-                        //Some(tx.execute(Some(prev_state_entry))); // Set node state entry
-                        //}
-
-                        Ok(())
-                    }
-                }
+        for record in records.into_iter().filter(|record| record.state.is_open()) {
+            let proposal_id = record.proposal.proposal_id;
+
+            self.votes.insert(proposal_id, record.tally);
+            self.weights.insert(proposal_id, record.weights);
+            self.voted.insert(
+                proposal_id,
+                record
+                    .voters
+                    .into_iter()
+                    .map(|voter| (voter, true))
+                    .collect(),
+            );
+            self.pending_proposals.insert(proposal_id, record.proposal);
+
+            // Count any votes appended to the ledger since the system was stopped
+            self.count_recorded_votes(&proposal_id);
+        }
+
+        if !self.pending_proposals.is_empty() {
+            info!(
+                "Restored {} open proposals from the governance store",
+                self.pending_proposals.len()
+            );
+        }
+    }
+
+    /// Resumes waiting for the activation point of each of the proposals that were scheduled when the system was last
+    /// stopped, and makes the changes whose activation points the ledger has since reached.
+    fn restore_scheduled_proposals(&mut self) {
+        let records = match self.governance_store.records() {
+            Ok(records) => records,
+            Err(e) => {
+                warn!("Failed to read the governance store: {}", e);
+
+                return;
             }
-            _ => Err(ExecutionError::InvalidTargetProposalParam {
-                proposal_param: target_proposal.proposal_data.param_name,
-            }),
+        };
+
+        for record in records
+            .into_iter()
+            .filter(|record| record.state == ProposalState::Scheduled)
+        {
+            self.scheduled_proposals
+                .insert(record.proposal.proposal_id, record.proposal);
         }
+
+        self.activate_scheduled_proposals();
     }
 
-    /// Determines the number of coins associated with a particular voter.
+    /// Builds a record of the given proposal in the given state, including the votes that have been cast on it.
     ///
     /// # Arguments
     ///
-    /// * `vote` - The vote that the weight should be determined of
-    pub fn determine_vote_weight(&self, vote: &Vote) -> BigUint {
-        // Determine the address of the account submitting the vote
-        let voter = if let Some(addr) = vote.voter_address() {
-            addr
-        } else {
-            // Since the vote doesn't have a voter, the vote can't have any weight
-            return BigUint::zero();
-        };
+    /// * `proposal` - The proposal
+    /// * `state` - The stage of its lifecycle that the proposal is in
+    fn proposal_record(&self, proposal: &Proposal, state: ProposalState) -> ProposalRecord {
+        let proposal_id = &proposal.proposal_id;
 
-        // Get the balance of the voter
-        self.ledger.get_balance_of_account(&voter)
+        let mut record =
+            ProposalRecord::new(proposal.clone()).transition(state, chrono::Utc::now());
+        record.tally = self.get_coins_in_support_of(proposal_id);
+        record.voters = self
+            .voted
+            .get(proposal_id)
+            .map(|voters| voters.keys().copied().collect())
+            .unwrap_or_default();
+        record.weights = self.weights.get(proposal_id).cloned().unwrap_or_default();
+
+        record
     }
 
-    /// Determins the number of coins from voters in favor of the proposal.
+    /// Persists a record of the given proposal in the given state.
     ///
     /// # Arguments
     ///
-    /// * `proposal` - The proposal that the weight should be determined of
-    pub fn get_coins_in_support_of(&self, proposal: &Hash) -> BigInt {
-        self.votes.get(proposal).unwrap_or(&BigInt::zero()).clone()
+    /// * `proposal` - The proposal
+    /// * `state` - The stage of its lifecycle that the proposal is in
+    fn persist_proposal(&self, proposal: &Proposal, state: ProposalState) {
+        if let Err(e) = self
+            .governance_store
+            .insert(&self.proposal_record(proposal, state))
+        {
+            warn!("Failed to persist proposal {}: {}", proposal.proposal_id, e);
+        }
     }
 
-    /// Determines whether or not a particular proposal is valid.
+    /// Persists the final state of the given proposal, which has been removed from the set of pending proposals, and
+    /// stops tallying votes on it. Unless the proposal would have appended a transaction to the ledger (which keeps
+    /// its own record of transactions), the decision is also recorded in the governance history.
     ///
     /// # Arguments
     ///
-    /// * `proposal` - The proposal that should be validated
-    pub fn validate_proposal(&self, proposal: &Hash) -> bool {
-        // Get the proposal's metadata
-        if let Some(prop) = self.pending_proposals.get(proposal) {
-            // If the proposal is a transaction, validate it
-            return if prop.proposal_data.param_name.as_str() == "ledger::transactions" {
-                // The proposal must be an append op if it is proposing a TX
-                if let Operation::Append { value_to_append } = &prop.proposal_data.operation {
-                    // Try to deserialize the transaction
-                    if let Ok(tx) = bincode::deserialize::<Transaction>(&value_to_append) {
-                        // Validate the transaction
-                        let validator = GraphBoundValidator::new(&self.ledger);
-                        validator.transaction_is_valid(&tx).is_ok()
-                    } else {
-                        false
-                    }
-                } else {
-                    false
+    /// * `proposal` - The proposal
+    /// * `state` - The state in which the proposal was closed
+    fn close_proposal(&mut self, proposal: &Proposal, state: ProposalState) {
+        self.record_decision(&self.proposal_record(proposal, state));
+
+        self.votes.remove(&proposal.proposal_id);
+        self.voted.remove(&proposal.proposal_id);
+        self.weights.remove(&proposal.proposal_id);
+        self.own_votes.remove(&proposal.proposal_id);
+    }
+
+    /// Persists the given record of a closed proposal. Unless the proposal would have appended a transaction to the
+    /// ledger, the decision is also recorded in the governance history.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record of the closed proposal
+    fn record_decision(&self, record: &ProposalRecord) {
+        let proposal_id = &record.proposal.proposal_id;
+
+        if let Err(e) = self.governance_store.insert(record) {
+            warn!("Failed to persist proposal {}: {}", proposal_id, e);
+        }
+
+        if record.proposal.proposal_data.param_name != "ledger::transactions" {
+            if let Err(e) = self
+                .governance_store
+                .append_decision(&GovernanceDecision::new(record, self.config.clone()))
+            {
+                warn!(
+                    "Failed to record the decision on proposal {}: {}",
+                    proposal_id, e
+                );
+            }
+        }
+    }
+
+    /// Gets the record of the proposal with the given ID, whether it is open or has been closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The ID of the proposal
+    pub fn get_proposal_record(&self, proposal_id: &Hash) -> Option<ProposalRecord> {
+        if let Some(proposal) = self.pending_proposals.get(proposal_id) {
+            return Some(self.proposal_record(proposal, ProposalState::Open));
+        }
+
+        self.governance_store.get(proposal_id).unwrap_or_else(|e| {
+            warn!("Failed to read the governance store: {}", e);
+
+            None
+        })
+    }
+
+    /// Gets each of the decisions made on proposals to change the network's parameters, in the order in which they
+    /// were made.
+    pub fn governance_history(&self) -> Result<Vec<GovernanceDecision>, ExecutionError> {
+        Ok(self.governance_store.history()?)
+    }
+
+    /// Gets the stage of its lifecycle that the proposal with the given ID is in, if the proposal is known.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The ID of the proposal
+    pub fn proposal_state(&self, proposal_id: &Hash) -> Option<ProposalState> {
+        if self.pending_proposals.contains_key(proposal_id) {
+            return Some(ProposalState::Open);
+        }
+
+        self.get_proposal_record(proposal_id)
+            .map(|record| record.state)
+    }
+
+    /// Decides each of the pending proposals whose voting deadlines have passed by the votes cast on them, closes
+    /// those that still can't be decided, and forgets proposals that were closed long ago. Returns the IDs of the
+    /// expired proposals.
+    pub fn expire_proposals(&mut self) -> Vec<Hash> {
+        let now = chrono::Utc::now();
+
+        // Collect each of the proposals that can no longer be voted on
+        let closed: Vec<Hash> = self
+            .pending_proposals
+            .values()
+            .filter(|proposal| proposal.is_expired(now))
+            .map(|proposal| proposal.proposal_id)
+            .collect();
+
+        // Votes among those participating are only decided once voting closes
+        for proposal_id in closed.iter() {
+            self.clear_proposal(proposal_id);
+        }
+
+        let expired: Vec<Hash> = closed
+            .into_iter()
+            .filter(|proposal_id| self.pending_proposals.contains_key(proposal_id))
+            .collect();
+
+        for proposal_id in expired.iter() {
+            if let Some(proposal) = self.pending_proposals.remove(proposal_id) {
+                self.forget_proposed_transaction(&proposal);
+                self.close_proposal(&proposal, ProposalState::Expired);
+            }
+        }
+
+        // Every so often, forget about proposals that were closed long ago
+        if now - self.last_proposal_cleanup
+            >= chrono::Duration::seconds(PROPOSAL_CLEANUP_INTERVAL_SECS)
+        {
+            self.last_proposal_cleanup = now;
+
+            match self.governance_store.remove_closed_before(
+                now - chrono::Duration::seconds(governance::CLOSED_PROPOSAL_RETENTION_SECS),
+            ) {
+                Ok(removed) if !removed.is_empty() => {
+                    debug!("Forgot {} long-closed proposals", removed.len())
                 }
-            } else {
-                true
-            };
+                Ok(_) => (),
+                Err(e) => warn!("Failed to clean up the governance store: {}", e),
+            }
         }
 
-        false
+        expired
+    }
+
+    /// Proposes each of the transactions restored from the persisted mempool, or from the persisted set of held
+    /// transactions, that can still be executed, and holds those that haven't matured yet.
+    fn propose_restored_transactions(&mut self) {
+        // Drop anything that was executed or that expired while the node was offline
+        self.prune_mempool();
+
+        let mut restored: Vec<Transaction> = self
+            .mempool
+            .hashes()
+            .iter()
+            .filter_map(|hash| self.mempool.get(hash).cloned())
+            .collect();
+
+        let last_nonces = self.last_nonces();
+
+        // Held transactions are restored even if they have since left the mempool, as long as they can still be
+        // executed
+        let held: Vec<Transaction> =
+            mempool::read_alongside_mempool(&self.data_dir, "held", &self.config.network_name)
+                .unwrap_or_default();
+
+        for tx in held {
+            if !self.mempool.contains(&tx.hash)
+                && !self.mempool.is_withdrawn(&tx.hash)
+                && !self.ledger.hash_routes.contains_key(&tx.hash)
+                && !last_nonces
+                    .get(&tx.transaction_data.sender.to_str())
+                    .map_or(false, |last_nonce| tx.transaction_data.nonce <= *last_nonce)
+            {
+                restored.push(tx);
+            }
+        }
+
+        if !restored.is_empty() {
+            info!("Restored {} pending transactions", restored.len());
+        }
+
+        for tx in restored {
+            self.schedule_transaction(tx, &last_nonces);
+        }
+
+        self.persist_held_transactions();
+    }
+
+    /// Fixes the state at which votes on the given proposal are weighed to that of the ledger's executed head, if the
+    /// proposal doesn't already name a snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal that should be snapshotted
+    pub(crate) fn snapshot_proposal(&self, proposal: Proposal) -> Proposal {
+        if proposal.snapshot.is_some() {
+            return proposal;
+        }
+
+        match self.ledger.obtain_executed_head() {
+            Some(head) => proposal.with_snapshot(head.hash),
+            None => proposal,
+        }
+    }
+
+    /// Add a given proposal to the system's localized proposals list.
+    pub fn register_proposal(&mut self, proposal: Proposal) {
+        let proposal = self.snapshot_proposal(proposal);
+
+        // Check proposal not already registered
+        self.localized_proposals
+            .entry(proposal.proposal_id)
+            .or_insert(proposal); // Add proposal
+
+        // Make sure that we're going to broadcast the new proposals
+        self.new_tx_ctx.store(true, Ordering::SeqCst);
+    }
+
+    /// Add a given proposal to the system's pending proposals list without alerting any listeners to this change.
+    /// The proposal is kept exactly as it was made, since its ID and signature cover its snapshot.
+    pub fn push_proposal(&mut self, proposal: Proposal) {
+        self.persist_proposal(&proposal, ProposalState::Open);
+
+        // Register the proposal without altering the new_tx context
+        let proposal_id = proposal.proposal_id;
+        self.pending_proposals.insert(proposal_id, proposal);
+
+        // Votes may have been appended to the ledger before the proposal reached this node
+        self.count_recorded_votes(&proposal_id);
+    }
+
+    /// Adds the given transaction to the mempool, and registers a proposal to append it to the ledger. If the
+    /// transaction is time-locked beyond the time of parents that haven't been accepted yet, depends on a transaction
+    /// that hasn't been accepted yet, or uses a nonce that its sender can't use yet, it is held until it matures.
+    /// Transactions that can never mature are refused.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be proposed
+    pub fn propose_transaction(&mut self, tx: Transaction) -> Result<(), MempoolError> {
+        // Transactions are proposed on the strength of their senders' signatures
+        if tx.hash != tx.transaction_data.canonical_hash()
+            || !tx.verify_signature(self.config.chain_id())
+        {
+            return Err(MempoolError::Unsigned {
+                tx_hash: tx.hash.to_str(),
+            });
+        }
+
+        // There's no point in holding a transaction that will never mature
+        if !GraphBoundValidator::new(&self.ledger, self.config.chain_id())
+            .transaction_can_mature(&tx)
+        {
+            return Err(MempoolError::NeverMatures {
+                tx_hash: tx.hash.to_str(),
+            });
+        }
+
+        self.admit_transaction(tx.clone())?;
+        self.schedule_transaction(tx, &self.last_nonces());
+
+        Ok(())
+    }
+
+    /// Adds the given transaction to the mempool without proposing it (e.g. because it was proposed by a peer),
+    /// withdrawing any transactions that it displaces. A newer transaction signed by the sender of a pending
+    /// transaction with the same nonce supersedes the pending transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be added to the mempool
+    pub fn admit_transaction(&mut self, tx: Transaction) -> Result<(), MempoolError> {
+        let now = chrono::Utc::now();
+
+        match self.mempool.insert(tx.clone(), now) {
+            Ok(evicted) => {
+                for evicted_tx in evicted.iter() {
+                    self.withdraw_transaction(evicted_tx);
+                }
+            }
+            // Only the sender may replace their own transactions
+            Err(MempoolError::NonceConflict { .. })
+                if tx.verify_signature(self.config.chain_id()) =>
+            {
+                let replaced = self.mempool.replace(tx, now)?;
+
+                info!(
+                    "Transaction {} has been replaced by its sender",
+                    replaced.hash
+                );
+
+                self.supersede_transaction(&replaced);
+            }
+            Err(e) => return Err(e),
+        }
+
+        self.persist_mempool();
+
+        Ok(())
+    }
+
+    /// Withdraws the pending transaction targeted by the given cancellation, on the condition that the cancellation
+    /// was signed by the transaction's sender. Returns the withdrawn transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancellation` - The signed cancellation
+    pub fn cancel_transaction(
+        &mut self,
+        cancellation: &Cancellation,
+    ) -> Result<Transaction, ExecutionError> {
+        if !cancellation.valid(self.config.chain_id()) {
+            return Err(ExecutionError::Miscellaneous {
+                error: "cancellation signature is invalid".to_owned(),
+            });
+        }
+
+        // Only pending transactions can be withdrawn
+        let sender = if let Some(tx) = self.mempool.get(&cancellation.target_tx) {
+            tx.transaction_data.sender
+        } else {
+            return Err(ExecutionError::TransactionNotPending {
+                tx_hash: cancellation.target_tx.to_str(),
+            });
+        };
+
+        // Only the sender may cancel their own transactions
+        if cancellation.canceller_address() != Some(sender) {
+            return Err(ExecutionError::Miscellaneous {
+                error: "cancellation wasn't signed by the transaction's sender".to_owned(),
+            });
+        }
+
+        let cancelled = self
+            .mempool
+            .withdraw(&cancellation.target_tx, chrono::Utc::now())
+            .ok_or_else(|| ExecutionError::TransactionNotPending {
+                tx_hash: cancellation.target_tx.to_str(),
+            })?;
+
+        self.supersede_transaction(&cancelled);
+        self.persist_mempool();
+
+        Ok(cancelled)
+    }
+
+    /// Withdraws the pending transaction targeted by the given cancellation, and queues the cancellation to be
+    /// published.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancellation` - The signed cancellation
+    pub fn register_cancellation(
+        &mut self,
+        cancellation: Cancellation,
+    ) -> Result<Transaction, ExecutionError> {
+        let cancelled = self.cancel_transaction(&cancellation)?;
+
+        self.localized_cancellations.push(cancellation);
+
+        // Make sure that we're going to broadcast the cancellation
+        self.new_tx_ctx.store(true, Ordering::SeqCst);
+
+        Ok(cancelled)
+    }
+
+    /// Gets the number of finks from each account's balance that can't be put toward the deposits of new proposals:
+    /// those burned from the account's deposits, and those locked as deposits for the account's open proposals.
+    /// Deposits are tracked by each node's governance state rather than by the ledger, so they only bear on
+    /// governance, and never on whether a transaction is valid.
+    pub fn locked_balances(&self) -> HashMap<Address, BigUint> {
+        let mut locked = self.burned_deposits.clone();
+
+        // Proposals that haven't been published yet lock their deposits as well
+        let unpublished = self
+            .localized_proposals
+            .values()
+            .filter(|proposal| !self.pending_proposals.contains_key(&proposal.proposal_id));
+
+        for proposal in self.pending_proposals.values().chain(unpublished) {
+            if let Some(proposer) = proposal
+                .proposer_address()
+                .filter(|_| !proposal.deposit.is_zero())
+            {
+                *locked.entry(proposer).or_insert_with(BigUint::zero) += &proposal.deposit;
+            }
+        }
+
+        locked
+    }
+
+    /// Makes a validator bound to the system's ledger and to the config in effect for the next transaction appended
+    /// to it.
+    pub fn validator(&self) -> GraphBoundValidator<'_> {
+        self.validator_at(self.ledger.nodes.len() as u64)
+    }
+
+    /// Makes a validator bound to the system's ledger and to the config in effect for the transaction at the given
+    /// position in the ledger.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The number of ledger nodes that precede the transaction
+    pub fn validator_at(&self, position: u64) -> GraphBoundValidator<'_> {
+        let epoch = self.config.epoch_at(position);
+
+        GraphBoundValidator::from_config(&self.ledger, &self.config)
+            .with_max_payload_size(epoch.max_payload_size)
+            .with_stamp_difficulty(epoch.stamp_difficulty)
+    }
+
+    /// Ensures that the proposer of the given proposal, which hasn't been registered yet, can lock the proposal's
+    /// deposit from the unlocked portion of its balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal whose deposit should be checked
+    pub fn check_deposit(&self, proposal: &Proposal) -> Result<(), ExecutionError> {
+        // Proposals that have already been registered have already locked their deposits
+        if proposal.deposit.is_zero()
+            || self.pending_proposals.contains_key(&proposal.proposal_id)
+            || self.localized_proposals.contains_key(&proposal.proposal_id)
+        {
+            return Ok(());
+        }
+
+        let proposer =
+            proposal
+                .proposer_address()
+                .ok_or_else(|| ExecutionError::Miscellaneous {
+                    error: "proposals that lock a deposit must be signed".to_owned(),
+                })?;
+
+        let balance = self.ledger.get_balance_of_account(&proposer);
+        let available = match self.locked_balances().get(&proposer) {
+            Some(locked) if *locked >= balance => BigUint::zero(),
+            Some(locked) => balance - locked,
+            None => balance,
+        };
+
+        if available >= proposal.deposit {
+            Ok(())
+        } else {
+            Err(ExecutionError::InsufficientBalanceForDeposit {
+                proposal_id: proposal.proposal_id.to_str(),
+                proposer: proposer.to_str(),
+                deposit: proposal.deposit.to_string(),
+                available: available.to_string(),
+            })
+        }
+    }
+
+    /// Ensures that the given proposal, which hasn't been registered yet, names a snapshot at which every node can
+    /// weigh its votes: a node in the ledger that trails the newest node by no more than `MAX_SNAPSHOT_DEPTH` nodes.
+    /// Older snapshots would let voters count coins that they have since moved.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal whose snapshot should be checked
+    pub fn check_snapshot(&self, proposal: &Proposal) -> Result<(), ExecutionError> {
+        // Proposals that have already been registered have already been checked
+        if self.pending_proposals.contains_key(&proposal.proposal_id)
+            || self.localized_proposals.contains_key(&proposal.proposal_id)
+        {
+            return Ok(());
+        }
+
+        let invalid = |reason: String| ExecutionError::InvalidSnapshot {
+            proposal_id: proposal.proposal_id.to_str(),
+            reason,
+        };
+
+        let snapshot = proposal
+            .snapshot
+            .ok_or_else(|| invalid("it doesn't name a snapshot".to_owned()))?;
+        let position = self
+            .ledger
+            .hash_routes
+            .get(&snapshot)
+            .ok_or_else(|| invalid(format!("snapshot {} isn't in the ledger", snapshot)))?;
+
+        if self.ledger.nodes.len() - 1 - position > MAX_SNAPSHOT_DEPTH {
+            return Err(invalid(format!(
+                "snapshot {} trails the ledger's newest node by more than {} nodes",
+                snapshot, MAX_SNAPSHOT_DEPTH
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Removes the proposal with the given ID, which has been rejected by its voters, from the set of pending
+    /// proposals. If the weight of the votes against the proposal is at least `DEPOSIT_BURN_RATIO` times that of the
+    /// votes in its favor, the proposer's deposit is burned, rather than refunded. Returns whether or not the proposal
+    /// was pending.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The hash of the rejected proposal
+    pub fn reject_proposal(&mut self, proposal_id: &Hash) -> bool {
+        let proposal = if let Some(proposal) = self.pending_proposals.get(proposal_id) {
+            proposal.clone()
+        } else {
+            return false;
+        };
+
+        // Split the weight counted toward the proposal's tally into that in favor of it, and that against it
+        let (in_favor, against) = self.weights.get(proposal_id).map_or_else(
+            || (BigInt::zero(), BigInt::zero()),
+            governance::split_weights,
+        );
+
+        if !proposal.deposit.is_zero()
+            && against > BigInt::zero()
+            && against >= in_favor * governance::DEPOSIT_BURN_RATIO
+        {
+            if let Some(proposer) = proposal.proposer_address() {
+                self.burn_deposit(&proposer, &proposal.deposit);
+            }
+        }
+
+        self.discard_proposal(proposal_id)
+    }
+
+    /// Permanently prevents the given deposit from being spent from the given account's balance, or counted toward
+    /// the weight of the account's votes.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The proposer whose deposit should be burned
+    /// * `deposit` - The number of finks that should be burned
+    fn burn_deposit(&mut self, account: &Address, deposit: &BigUint) {
+        info!("Burning a deposit of {} finks from {}", deposit, account);
+
+        let burned = self
+            .burned_deposits
+            .entry(*account)
+            .or_insert_with(BigUint::zero);
+        *burned += deposit;
+
+        if let Err(e) = self.governance_store.insert_burned_deposit(account, burned) {
+            warn!(
+                "Failed to persist the deposits burned from {}: {}",
+                account, e
+            );
+        }
+    }
+
+    /// Gets the balance of the given account at the given snapshot, less any deposits burned from the account since.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account whose balance should be measured
+    /// * `snapshot` - The hash of the ledger node at whose state the balance should be measured
+    fn voting_balance_of(&self, account: &Address, snapshot: &Hash) -> BigUint {
+        let balance = self.ledger.get_balance_of_account_at(account, snapshot);
+
+        match self.burned_deposits.get(account) {
+            Some(burned) if *burned >= balance => BigUint::zero(),
+            Some(burned) => balance - burned,
+            None => balance,
+        }
+    }
+
+    /// Records the delegation carried by the given transaction, if it carries one, as that made by the transaction's
+    /// sender for the delegation's scope from the transaction's position in the ledger onward.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that has been appended to the ledger
+    /// * `position` - The index of the transaction's node in the ledger
+    fn record_delegation(&mut self, tx: &Transaction, position: usize) {
+        let delegation = match Delegation::from_transaction(tx, self.config.chain_id()) {
+            Some(Ok(delegation)) => delegation,
+            Some(Err(reason)) => {
+                warn!(
+                    "Ignoring the delegation carried by transaction {}: {}",
+                    tx.hash, reason
+                );
+
+                return;
+            }
+            None => return,
+        };
+
+        self.delegations
+            .entry(tx.transaction_data.sender)
+            .or_default()
+            .entry(delegation.param_name)
+            .or_default()
+            .push((position, delegation.delegate));
+    }
+
+    /// Gets the account that votes on proposals targeting the given parameter on behalf of the given delegator, if
+    /// the delegator had delegated its votes as of the given position in the ledger. A delegation for the parameter
+    /// itself takes precedence over one for every parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `delegator` - The account whose delegate should be found
+    /// * `param_name` - The name of the parameter
+    /// * `position` - The index of the ledger node as of which delegations should be considered
+    pub fn delegate_of(
+        &self,
+        delegator: &Address,
+        param_name: &str,
+        position: usize,
+    ) -> Option<Address> {
+        let scopes = self.delegations.get(delegator)?;
+
+        // Find the last delegation for the scope appended at or before the position
+        let latest = |scope: &Option<String>| {
+            scopes.get(scope).and_then(|history| {
+                history
+                    .iter()
+                    .rev()
+                    .find(|(appended_at, _)| *appended_at <= position)
+            })
+        };
+
+        // A revocation for the parameter falls back to the delegation for every parameter
+        latest(&Some(param_name.to_owned()))
+            .and_then(|(_, delegate)| *delegate)
+            .or_else(|| latest(&None).and_then(|(_, delegate)| *delegate))
+    }
+
+    /// Gets each of the weights that a vote by the given voter on the given proposal would add to the proposal's
+    /// tally: that of the voter, and that of each account that has delegated its votes on the proposal's parameter
+    /// to the voter and hasn't been counted yet. Every weight is measured at the proposal's snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal being voted on
+    /// * `voter` - The account casting the vote
+    fn vote_weights(&self, proposal: &Proposal, voter: &Address) -> Vec<(Address, BigUint)> {
+        // Votes on proposals without snapshots can't have any weight
+        let snapshot = if let Some(snapshot) = proposal.snapshot {
+            snapshot
+        } else {
+            return Vec::new();
+        };
+        let counted = self.weights.get(&proposal.proposal_id);
+
+        let mut weights = vec![(*voter, self.voting_balance_of(voter, &snapshot))];
+
+        // Only delegations appended by the snapshot count, so that every node attributes the same weight to the vote
+        let position = if let Some(position) = self.ledger.hash_routes.get(&snapshot) {
+            *position
+        } else {
+            return weights;
+        };
+
+        // Delegators that have already been counted can't be counted twice
+        for delegator in self.delegations.keys() {
+            if delegator != voter
+                && !counted.map_or(false, |counted| counted.contains_key(delegator))
+                && self.delegate_of(delegator, &proposal.proposal_data.param_name, position)
+                    == Some(*voter)
+            {
+                weights.push((*delegator, self.voting_balance_of(delegator, &snapshot)));
+            }
+        }
+
+        weights
+    }
+
+    /// Records the vote carried by the given transaction, if it carries one, and counts it toward the tally of the
+    /// proposal that it targets, if that proposal is being voted on.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that has been appended to the ledger
+    fn record_vote(&mut self, tx: &Transaction) {
+        let vote = match Vote::from_transaction(tx, self.config.chain_id()) {
+            Some(Ok(vote)) => vote,
+            Some(Err(reason)) => {
+                warn!(
+                    "Ignoring the vote carried by transaction {}: {}",
+                    tx.hash, reason
+                );
+
+                return;
+            }
+            None => return,
+        };
+        let proposal_id = vote.target_proposal;
+
+        self.recorded_votes
+            .entry(proposal_id)
+            .or_default()
+            .push((tx.transaction_data.timestamp, vote));
+
+        // The vote may be enough to decide the proposal
+        if self.count_recorded_votes(&proposal_id) {
+            self.clear_proposal(&proposal_id);
+        }
+    }
+
+    /// Tallies the votes appended to the ledger on the pending proposal with the given ID, if votes on the proposal
+    /// are counted from the ledger. Returns whether or not any votes were counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The ID of the proposal
+    fn count_recorded_votes(&mut self, proposal_id: &Hash) -> bool {
+        let proposal = match self.pending_proposals.get(proposal_id) {
+            Some(proposal)
+                if proposal.is_voted_on_ledger()
+                    && self.recorded_votes.contains_key(proposal_id) =>
+            {
+                proposal.clone()
+            }
+            _ => return false,
+        };
+
+        let (ballots, weights) = self.recorded_weights(&proposal);
+
+        self.votes
+            .insert(*proposal_id, weights.values().cloned().sum());
+        self.voted.insert(
+            *proposal_id,
+            ballots.keys().map(|voter| (*voter, true)).collect(),
+        );
+        self.weights.insert(*proposal_id, weights);
+
+        // Make sure that the tally survives a restart
+        self.persist_proposal(&proposal, ProposalState::Open);
+
+        true
+    }
+
+    /// Gets the ballot of each account that has voted on the given proposal through the ledger, along with the weight
+    /// counted toward the proposal's tally from each address. Only the first vote that each account appends before
+    /// the proposal's deadline counts. Accounts that don't vote themselves are counted with the delegates that they
+    /// had named by the proposal's snapshot, and every weight is measured at the snapshot, so that every node with the
+    /// same ledger arrives at the same tally.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal whose votes should be weighed
+    fn recorded_weights(
+        &self,
+        proposal: &Proposal,
+    ) -> (HashMap<Address, bool>, HashMap<Address, BigInt>) {
+        let mut ballots: HashMap<Address, bool> = HashMap::new();
+
+        for (cast_at, vote) in self
+            .recorded_votes
+            .get(&proposal.proposal_id)
+            .into_iter()
+            .flatten()
+        {
+            if proposal.is_expired(*cast_at) {
+                continue;
+            }
+
+            if let Some(voter) = vote.voter_address() {
+                ballots.entry(voter).or_insert(vote.in_favor);
+            }
+        }
+
+        // Votes on proposals without snapshots can't have any weight
+        let (snapshot, position) = match proposal
+            .snapshot
+            .and_then(|snapshot| Some((snapshot, *self.ledger.hash_routes.get(&snapshot)?)))
+        {
+            Some(found) => found,
+            None => return (ballots, HashMap::new()),
+        };
+
+        let weigh = |account: &Address, in_favor: bool| {
+            let weight = self
+                .voting_balance_of(account, &snapshot)
+                .to_bigint()
+                .unwrap_or_else(BigInt::zero);
+
+            if in_favor {
+                weight
+            } else {
+                -weight
+            }
+        };
+
+        let mut weights: HashMap<Address, BigInt> = ballots
+            .iter()
+            .map(|(voter, in_favor)| (*voter, weigh(voter, *in_favor)))
+            .collect();
+
+        for delegator in self.delegations.keys() {
+            if ballots.contains_key(delegator) {
+                continue;
+            }
+
+            if let Some(in_favor) = self
+                .delegate_of(delegator, &proposal.proposal_data.param_name, position)
+                .and_then(|delegate| ballots.get(&delegate))
+            {
+                weights.insert(*delegator, weigh(delegator, *in_favor));
+            }
+        }
+
+        (ballots, weights)
+    }
+
+    /// Checks whether or not the proposal with the given ID proposes a transaction that has since been replaced or
+    /// cancelled by its sender.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The hash of the proposal
+    pub fn proposal_is_superseded(&self, proposal_id: &Hash) -> bool {
+        self.superseded_proposals.contains_key(proposal_id)
+    }
+
+    /// Registers a proposal to append the given mempool transaction to the ledger, or holds the transaction until it
+    /// matures.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be proposed
+    /// * `last_nonces` - The last nonce used by each sender in the most recently executed state
+    fn schedule_transaction(&mut self, tx: Transaction, last_nonces: &HashMap<String, u64>) {
+        // Hold the transaction if it can't be executed yet
+        if !GraphBoundValidator::new(&self.ledger, self.config.chain_id())
+            .transaction_is_mature(&tx)
+        {
+            info!("Holding transaction {} until it matures", tx.hash);
+
+            self.held_transactions.insert(tx.hash, tx);
+            self.persist_held_transactions();
+
+            return;
+        }
+
+        // Hold the transaction until the sender's earlier transactions have been accepted
+        if System::nonce_is_premature(&tx, last_nonces) {
+            info!(
+                "Holding transaction {} until the nonces preceding {} have been used",
+                tx.hash, tx.transaction_data.nonce
+            );
+
+            self.held_transactions.insert(tx.hash, tx);
+            self.persist_held_transactions();
+
+            return;
+        }
+
+        // Register a proposal for the transaction
+        self.register_transaction_proposal(&tx);
+    }
+
+    /// Gets the last nonce used by each sender, according to the most recently executed state in the ledger.
+    fn last_nonces(&self) -> HashMap<String, u64> {
+        self.ledger
+            .obtain_executed_head()
+            .and_then(|head| head.state_entry)
+            .map(|entry| entry.data.nonces)
+            .unwrap_or_default()
+    }
+
+    /// Checks whether or not the transaction skips any of its sender's nonces, according to the given last nonces.
+    /// Transactions that depend on an earlier transaction are held until that transaction is accepted instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction whose nonce should be checked
+    /// * `last_nonces` - The last nonce used by each sender
+    fn nonce_is_premature(tx: &Transaction, last_nonces: &HashMap<String, u64>) -> bool {
+        let next_nonce = last_nonces
+            .get(&tx.transaction_data.sender.to_str())
+            .map_or(0, |last_nonce| u128::from(*last_nonce) + 1);
+
+        tx.transaction_data.after.is_none() && u128::from(tx.transaction_data.nonce) > next_nonce
+    }
+
+    /// Picks the nonce that the sender's next transaction should use, given the last nonce recorded in the state that
+    /// the transaction builds on. Transactions that the sender has queued in the mempool, or that have been built but
+    /// not yet published, are accounted for, so that concurrent callers are never handed the same nonce. If the nonce
+    /// follows on from one of those transactions, its hash is returned as well, so that the new transaction can be
+    /// made to depend on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - The sender of the transaction
+    /// * `last_nonce` - The last nonce used by the sender in the state that the transaction builds on
+    pub fn next_nonce(&self, sender: &Address, last_nonce: Option<u64>) -> (u64, Option<Hash>) {
+        let state_nonce = last_nonce.map_or(0, |last_nonce| last_nonce.saturating_add(1));
+        let ttl = chrono::Duration::seconds(NONCE_RESERVATION_TTL_SECS);
+        let now = chrono::Utc::now();
+
+        // Find the sender's latest transaction that the state doesn't know about yet
+        let queued = self
+            .mempool
+            .last_from(sender)
+            .map(|tx| (tx.transaction_data.nonce, tx.hash));
+        let reserved = self
+            .nonce_reservations
+            .get(sender)
+            .filter(|reservation| reservation.reserved_at + ttl >= now)
+            .map(|reservation| (reservation.nonce, reservation.tx_hash));
+
+        match queued
+            .into_iter()
+            .chain(reserved)
+            .max_by_key(|(nonce, _)| *nonce)
+        {
+            Some((nonce, tx_hash)) if nonce >= state_nonce => {
+                (nonce.saturating_add(1), Some(tx_hash))
+            }
+            _ => (state_nonce, None),
+        }
+    }
+
+    /// Reserves the nonce used by the given transaction, which was built with a nonce obtained from `next_nonce`, so
+    /// that it isn't handed out again before the transaction is published.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction whose nonce should be reserved
+    pub fn reserve_nonce(&mut self, tx: &Transaction) {
+        self.nonce_reservations.insert(
+            tx.transaction_data.sender,
+            NonceReservation {
+                nonce: tx.transaction_data.nonce,
+                tx_hash: tx.hash,
+                reserved_at: chrono::Utc::now(),
+            },
+        );
+    }
+
+    /// Makes the data of a proposal to append the given transaction to the ledger.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be appended
+    fn transaction_proposal_data(tx: &Transaction) -> ProposalData {
+        ProposalData::new(
+            "ledger::transactions".to_owned(),
+            Operation::Append {
+                value_to_append: tx.to_bytes(),
+            },
+        )
+    }
+
+    /// Registers a proposal to append the given transaction to the ledger, unless one has already been made. The
+    /// proposal is attributed to the transaction's sender, whose signature on the transaction vouches for it, so the
+    /// sender's account needn't be stored on this node.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be proposed
+    fn register_transaction_proposal(&mut self, tx: &Transaction) {
+        if !self.transaction_proposal_ids(tx).is_empty() {
+            return;
+        }
+
+        let proposal = self.snapshot_proposal(Proposal::new(
+            format!("new_tx({})", tx.hash.to_str()),
+            Self::transaction_proposal_data(tx),
+        ));
+
+        self.register_proposal(proposal);
+    }
+
+    /// Gets the IDs of each of the pending or unpublished proposals to append the given transaction to the ledger.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction whose proposals should be found
+    fn transaction_proposal_ids(&self, tx: &Transaction) -> Vec<Hash> {
+        self.localized_proposals
+            .values()
+            .chain(self.pending_proposals.values())
+            .filter(|proposal| {
+                proposal
+                    .proposed_transaction()
+                    .map_or(false, |proposed| proposed.hash == tx.hash)
+            })
+            .map(|proposal| proposal.proposal_id)
+            .collect()
+    }
+
+    /// Stops holding or proposing the given transaction, which has been removed from the mempool. Returns the IDs of
+    /// the transaction's proposals.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be withdrawn
+    fn withdraw_transaction(&mut self, tx: &Transaction) -> Vec<Hash> {
+        let proposal_ids = self.transaction_proposal_ids(tx);
+
+        if self.held_transactions.remove(&tx.hash).is_some() {
+            self.persist_held_transactions();
+        }
+
+        for proposal_id in proposal_ids.iter() {
+            self.localized_proposals.remove(proposal_id);
+
+            if let Some(proposal) = self.pending_proposals.remove(proposal_id) {
+                self.close_proposal(&proposal, ProposalState::Rejected);
+            }
+        }
+
+        proposal_ids
+    }
+
+    /// Stops holding or proposing the given transaction, which has been replaced or cancelled by its sender, and
+    /// refuses any further votes on its proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The superseded transaction
+    fn supersede_transaction(&mut self, tx: &Transaction) {
+        let now = chrono::Utc::now();
+
+        for proposal_id in self.withdraw_transaction(tx) {
+            self.superseded_proposals.insert(proposal_id, now);
+        }
+
+        self.persist_superseded_proposals();
+    }
+
+    /// Evicts each of the mempool's transactions that have expired, or that can no longer be executed because they
+    /// (or other transactions with their nonces) have already been added to the ledger. Returns the hashes of the
+    /// evicted transactions.
+    pub fn prune_mempool(&mut self) -> Vec<Hash> {
+        // Forget about superseded proposals once they can no longer be pending anywhere
+        let ttl = chrono::Duration::seconds(mempool::DEFAULT_MEMPOOL_TTL_SECS);
+        let now = chrono::Utc::now();
+        let n_superseded = self.superseded_proposals.len();
+        self.superseded_proposals
+            .retain(|_, superseded_at| *superseded_at + ttl >= now);
+
+        if self.superseded_proposals.len() != n_superseded {
+            self.persist_superseded_proposals();
+        }
+
+        if self.mempool.is_empty() {
+            return Vec::new();
+        }
+
+        // Get the last nonce used by each sender in the most recently executed state
+        let nonces = self.last_nonces();
+        let ledger = &self.ledger;
+
+        // Transactions held until they mature stay held after outliving their time in the mempool
+        let mut evicted = self.mempool.evict_expired(now);
+        evicted.retain(|tx| !self.held_transactions.contains_key(&tx.hash));
+
+        evicted.append(&mut self.mempool.evict(|tx| {
+            ledger.hash_routes.contains_key(&tx.hash)
+                || nonces
+                    .get(&tx.transaction_data.sender.to_str())
+                    .map_or(false, |last_nonce| tx.transaction_data.nonce <= *last_nonce)
+        }));
+
+        for tx in evicted.iter() {
+            self.withdraw_transaction(tx);
+        }
+
+        if !evicted.is_empty() {
+            debug!("Evicted {} transactions from the mempool", evicted.len());
+
+            self.persist_mempool();
+        }
+
+        evicted.into_iter().map(|tx| tx.hash).collect()
+    }
+
+    /// Removes the proposal with the given ID from the set of pending proposals without executing it. If the proposal
+    /// would have appended a transaction to the ledger, the transaction is removed from the mempool as well. Returns
+    /// whether or not the proposal was pending.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The hash of the proposal that should be discarded
+    pub fn discard_proposal(&mut self, proposal_id: &Hash) -> bool {
+        let proposal = if let Some(prop) = self.pending_proposals.remove(proposal_id) {
+            prop
+        } else {
+            return false;
+        };
+
+        self.forget_proposed_transaction(&proposal);
+        self.close_proposal(&proposal, ProposalState::Rejected);
+
+        true
+    }
+
+    /// Removes the transaction that the given proposal would append to the ledger from the mempool, if the proposal
+    /// targets the ledger.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal whose transaction should be forgotten
+    fn forget_proposed_transaction(&mut self, proposal: &Proposal) {
+        if proposal.proposal_data.param_name != "ledger::transactions" {
+            return;
+        }
+
+        if let Operation::Append { value_to_append } = &proposal.proposal_data.operation {
+            if let Ok(tx) = Transaction::from_bytes(value_to_append) {
+                self.forget_transaction(&tx.hash);
+            }
+        }
+    }
+
+    /// Removes the transaction with the given hash from the mempool, and stops holding it.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash` - The hash of the transaction that should be forgotten
+    fn forget_transaction(&mut self, tx_hash: &Hash) {
+        // Held transactions may have left the mempool already
+        if self.held_transactions.remove(tx_hash).is_some() {
+            self.persist_held_transactions();
+        }
+
+        if self.mempool.remove(tx_hash).is_some() {
+            self.persist_mempool();
+        }
+    }
+
+    /// Proposes each of the held transactions that have matured, or whose nonce gaps have closed, since they were
+    /// published, and returns the number of transactions that were released.
+    pub fn release_mature_transactions(&mut self) -> usize {
+        // Collect the hashes of each of the transactions that can now be executed
+        let mature: Vec<Hash> = {
+            let validator = GraphBoundValidator::new(&self.ledger, self.config.chain_id());
+            let last_nonces = self.last_nonces();
+
+            self.held_transactions
+                .iter()
+                .filter(|(_, tx)| {
+                    validator.transaction_is_mature(tx)
+                        && !System::nonce_is_premature(tx, &last_nonces)
+                })
+                .map(|(hash, _)| *hash)
+                .collect()
+        };
+
+        // Propose each of the mature transactions
+        for hash in mature.iter() {
+            if let Some(tx) = self.held_transactions.remove(hash) {
+                self.register_transaction_proposal(&tx);
+            }
+        }
+
+        if !mature.is_empty() {
+            self.persist_held_transactions();
+        }
+
+        mature.len()
+    }
+
+    /// Clears the lists of localized proposals, cancellations and votes contained inside the system.
+    pub fn clear_localized_proposals(&mut self) {
+        // Reset both state measures of localized prop.
+        self.localized_proposals.clear();
+        self.localized_cancellations.clear();
+        self.localized_votes.clear();
+        self.new_tx_ctx.store(false, Ordering::SeqCst);
+    }
+
+    /// Move a proposal from the system's set of localized proposals to its general proposals.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The hash of the proposal that should be proposed
+    pub fn propose_proposal(&mut self, proposal_id: &Hash) -> Result<(), ExecutionError> {
+        // Ensure that the proposal exists. Otherwise, return a suitable error
+        if let Some(prop) = self.localized_proposals.remove(proposal_id) {
+            self.persist_proposal(&prop, ProposalState::Open);
+            self.pending_proposals.insert(*proposal_id, prop);
+
+            Ok(())
+        } else {
+            Err(ExecutionError::ProposalDoesNotExist {
+                proposal_id: proposal_id.to_str(),
+            })
+        }
+    }
+
+    /// Gets an atomic reference to the system's current new_tx state variable.
+    pub(crate) fn get_state_ref(&self) -> Arc<AtomicBool> {
+        // Clone the system's new_tx ctx reference variable
+        self.new_tx_ctx.clone()
+    }
+
+    /// Registers a provided vote as in favor or against a particular proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The hash of the proposal that the vote is targeting
+    /// * `vote` - The vote primitive instance
+    pub fn register_vote_for_proposal(
+        &mut self,
+        proposal_id: Hash,
+        vote: &Vote,
+    ) -> Result<(), ExecutionError> {
+        // The signature must be valid. Votes without any signature are rejected upon registration.
+        if let Some(sig) = &vote.signature {
+            if !sig.verify(
+                &SigningContext::new(Domain::Vote, self.config.chain_id()),
+                &*vote.hash(),
+            ) {
+                return Err(ExecutionError::Miscellaneous {
+                    error: "vote signature is invalid".to_owned(),
+                });
+            }
+        }
+
+        self.register_verified_vote_for_proposal(proposal_id, vote)
+    }
+
+    /// Registers a provided vote whose signature has already been verified (e.g. as part of a batch) as in favor or
+    /// against a particular proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The hash of the proposal that the vote is targeting
+    /// * `vote` - The vote primitive instance
+    pub fn register_verified_vote_for_proposal(
+        &mut self,
+        proposal_id: Hash,
+        vote: &Vote,
+    ) -> Result<(), ExecutionError> {
+        // Votes on transactions that their senders have since replaced or cancelled don't count
+        if self.proposal_is_superseded(&proposal_id) {
+            return Err(ExecutionError::ProposalSuperseded {
+                proposal_id: proposal_id.to_str(),
+            });
+        }
+
+        // Votes cast after the proposal's deadline don't count, even if it hasn't been expired yet
+        if let Some(proposal) = self.pending_proposals.get(&proposal_id) {
+            if proposal.is_expired(chrono::Utc::now()) {
+                return Err(ExecutionError::ProposalClosed {
+                    proposal_id: proposal_id.to_str(),
+                    state: ProposalState::Expired.to_string(),
+                });
+            }
+        } else if let Some(state) = self.proposal_state(&proposal_id) {
+            return Err(ExecutionError::ProposalClosed {
+                proposal_id: proposal_id.to_str(),
+                state: state.to_string(),
+            });
+        }
+
+        // Ensure that the proposal exists in the runtime
+        if let Some(proposal) = self.pending_proposals.get(&proposal_id).cloned() {
+            // Votes on changes to the network's parameters are only counted from the ledger
+            if proposal.is_voted_on_ledger() {
+                return Err(ExecutionError::VoteNotOnLedger {
+                    proposal_id: proposal_id.to_str(),
+                });
+            }
+
+            // The vote must have a signature in order to be registered
+            let sig = if let Some(sig) = vote.clone().signature.take() {
+                sig
+            } else {
+                return Err(ExecutionError::Miscellaneous {
+                    error: "vote signature is nil".to_owned(),
+                });
+            };
+
+            // Try to get a public key from the vote's signature. If there is no public key
+            // associated with the signature, it must be invalid.
+            if let Ok(public_key) = sig.public_key() {
+                let voter = Address::from_public_key(&public_key);
+
+                // Get the set of users that have voted for the proposal so that we can ensure
+                // this person isn't voting twice
+                let voters = self.voted.entry(proposal_id).or_insert_with(HashMap::new);
+
+                // Ensure the voter is unique
+                if !voters.contains_key(&voter) {
+                    // We've voted now
+                    voters.insert(voter, true);
+
+                    // Calculate the weight of the vote, and of each of the votes delegated to the voter
+                    let weights = self.vote_weights(&proposal, &voter);
+
+                    let counted = self.weights.entry(proposal_id).or_insert_with(HashMap::new);
+                    let tally = self.votes.entry(proposal_id).or_insert_with(BigInt::zero);
+
+                    for (source, weight) in weights {
+                        let weight = weight.to_bigint().unwrap_or_else(BigInt::zero);
+                        let weight = if vote.in_favor { weight } else { -weight };
+
+                        // A direct vote replaces any weight counted for the voter through its delegate
+                        if let Some(previous) = counted.insert(source, weight.clone()) {
+                            *tally -= previous;
+                        }
+
+                        *tally += weight;
+                    }
+
+                    // Make sure that the vote survives a restart
+                    self.persist_proposal(&proposal, ProposalState::Open);
+                }
+            } else {
+                return Err(ExecutionError::Miscellaneous {
+                    error: "vote signature public key is nil".to_owned(),
+                });
+            }
+
+            Ok(())
+        } else {
+            Err(ExecutionError::ProposalDoesNotExist {
+                proposal_id: proposal_id.to_str(),
+            })
+        }
+    }
+
+    /// Execute a proposal in the pending proposals set with the given hash.
+    pub fn execute_proposal(&mut self, proposal_id: Hash) -> Result<(), ExecutionError> {
+        // Try to pull the proposal out of the proposal queue. If this fails, that means that the proposal doesn't actually exist
+        let target_proposal = if let Some(prop) = self.pending_proposals.remove(&proposal_id) {
+            prop
+        } else {
+            return Err(ExecutionError::ProposalDoesNotExist {
+                proposal_id: proposal_id.to_str(),
+            }); // Return error
+        };
+
+        // Find the parameter that the proposal targets, and make sure that the proposal is a well-formed change to it
+        let checked = if let Some(param) = self
+            .parameters
+            .get(&target_proposal.proposal_data.param_name)
+        {
+            param
+                .check_operation(&target_proposal.proposal_data.operation)
+                .and_then(|_| self.check_activation(param, &target_proposal))
+        } else {
+            Err(ExecutionError::InvalidTargetProposalParam {
+                proposal_param: target_proposal.proposal_data.param_name.clone(),
+            })
+        };
+
+        // Changes scheduled for a point that the ledger hasn't reached yet are made once it does
+        if let (Ok(_), Some(activation)) = (&checked, target_proposal.activation) {
+            info!(
+                "Scheduled proposal {} to take effect at {}",
+                proposal_id, activation
+            );
+
+            self.close_proposal(&target_proposal, ProposalState::Scheduled);
+            self.scheduled_proposals
+                .insert(proposal_id, target_proposal);
+
+            return Ok(());
+        }
+
+        // Changes that don't alter the rules for executing transactions (e.g. appending a transaction) are made
+        // straight away
+        let position = self.ledger.nodes.len() as u64;
+        let result = checked.and_then(|_| self.apply_change(&target_proposal, position));
+
+        // The proposal won its vote either way, but it has only been executed if the change could be made
+        self.close_proposal(
+            &target_proposal,
+            if result.is_ok() {
+                ProposalState::Executed
+            } else {
+                ProposalState::Passed
+            },
+        );
+
+        result
+    }
+
+    /// Ensures that the given proposal, which hasn't been registered yet, is scheduled in the way that the parameter
+    /// it targets requires.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal whose activation point should be checked
+    pub fn check_schedule(&self, proposal: &Proposal) -> Result<(), ExecutionError> {
+        match self.parameters.get(&proposal.proposal_data.param_name) {
+            Some(param) => self.check_activation(param, proposal),
+            None => Err(ExecutionError::InvalidTargetProposalParam {
+                proposal_param: proposal.proposal_data.param_name.clone(),
+            }),
+        }
+    }
+
+    /// Ensures that the given proposal is only scheduled if it targets a parameter whose changes can be scheduled,
+    /// and that it is scheduled if it targets a parameter whose changes must be. Transactions are appended to the
+    /// ledger as soon as their proposals pass, so they can't be scheduled. Changes can't take effect retroactively,
+    /// so a proposal is no longer valid once the ledger has reached its activation point.
+    ///
+    /// # Arguments
+    ///
+    /// * `param` - The parameter that the proposal targets
+    /// * `proposal` - The proposal whose activation point should be checked
+    fn check_activation(
+        &self,
+        param: &Parameter,
+        proposal: &Proposal,
+    ) -> Result<(), ExecutionError> {
+        match proposal.activation {
+            Some(_) if proposal.proposal_data.param_name == "ledger::transactions" => {
+                Err(ExecutionError::InvalidOperation {
+                    operation: "schedule".to_owned(),
+                    proposal_param: proposal.proposal_data.param_name.clone(),
+                })
+            }
+            None if param.requires_activation => Err(ExecutionError::ActivationRequired {
+                proposal_param: param.name.clone(),
+            }),
+            Some(activation) if self.activation_position(activation).is_some() => {
+                Err(ExecutionError::ActivationPassed {
+                    proposal_param: param.name.clone(),
+                    activation: activation.to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Makes the change proposed by the given proposal, which has passed, and records the position in the ledger from
+    /// which any resulting change to the rules for executing transactions takes effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal whose change should be made
+    /// * `position` - The number of ledger nodes that precede the first transaction affected by the change
+    fn apply_change(&mut self, proposal: &Proposal, position: u64) -> Result<(), ExecutionError> {
+        // Changes never reach back to transactions that the ledger already contains (e.g. when a node that has been
+        // stopped catches up on the changes that it missed)
+        let position = position.max(self.ledger.nodes.len() as u64);
+
+        let apply = self
+            .parameters
+            .get(&proposal.proposal_data.param_name)
+            .map(|param| param.apply)
+            .ok_or_else(|| ExecutionError::InvalidTargetProposalParam {
+                proposal_param: proposal.proposal_data.param_name.clone(),
+            })?;
+
+        let previous = self.config.epoch(position);
+
+        apply(self, proposal.proposal_data.operation.clone())?;
+
+        // Transactions don't change the rules themselves, though they may cause scheduled changes to be made
+        if proposal.proposal_data.param_name != "ledger::transactions"
+            && self.config.epoch(position) != previous
+        {
+            self.config.record_epoch(position, previous);
+
+            self.config
+                .write_to_disk()
+                .map_err(|e| ExecutionError::Miscellaneous {
+                    error: e.to_string(),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Determines the position in the ledger that the given activation point names, if the ledger has reached it.
+    ///
+    /// # Arguments
+    ///
+    /// * `activation` - The activation point
+    pub fn activation_position(&self, activation: ActivationPoint) -> Option<u64> {
+        match activation {
+            ActivationPoint::Height(height) if self.ledger.nodes.len() as u64 >= height => {
+                Some(height)
+            }
+            ActivationPoint::Height(_) => None,
+            // The change takes effect from the node after the transaction
+            ActivationPoint::Transaction(tx_hash) => self
+                .ledger
+                .hash_routes
+                .get(&tx_hash)
+                .map(|index| *index as u64 + 1),
+        }
+    }
+
+    /// Makes the change proposed by each of the scheduled proposals whose activation points the ledger has reached,
+    /// in the order of their activation points, and returns the number of proposals that were executed.
+    pub fn activate_scheduled_proposals(&mut self) -> usize {
+        let mut due: Vec<(u64, Hash)> = self
+            .scheduled_proposals
+            .values()
+            .filter_map(|proposal| {
+                proposal
+                    .activation
+                    .and_then(|activation| self.activation_position(activation))
+                    .map(|position| (position, proposal.proposal_id))
+            })
+            .collect();
+
+        // Changes taking effect at the same position are made in the order of their IDs, so that every node agrees
+        due.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| (*a.1).cmp(&*b.1)));
+
+        let mut executed = 0;
+
+        for (position, proposal_id) in due {
+            let proposal = if let Some(proposal) = self.scheduled_proposals.remove(&proposal_id) {
+                proposal
+            } else {
+                continue;
+            };
+
+            let state = match self.apply_change(&proposal, position) {
+                Ok(_) => {
+                    info!(
+                        "Scheduled proposal {} took effect at position {}",
+                        proposal_id, position
+                    );
+
+                    executed += 1;
+
+                    ProposalState::Executed
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to execute scheduled proposal {}: {}",
+                        proposal_id, e
+                    );
+
+                    ProposalState::Passed
+                }
+            };
+
+            // Keep the votes counted when the proposal was scheduled
+            let record = match self.governance_store.get(&proposal_id) {
+                Ok(Some(record)) => record,
+                Ok(None) => ProposalRecord::new(proposal),
+                Err(e) => {
+                    warn!("Failed to read the governance store: {}", e);
+
+                    ProposalRecord::new(proposal)
+                }
+            };
+            self.record_decision(&record.transition(state, chrono::Utc::now()));
+        }
+
+        executed
+    }
+
+    /// Appends the provided transaction to the ledger, on the condition that it asserts the correct parent state.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be appended to the ledger
+    pub(crate) fn append_transaction(&mut self, tx: Transaction) -> Result<(), ExecutionError> {
+        // The transaction is no longer pending, whether or not it can be executed
+        self.forget_transaction(&tx.hash);
+
+        // Hold the transaction to every rule, under the limits in effect at its position in the ledger, so that
+        // replaying the ledger applies the same rules as when the transaction was first appended. Whether the
+        // transaction's parents have been executed yet depends on when it is appended, rather than on the transaction.
+        self.validator_at(self.ledger.nodes.len() as u64)
+            .without_rule(RecencyRule.name())
+            .transaction_is_valid(&tx)
+            .map_err(|e| ExecutionError::Miscellaneous {
+                error: format!("Invalid transaction: {}", e),
+            })?;
+
+        // Get the index of the submitted transaction entry
+        let entry_index = self.ledger.push(tx.clone(), None);
+
+        // Execute the parent transactions, get the overall hash
+        let parent_tx_state = self.ledger.execute_parent_nodes(entry_index)?;
+
+        // Get the hash of the parent state that the transaction THINKS is right
+        let asserted_parent_state_hash =
+            if let Some(parent_state_hash) = tx.transaction_data.parent_state_hash {
+                parent_state_hash
+            } else {
+                // Remove the head tx, since it's invalid
+                self.ledger.rollback_head();
+
+                // Return the error
+                return Err(ExecutionError::Miscellaneous {
+                    error: "Invalid transaction: must have parent state hash.".to_owned(),
+                });
+            };
+
+        // UWU WHAT'S THIS I SEE?
+        if parent_tx_state.hash != asserted_parent_state_hash {
+            // Remove the head tx, since it's invalid
+            self.ledger.rollback_head();
+
+            // Return the error
+            return Err(ExecutionError::Miscellaneous{error: format!("Invalid transaction: merged parent states must have a hash matching that which is asserted by the transaction (found {}, tx asserted {}).", parent_tx_state.hash, asserted_parent_state_hash)});
+        };
+
+        // Give effect to any delegation or vote carried by the transaction
+        self.record_delegation(&tx, entry_index);
+        self.record_vote(&tx);
+
+        // Drop any pending transactions that the ledger has since made obsolete
+        self.prune_mempool();
+
+        // Make any scheduled changes that take effect after the transaction
+        self.activate_scheduled_proposals();
+
+        Ok(())
+    }
+
+    /// Verifies the signatures of each of the given transactions, downloaded from a peer, in a batch, and appends the
+    /// valid transactions to the ledger in the order in which they were downloaded. Only the network's genesis
+    /// transaction, which must be the root of the ledger, may go unsigned.
+    ///
+    /// # Arguments
+    ///
+    /// * `transactions` - The transactions that were downloaded
+    pub fn execute_synced_transactions(&mut self, transactions: Vec<Transaction>) {
+        // The genesis transaction isn't signed, so every other transaction must be verified
+        let signed: Vec<(usize, &Transaction)> = transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| !tx.genesis)
+            .collect();
+
+        // Find any transactions that don't belong to their senders, including those that aren't signed at all
+        let signed_transactions: Vec<&Transaction> = signed.iter().map(|(_, tx)| *tx).collect();
+        let invalid_indices: Vec<usize> =
+            match signature::verify_tx_batch(self.config.chain_id(), &signed_transactions) {
+                Ok(_) => Vec::new(),
+                Err(BatchVerificationError::InvalidSignatures { invalid_indices }) => {
+                    invalid_indices.into_iter().map(|i| signed[i].0).collect()
+                }
+            };
+
+        for (i, tx) in transactions.into_iter().enumerate() {
+            let hash = tx.hash;
+
+            // Skip any transactions whose signatures are invalid
+            if invalid_indices.contains(&i) {
+                warn!(
+                    "Refusing to synchronize transaction {}: invalid signature",
+                    hash
+                );
+
+                continue;
+            }
+
+            // Only the root of the ledger may be a genesis transaction, and the root must be one
+            if tx.genesis != self.ledger.nodes.is_empty() {
+                warn!(
+                    "Refusing to synchronize transaction {}: only the root of the ledger may be a genesis transaction",
+                    hash
+                );
+
+                continue;
+            }
+
+            // If we haven't a single node in the graph, we'll just treat this node as the root
+            if self.ledger.nodes.is_empty() {
+                // Just push the root node onto the graph
+                self.ledger.push(tx, None);
+
+                continue;
+            }
+
+            // Make a proposal for the transaction, so we can execute it more effectively
+            let proposal = Proposal::new(
+                "sync_child".to_owned(),
+                ProposalData::new(
+                    "ledger::transactions".to_owned(),
+                    Operation::Append {
+                        value_to_append: tx.to_bytes(),
+                    },
+                ),
+            );
+
+            // The ID of the proposal. We need to copy this, since we'll move it into the system through registration
+            let id = proposal.proposal_id;
+
+            // Put the proposal in the system, so we can execute it
+            self.push_proposal(proposal);
+
+            // Execute the proposal so it gets added to the dag
+            match self.execute_proposal(id) {
+                Ok(_) => info!("Successfully executed transaction {}", id),
+                Err(e) => warn!("Failed to execute transaction {}: {}", hash, e),
+            }
+        }
+    }
+
+    /// Determines the number of coins that a particular vote would add to the tally of the proposal that it targets:
+    /// the voter's balance, plus that of each account that has delegated its votes to the voter and hasn't been
+    /// counted yet. Measuring every balance at the proposal's snapshot ensures that coins moved between accounts
+    /// after the proposal was made can't be counted twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `vote` - The vote that the weight should be determined of
+    pub fn determine_vote_weight(&self, vote: &Vote) -> BigUint {
+        // Determine the address of the account submitting the vote
+        let voter = if let Some(addr) = vote.voter_address() {
+            addr
+        } else {
+            // Since the vote doesn't have a voter, the vote can't have any weight
+            return BigUint::zero();
+        };
+
+        // Votes on unknown proposals can't have any weight
+        self.pending_proposals
+            .get(&vote.target_proposal)
+            .map_or_else(BigUint::zero, |proposal| {
+                self.vote_weights(proposal, &voter)
+                    .into_iter()
+                    .map(|(_, weight)| weight)
+                    .sum()
+            })
+    }
+
+    /// Registers a vote cast through this node, and queues it to be published.
+    ///
+    /// # Arguments
+    ///
+    /// * `vote` - The signed vote
+    pub fn cast_vote(&mut self, vote: Vote) -> Result<(), ExecutionError> {
+        self.register_vote_for_proposal(vote.target_proposal, &vote)?;
+        self.record_own_vote(&vote);
+
+        self.localized_votes.push(vote);
+
+        // Make sure that we're going to broadcast the vote
+        self.new_tx_ctx.store(true, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Remembers that the given vote, which has already been registered, was cast through this node.
+    ///
+    /// # Arguments
+    ///
+    /// * `vote` - The vote cast through this node
+    pub fn record_own_vote(&mut self, vote: &Vote) {
+        self.own_votes
+            .entry(vote.target_proposal)
+            .or_insert_with(Vec::new)
+            .push(vote.clone());
+    }
+
+    /// Gets each of the votes cast through this node on proposals that are still open.
+    pub fn own_votes(&self) -> Vec<Vote> {
+        self.own_votes.values().flatten().cloned().collect()
+    }
+
+    /// Gets the threshold that proposals targeting the parameter with the given name must meet: the parameter's
+    /// default threshold, unless the network's config overrides it.
+    ///
+    /// # Arguments
+    ///
+    /// * `param_name` - The name of the parameter
+    pub fn threshold_of(&self, param_name: &str) -> Threshold {
+        self.parameters
+            .get(param_name)
+            .map_or_else(Threshold::default, |param| param.threshold_in(&self.config))
+    }
+
+    /// Gets the threshold that the given proposal must meet to pass. A proposal to change the threshold of a
+    /// parameter must meet both the parameter's current threshold and the one that it proposes, on top of the
+    /// threshold of the thresholds themselves, so that no threshold can be changed by a weaker vote than it demands.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal
+    pub fn proposal_threshold(&self, proposal: &Proposal) -> Threshold {
+        let threshold = self.threshold_of(&proposal.proposal_data.param_name);
+
+        match &proposal.proposal_data.operation {
+            Operation::Amend { amended_value }
+                if proposal.proposal_data.param_name == "governance::thresholds" =>
+            {
+                ParameterThreshold::decode(amended_value).map_or(threshold, |change| {
+                    threshold
+                        .stricter(self.threshold_of(&change.param_name))
+                        .stricter(change.threshold)
+                })
+            }
+            _ => threshold,
+        }
+    }
+
+    /// Decides the pending proposal with the given ID according to the votes cast on it so far: returns true if the
+    /// proposal has passed, false if it can no longer pass, or none if it hasn't been decided yet (or isn't pending).
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The ID of the proposal
+    pub fn decide(&self, proposal_id: &Hash) -> Option<bool> {
+        let proposal = self.pending_proposals.get(proposal_id)?;
+
+        // Split the weight counted toward the proposal's tally into that in favor of it, and that against it
+        let (in_favor, against) = self.weights.get(proposal_id).map_or_else(
+            || (BigInt::zero(), BigInt::zero()),
+            governance::split_weights,
+        );
+
+        self.proposal_threshold(proposal).decide(
+            &in_favor.to_biguint().unwrap_or_else(BigUint::zero),
+            &against.to_biguint().unwrap_or_else(BigUint::zero),
+            &self.issuance_of(proposal),
+            proposal.is_expired(chrono::Utc::now()),
+        )
+    }
+
+    /// Executes the pending proposal with the given ID if it has passed, or rejects it if it can no longer pass.
+    /// Proposals that pass, but aren't valid, are discarded. Returns whether or not the ledger may have changed (i.e.
+    /// the proposal was executed, or a rejected proposal was removed).
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The ID of the proposal
+    pub fn clear_proposal(&mut self, proposal_id: &Hash) -> bool {
+        match self.decide(proposal_id) {
+            // If the proposal is invalid, don't execute it, but clear it, nonetheless
+            Some(true) if !self.validate_proposal(proposal_id) => {
+                self.discard_proposal(proposal_id);
+
+                false
+            }
+
+            // Execute the proposal
+            Some(true) => match self.execute_proposal(*proposal_id) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Failed to execute proposal {}: {}", proposal_id, e);
+
+                    false
+                }
+            },
+            Some(false) => self.reject_proposal(proposal_id),
+            None => false,
+        }
+    }
+
+    /// Gets the live tally of the votes on the proposal with the given ID, whether it is open or has been closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The ID of the proposal
+    pub fn tally(&self, proposal_id: &Hash) -> Option<ProposalTally> {
+        self.get_proposal_record(proposal_id).map(|record| {
+            let threshold = self.proposal_threshold(&record.proposal);
+
+            let issuance = self.issuance_of(&record.proposal);
+
+            ProposalTally::new(&record, threshold, issuance)
+        })
+    }
+
+    /// Gets the number of coins in circulation at the given proposal's snapshot, against which the votes on the
+    /// proposal are measured.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal
+    fn issuance_of(&self, proposal: &Proposal) -> BigUint {
+        proposal
+            .snapshot
+            .map_or_else(BigUint::zero, |snapshot| self.ledger.issuance_at(&snapshot))
+    }
+
+    /// Determins the number of coins from voters in favor of the proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal that the weight should be determined of
+    pub fn get_coins_in_support_of(&self, proposal: &Hash) -> BigInt {
+        self.votes.get(proposal).unwrap_or(&BigInt::zero()).clone()
+    }
+
+    /// Determines whether or not a particular proposal is valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal` - The proposal that should be validated
+    pub fn validate_proposal(&self, proposal: &Hash) -> bool {
+        // Get the proposal's metadata
+        if let Some(prop) = self.pending_proposals.get(proposal) {
+            // The proposal must be a well-formed change to a governable parameter, which the parameter permits
+            return self
+                .parameters
+                .get(&prop.proposal_data.param_name)
+                .map_or(false, |param| {
+                    param
+                        .check_operation(&prop.proposal_data.operation)
+                        .and_then(|_| self.check_activation(param, prop))
+                        .and_then(|_| (param.validate)(self, &prop.proposal_data.operation))
+                        .is_ok()
+                });
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::super::super::types::{
+        payload, stamp,
+        state::Entry,
+        transaction::{sign_transaction, TransactionBuilder},
+    };
+    use super::super::{
+        parameter::OperationKind,
+        threshold::{Share, ThresholdBase},
+    };
+    use super::*; // Import names from the parent module
+
+    use crate::rand::Rng; // Import rand
+    use ed25519_dalek::Keypair; // Import the edwards25519 digital signature library
+    use rand::rngs::OsRng; // Import the os's rng
+    use std::collections::BTreeMap;
+
+    /// Makes a system with its own data directory, whose ledger contains only a root transaction.
+    pub(crate) fn test_system() -> System {
+        let data_dir = io::format_db_dir(&format!(
+            ".tests/system_{}",
+            rand::thread_rng().gen::<u16>()
+        ));
+
+        let mut system = System::with_data_dir(
+            config::Config {
+                reward_per_gas: BigUint::from(config::DEFAULT_REWARD_PER_GAS),
+                network_name: "olympia".to_owned(),
+                chain_id: "olympia".to_owned(),
+                max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+                stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+                approval_thresholds: BTreeMap::new(),
+                history: Vec::new(),
+            },
+            &data_dir,
+        );
+
+        let root_tx = TransactionBuilder::new()
+            .nonce(0)
+            .sender(Address::default())
+            .recipient(Address::default())
+            .build()
+            .unwrap(); // Make a root transaction
+        system.ledger = Graph::new_with_db_path(root_tx, &format!("{}/ledger", data_dir));
+
+        system
+    }
+
+    /// Gets the data directory of a system made by `test_system`.
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - The system
+    pub(crate) fn test_data_dir(system: &System) -> &str {
+        &system.data_dir
+    }
+
+    /// Closes a system made by `test_system`, and removes its data directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - The system that should be removed
+    pub(crate) fn remove_test_system(system: System) {
+        let data_dir = system.data_dir.clone();
+        drop(system); // Close the system's databases before removing them
+
+        std::fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    /// Adds an executed node recording the given balances to the system's ledger, and returns its hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - The system whose ledger the node should be added to
+    /// * `balances` - The balance of each account in the node's state
+    fn push_balances(system: &mut System, balances: &[(Address, u64)]) -> Hash {
+        let tx = TransactionBuilder::new()
+            .nonce(system.ledger.nodes.len() as u64)
+            .sender(Address::default())
+            .recipient(Address::default())
+            .parents(vec![system.ledger.nodes[0].hash])
+            .build()
+            .unwrap(); // Make a transaction to attach the balances to
+        let hash = tx.hash;
+
+        system.ledger.push(
+            tx,
+            Some(Entry::new(
+                HashMap::new(),
+                balances
+                    .iter()
+                    .map(|(address, balance)| (address.to_str(), BigUint::from(*balance)))
+                    .collect(),
+            )),
+        );
+
+        hash
+    }
+
+    /// Makes a transaction from the given voter carrying a vote on the given proposal, dated at the given time.
+    fn vote_transaction(
+        voter: &Keypair,
+        proposal_id: Hash,
+        in_favor: bool,
+        cast_at: chrono::DateTime<chrono::Utc>,
+    ) -> Transaction {
+        let vote = Vote::new(
+            proposal_id,
+            in_favor,
+            Keypair::from_bytes(&voter.to_bytes()).unwrap(),
+            "olympia",
+        );
+
+        TransactionBuilder::new()
+            .nonce(0)
+            .sender(Address::from_key_pair(voter))
+            .recipient(Address::default())
+            .payload(payload::Payload::vote(&vote.encode()))
+            .timestamp(cast_at)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sync_unsigned_transactions() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut system = test_system();
+
+        let sender = Keypair::generate(&mut csprng);
+        let root = system.ledger.nodes[0].hash;
+        let root_state = system.ledger.nodes[0].state_entry.clone().unwrap();
+
+        // Makes a spend from the sender that builds on the root of the ledger
+        let spend = || {
+            TransactionBuilder::new()
+                .nonce(0)
+                .sender(Address::from_key_pair(&sender))
+                .recipient(Address::default())
+                .parents(vec![root])
+                .parental_state(root_state.clone(), vec![(root, root_state.clone())])
+        };
+
+        // Unsigned spends must be refused, even when they claim to be the genesis
+        let unsigned = spend().build().unwrap();
+        let mut fake_genesis = unsigned.clone();
+        fake_genesis.genesis = true;
+        system.execute_synced_transactions(vec![unsigned, fake_genesis]);
+        assert_eq!(system.ledger.nodes.len(), 1);
+
+        // The same spend is synchronized once it has been signed by its sender
+        let signed = spend()
+            .build_signed(Keypair::from_bytes(&sender.to_bytes()).unwrap(), "olympia")
+            .unwrap();
+        system.execute_synced_transactions(vec![signed]);
+        assert_eq!(system.ledger.nodes.len(), 2);
+    }
+
+    #[test]
+    fn test_replay_applies_epochs() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut system = test_system();
+
+        let sender = Keypair::generate(&mut csprng);
+        let root = system.ledger.nodes[0].hash;
+        let root_state = system.ledger.nodes[0].state_entry.clone().unwrap();
+        let spend = TransactionBuilder::new()
+            .nonce(0)
+            .sender(Address::from_key_pair(&sender))
+            .recipient(Address::default())
+            .parents(vec![root])
+            .parental_state(root_state.clone(), vec![(root, root_state)])
+            .build_signed(Keypair::from_bytes(&sender.to_bytes()).unwrap(), "olympia")
+            .unwrap(); // Make a spend that doesn't prove any work
+
+        // Makes a history in which stamps have had to prove work from the given position onwards
+        let initial = system.config.clone();
+        let history = |starts_at: u64| {
+            let mut config = initial.clone();
+
+            let previous = config.epoch(0);
+            config.stamp_difficulty = 8;
+            config.record_epoch(starts_at, previous);
+
+            config
+        };
+
+        // The spend can't be replayed at a position from which work has been required
+        system.config = history(1);
+        system.execute_synced_transactions(vec![spend.clone()]);
+        assert_eq!(system.ledger.nodes.len(), 1);
+
+        // The spend is replayed under the rules that were in effect at its position, not under the current rules
+        system.config = history(2);
+        system.execute_synced_transactions(vec![spend]);
+        assert_eq!(system.ledger.nodes.len(), 2);
+    }
+
+    #[test]
+    fn test_held_transactions() {
+        let mut system = test_system();
+
+        // Makes a transaction with the given maturity conditions, signed by its sender
+        let sender = Keypair::generate(&mut OsRng {});
+        let root = system.ledger.nodes[0].hash;
+        let locked_tx = |not_before: Option<chrono::DateTime<chrono::Utc>>, after: Option<Hash>| {
+            let mut tx = TransactionBuilder::new()
+                .nonce(0)
+                .sender(Address::from_key_pair(&sender))
+                .recipient(Address::default())
+                .parents(vec![root])
+                .build()
+                .unwrap();
+            tx.register_time_lock(not_before, after);
+            sign_transaction(
+                Keypair::from_bytes(&sender.to_bytes()).unwrap(),
+                &mut tx,
+                "olympia",
+            )
+            .unwrap();
+
+            tx
+        };
+
+        // Transactions that depend on a transaction that hasn't been accepted are held
+        let tx = locked_tx(None, Some(Hash::new(vec![1; 32])));
+        system.propose_transaction(tx.clone()).unwrap();
+        assert!(system.held_transactions.contains_key(&tx.hash));
+
+        // Transactions locked beyond the time of their parents will never mature, so they aren't held
+        let locked = locked_tx(Some(chrono::Utc::now() + chrono::Duration::days(1)), None);
+        match system.propose_transaction(locked) {
+            Err(MempoolError::NeverMatures { .. }) => (),
+            _ => panic!("transaction that can never mature was proposed"),
+        }
+
+        // The transaction is still held after a restart, even once it has left the mempool
+        system.mempool.remove(&tx.hash);
+        system.persist_mempool();
+
+        let restarted = System::with_data_dir(system.config.clone(), &system.data_dir);
+        assert!(restarted.held_transactions.contains_key(&tx.hash));
+    }
+
+    #[test]
+    fn test_transaction_proposals() {
+        let mut system = test_system();
+
+        let sender = Keypair::generate(&mut OsRng {});
+        let root = system.ledger.nodes[0].hash;
+
+        // Makes a transaction from the given sender that builds on the root of the ledger
+        let transaction = |sender: &Keypair| {
+            TransactionBuilder::new()
+                .nonce(0)
+                .sender(Address::from_key_pair(sender))
+                .recipient(Address::default())
+                .parents(vec![root])
+                .build_signed(Keypair::from_bytes(&sender.to_bytes()).unwrap(), "olympia")
+                .unwrap()
+        };
+
+        // Transactions are proposed on behalf of their senders, whose accounts needn't be stored on the node
+        let original = transaction(&sender);
+        system.propose_transaction(original.clone()).unwrap();
+        assert_eq!(system.localized_proposals.len(), 1);
+        let proposal = system.localized_proposals.values().next().unwrap();
+        let proposal_id = proposal.proposal_id;
+        assert_eq!(
+            proposal.verified_proposer("olympia"),
+            Some(Address::from_key_pair(&sender))
+        );
+        assert!(proposal.id_is_valid());
+
+        // Nobody else can sign for them
+        let stranger = Keypair::generate(&mut OsRng {});
+        assert_eq!(
+            proposal
+                .clone()
+                .sign(&stranger, "olympia")
+                .verified_proposer("olympia"),
+            None
+        );
+
+        // Transactions that aren't signed by their senders can't be proposed
+        let mut unsigned = transaction(&stranger);
+        unsigned.signature = None;
+        assert!(system.propose_transaction(unsigned.clone()).is_err());
+        assert!(system.mempool.get(&unsigned.hash).is_none());
+
+        // Replacing a transaction supersedes its proposal
+        let replacement = TransactionBuilder::new()
+            .nonce(0)
+            .sender(Address::from_key_pair(&sender))
+            .recipient(Address::default())
+            .value(BigUint::from(1u8))
+            .parents(vec![root])
+            .timestamp(original.transaction_data.timestamp + chrono::Duration::seconds(1))
+            .build_signed(Keypair::from_bytes(&sender.to_bytes()).unwrap(), "olympia")
+            .unwrap();
+        system.admit_transaction(replacement).unwrap();
+        assert!(system.proposal_is_superseded(&proposal_id));
+
+        // Neither the proposal nor the replaced transaction come back after a restart
+        let mut restarted = System::with_data_dir(system.config.clone(), &system.data_dir);
+        assert!(restarted.proposal_is_superseded(&proposal_id));
+        assert!(restarted.admit_transaction(original).is_err());
+    }
+
+    #[test]
+    fn test_rename_network() {
+        let mut system = test_system();
+
+        let sender = Keypair::generate(&mut OsRng {});
+        let tx = TransactionBuilder::new()
+            .nonce(0)
+            .sender(Address::from_key_pair(&sender))
+            .recipient(Address::default())
+            .parents(vec![system.ledger.nodes[0].hash])
+            .build_signed(sender, "olympia")
+            .unwrap();
+
+        // Renaming the network doesn't change the chain that signatures are bound to
+        let apply = system.parameters.get("config::network_name").unwrap().apply;
+        apply(
+            &mut system,
+            Operation::Amend {
+                amended_value: b"olympia_renamed".to_vec(),
+            },
+        )
+        .unwrap();
+        assert_eq!(system.config.network_name, "olympia_renamed");
+        assert_eq!(system.config.chain_id(), "olympia");
+
+        system.propose_transaction(tx.clone()).unwrap();
+        assert!(system.mempool.get(&tx.hash).is_some());
+    }
+
+    #[test]
+    fn test_repropose_expired_change() {
+        let mut system = test_system();
+
+        let data = ProposalData::new("config::network_name".to_owned(), Operation::Remove);
+        let now = chrono::Utc::now();
+
+        // Let a proposal run out of time
+        let expired = Proposal::with_voting_period(
+            "test".to_owned(),
+            data.clone(),
+            now - chrono::Duration::days(2),
+            now - chrono::Duration::days(1),
+        );
+        system.push_proposal(expired.clone());
+        assert_eq!(system.expire_proposals(), vec![expired.proposal_id]);
+        assert_eq!(
+            system.proposal_state(&expired.proposal_id),
+            Some(ProposalState::Expired)
+        );
+
+        // The same change can be proposed again, and is voted on separately
+        let reproposed = Proposal::new("test".to_owned(), data);
+        assert_ne!(reproposed.proposal_id, expired.proposal_id);
+        assert_eq!(system.proposal_state(&reproposed.proposal_id), None);
+
+        system.push_proposal(reproposed.clone());
+        assert_eq!(
+            system.proposal_state(&reproposed.proposal_id),
+            Some(ProposalState::Open)
+        );
+        assert_eq!(
+            system.proposal_state(&expired.proposal_id),
+            Some(ProposalState::Expired)
+        );
+    }
+
+    #[test]
+    fn test_recycled_coins_add_no_weight() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut system = test_system();
+
+        let holder = Keypair::generate(&mut csprng);
+        let accomplice = Keypair::generate(&mut csprng);
+        let bystander = Keypair::generate(&mut csprng);
+        let holder_address = Address::from_key_pair(&holder);
+        let accomplice_address = Address::from_key_pair(&accomplice);
+
+        // The accomplice doesn't own any coins when the proposal is made
+        let snapshot = push_balances(
+            &mut system,
+            &[
+                (holder_address, 100),
+                (Address::from_key_pair(&bystander), 100),
+            ],
+        );
+
+        let proposal = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+        )
+        .with_snapshot(snapshot);
+        let proposal_id = proposal.proposal_id;
+        system.push_proposal(proposal);
+
+        // The holder votes with their coins, and then moves them to the accomplice
+        let now = chrono::Utc::now();
+        system.record_vote(&vote_transaction(&holder, proposal_id, true, now));
+        push_balances(
+            &mut system,
+            &[(holder_address, 0), (accomplice_address, 100)],
+        );
+        assert_eq!(
+            system.ledger.get_balance_of_account(&accomplice_address),
+            BigUint::from(100u64)
+        );
+
+        // The accomplice didn't have any coins at the snapshot, so their vote doesn't add any weight
+        system.record_vote(&vote_transaction(&accomplice, proposal_id, true, now));
+        assert_eq!(
+            system.get_coins_in_support_of(&proposal_id),
+            BigInt::from(100u64)
+        );
+    }
+
+    #[test]
+    fn test_unknown_snapshot_adds_no_weight() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut system = test_system();
+
+        let holder = Keypair::generate(&mut csprng);
+        push_balances(&mut system, &[(Address::from_key_pair(&holder), 100)]);
+
+        // A proposal snapshotted at a node that isn't in the ledger can't be weighed
+        let proposal = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+        )
+        .with_snapshot(Hash::new(vec![1; 32]));
+        let proposal_id = proposal.proposal_id;
+        system.push_proposal(proposal);
+
+        system.record_vote(&vote_transaction(
+            &holder,
+            proposal_id,
+            true,
+            chrono::Utc::now(),
+        ));
+        assert_eq!(system.get_coins_in_support_of(&proposal_id), BigInt::zero());
+    }
+
+    #[test]
+    fn test_stale_snapshot() {
+        let mut system = test_system();
+
+        let snapshot = push_balances(&mut system, &[]);
+        let proposal = |snapshot: Option<Hash>| {
+            let proposal = Proposal::new(
+                "test".to_owned(),
+                ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+            );
+
+            match snapshot {
+                Some(snapshot) => proposal.with_snapshot(snapshot),
+                None => proposal,
+            }
+        };
+
+        // Proposals must be weighed at a recent node that's in the ledger
+        assert!(system.check_snapshot(&proposal(Some(snapshot))).is_ok());
+        assert!(system.check_snapshot(&proposal(None)).is_err());
+        assert!(system
+            .check_snapshot(&proposal(Some(Hash::new(vec![1; 32]))))
+            .is_err());
+
+        // Snapshots that the ledger has moved on from are refused
+        for _ in 0..MAX_SNAPSHOT_DEPTH {
+            push_balances(&mut system, &[]);
+        }
+        assert!(system.check_snapshot(&proposal(Some(snapshot))).is_ok());
+
+        push_balances(&mut system, &[]);
+        assert!(system.check_snapshot(&proposal(Some(snapshot))).is_err());
+    }
+
+    #[test]
+    fn test_delegated_votes() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut system = test_system();
+
+        let delegator = Keypair::generate(&mut csprng);
+        let delegate = Keypair::generate(&mut csprng);
+        let delegator_address = Address::from_key_pair(&delegator);
+        let delegate_address = Address::from_key_pair(&delegate);
+
+        let snapshot = push_balances(
+            &mut system,
+            &[
+                (delegator_address, 40),
+                (delegate_address, 60),
+                (Address::from_key_pair(&Keypair::generate(&mut csprng)), 100),
+            ],
+        );
+
+        // Makes a transaction from the delegator carrying a delegation of its votes to the given account
+        let delegation_to = |delegate: Option<Address>| {
+            let delegation = Delegation::new(
+                delegate,
+                None,
+                Keypair::from_bytes(&delegator.to_bytes()).unwrap(),
+                "olympia",
+            );
+
+            TransactionBuilder::new()
+                .nonce(0)
+                .sender(delegator_address)
+                .recipient(delegate.unwrap_or_else(Address::default))
+                .payload(payload::Payload::delegation(&delegation.encode()))
+                .build()
+                .unwrap()
+        };
+
+        // Accounts can't delegate their votes to themselves
+        system.record_delegation(&delegation_to(Some(delegator_address)), 1);
+        assert_eq!(
+            system.delegate_of(&delegator_address, "config::network_name", 1),
+            None
+        );
+
+        // Delegations take effect from the ledger node that carries them onward
+        system.record_delegation(&delegation_to(Some(delegate_address)), 1);
+        assert_eq!(
+            system.delegate_of(&delegator_address, "config::network_name", 0),
+            None
+        );
+        assert_eq!(
+            system.delegate_of(&delegator_address, "config::network_name", 1),
+            Some(delegate_address)
+        );
+
+        // A revocation appended after the proposal's snapshot doesn't affect the proposal's tally
+        system.record_delegation(&delegation_to(None), 2);
+        assert_eq!(
+            system.delegate_of(&delegator_address, "config::network_name", 2),
+            None
+        );
+
+        let proposal = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+        )
+        .with_snapshot(snapshot);
+        let proposal_id = proposal.proposal_id;
+        system.push_proposal(proposal);
+
+        // The delegate votes with the delegator's coins as well as its own
+        let now = chrono::Utc::now();
+        system.record_vote(&vote_transaction(&delegate, proposal_id, true, now));
+        assert_eq!(
+            system.get_coins_in_support_of(&proposal_id),
+            BigInt::from(100u64)
+        );
+
+        // The delegator overrides its delegate by voting itself
+        system.record_vote(&vote_transaction(&delegator, proposal_id, false, now));
+        assert_eq!(
+            system.get_coins_in_support_of(&proposal_id),
+            BigInt::from(20u64)
+        );
+    }
+
+    #[test]
+    fn test_recorded_votes() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut system = test_system();
+
+        // Changes to the network's name are decided by a simple majority of those who vote on them
+        system.config.approval_thresholds.insert(
+            "config::network_name".to_owned(),
+            Threshold::simple_majority(Share::new(1, 10)),
+        );
+
+        let voter = Keypair::generate(&mut csprng);
+        let bystander = Keypair::generate(&mut csprng);
+        let bystander_address = Address::from_key_pair(&bystander);
+        let snapshot = push_balances(
+            &mut system,
+            &[
+                (Address::from_key_pair(&voter), 30),
+                (bystander_address, 70),
+            ],
+        );
+
+        let now = chrono::Utc::now();
+        let propose = |voting_deadline: chrono::DateTime<chrono::Utc>| {
+            Proposal::with_voting_period(
+                "test".to_owned(),
+                ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+                now - chrono::Duration::hours(2),
+                voting_deadline,
+            )
+            .with_snapshot(snapshot)
+            .with_activation(ActivationPoint::Height(100))
+        };
+
+        // Votes appended to the ledger before the proposal reaches the node still count
+        let proposal = propose(now + chrono::Duration::hours(1));
+        let proposal_id = proposal.proposal_id;
+        system.record_vote(&vote_transaction(
+            &voter,
+            proposal_id,
+            true,
+            now - chrono::Duration::hours(1),
+        ));
+        system.push_proposal(proposal);
+        assert_eq!(
+            system.get_coins_in_support_of(&proposal_id),
+            BigInt::from(30u64)
+        );
+
+        // Each account's first vote is final, and nobody can vote on behalf of anybody else
+        system.record_vote(&vote_transaction(&voter, proposal_id, false, now));
+        let mut forged = vote_transaction(&voter, proposal_id, false, now);
+        forged.transaction_data.sender = bystander_address;
+        system.record_vote(&forged);
+        assert_eq!(
+            system.get_coins_in_support_of(&proposal_id),
+            BigInt::from(30u64)
+        );
+
+        // Votes gossiped between nodes don't count
+        match system.register_vote_for_proposal(
+            proposal_id,
+            &Vote::new(proposal_id, false, bystander, "olympia"),
+        ) {
+            Err(ExecutionError::VoteNotOnLedger { .. }) => (),
+            _ => panic!("gossiped vote was counted"),
+        }
+
+        // Until voting closes, the coins that haven't voted could still reverse the outcome
+        assert_eq!(system.decide(&proposal_id), None);
+
+        // Once voting closes, the proposal is decided by the votes appended before its deadline
+        let closed = propose(now - chrono::Duration::minutes(1));
+        let closed_id = closed.proposal_id;
+        system.record_vote(&vote_transaction(
+            &voter,
+            closed_id,
+            true,
+            now - chrono::Duration::hours(1),
+        ));
+        system.push_proposal(closed);
+        assert!(system.expire_proposals().is_empty());
+        assert_eq!(
+            system.proposal_state(&closed_id),
+            Some(ProposalState::Scheduled)
+        );
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut system = test_system();
+
+        let holder = Keypair::generate(&mut csprng);
+        push_balances(&mut system, &[(Address::from_key_pair(&holder), 100)]);
+
+        // Ordinary transactions only need a simple majority of the stake that votes on them
+        assert_eq!(
+            system.threshold_of("ledger::transactions"),
+            Threshold::simple_majority(Share::new(1, 10))
+        );
+
+        let parameters = ParameterRegistry::default();
+        let propose = |value: &str| {
+            let operation = parameters
+                .get("governance::thresholds")
+                .unwrap()
+                .operation(OperationKind::Amend, Some(value))
+                .unwrap();
+
+            Proposal::new(
+                "test".to_owned(),
+                ProposalData::new("governance::thresholds".to_owned(), operation),
+            )
+        };
+
+        // Lowering the threshold of the reward rate takes a vote that meets the threshold of the thresholds
+        // themselves, as well as both the old and new thresholds of the reward rate
+        let proposal = propose("config::reward_per_gas 1/5 1/2 participating");
+        assert_eq!(
+            system.proposal_threshold(&proposal),
+            Threshold {
+                quorum: Share::new(1, 5),
+                approval: Share::new(3, 4),
+                base: ThresholdBase::Issuance,
+            }
+        );
+
+        let proposal_id = proposal.proposal_id;
+        system.push_proposal(proposal);
+        assert!(system.validate_proposal(&proposal_id));
+
+        // Keep the persisted config from clobbering that of other tests
+        system.config.network_name = "olympia_thresholds".to_owned();
+        system.execute_proposal(proposal_id).unwrap();
+        std::fs::remove_file(io::format_config_dir("network_olympia_thresholds.json")).unwrap();
+
+        assert_eq!(
+            system.threshold_of("config::reward_per_gas"),
+            Threshold::simple_majority(Share::new(1, 5))
+        );
+
+        // Thresholds can only be set for known parameters
+        let proposal = propose("config::unknown 1/5 1/2 participating");
+        let proposal_id = proposal.proposal_id;
+        system.push_proposal(proposal);
+        assert!(!system.validate_proposal(&proposal_id));
+    }
+
+    #[test]
+    fn test_scheduled_changes() {
+        let mut system = test_system();
+        system.config.network_name = "olympia_scheduled".to_owned(); // Keep the persisted config to this test
+
+        let operation = system
+            .parameters
+            .get("config::reward_per_gas")
+            .unwrap()
+            .operation(OperationKind::Amend, Some("2000000"))
+            .unwrap();
+        let proposal = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new("config::reward_per_gas".to_owned(), operation),
+        )
+        .with_activation(ActivationPoint::Height(3));
+        let proposal_id = proposal.proposal_id;
+
+        system.push_proposal(proposal.clone());
+        assert!(system.validate_proposal(&proposal_id));
+
+        // Changes to the rules for executing transactions must say where they take effect
+        let unscheduled = Proposal::new("test".to_owned(), proposal.proposal_data);
+        let unscheduled_id = unscheduled.proposal_id;
+        system.push_proposal(unscheduled);
+        assert!(!system.validate_proposal(&unscheduled_id));
+        assert!(system.execute_proposal(unscheduled_id).is_err());
+        assert_eq!(
+            system.proposal_state(&unscheduled_id),
+            Some(ProposalState::Passed)
+        );
+
+        // The change waits for the ledger to reach its activation point
+        system.execute_proposal(proposal_id).unwrap();
+        assert_eq!(
+            system.proposal_state(&proposal_id),
+            Some(ProposalState::Scheduled)
+        );
+        assert_eq!(
+            system.config.reward_per_gas,
+            BigUint::from(config::DEFAULT_REWARD_PER_GAS)
+        );
+
+        push_balances(&mut system, &[]);
+        assert_eq!(system.activate_scheduled_proposals(), 0);
+
+        push_balances(&mut system, &[]);
+        assert_eq!(system.activate_scheduled_proposals(), 1);
+        assert_eq!(
+            system.proposal_state(&proposal_id),
+            Some(ProposalState::Executed)
+        );
+        assert_eq!(system.config.reward_per_gas, BigUint::from(2_000_000u64));
+
+        // Transactions before the activation point are still executed under the old rules
+        assert_eq!(
+            system.config.epoch_at(2).reward_per_gas,
+            BigUint::from(config::DEFAULT_REWARD_PER_GAS)
+        );
+        assert_eq!(
+            system.config.epoch_at(3).reward_per_gas,
+            BigUint::from(2_000_000u64)
+        );
+        assert_eq!(
+            system
+                .governance_history()
+                .unwrap()
+                .iter()
+                .map(|decision| decision.outcome)
+                .collect::<Vec<ProposalState>>(),
+            vec![
+                ProposalState::Passed,
+                ProposalState::Scheduled,
+                ProposalState::Executed
+            ]
+        );
+
+        // Changes can't take effect retroactively, so those that pass after the ledger has reached their activation
+        // points are rejected
+        for _ in 0..3 {
+            push_balances(&mut system, &[]);
+        }
+        let operation = system
+            .parameters
+            .get("config::stamp_difficulty")
+            .unwrap()
+            .operation(OperationKind::Amend, Some("1"))
+            .unwrap();
+        let data = ProposalData::new("config::stamp_difficulty".to_owned(), operation);
+        let proposal = Proposal::new("test".to_owned(), data.clone())
+            .with_activation(ActivationPoint::Height(4));
+        let proposal_id = proposal.proposal_id;
+        system.push_proposal(proposal.clone());
+        assert!(system.check_schedule(&proposal).is_err());
+        assert!(!system.validate_proposal(&proposal_id));
+        assert!(system.execute_proposal(proposal_id).is_err());
+        assert_eq!(
+            system.proposal_state(&proposal_id),
+            Some(ProposalState::Passed)
+        );
+        assert_eq!(system.config.stamp_difficulty, 0);
+
+        // Nor do scheduled changes reach back to transactions that the ledger already contains
+        let proposal =
+            Proposal::new("test".to_owned(), data).with_activation(ActivationPoint::Height(7));
+        let proposal_id = proposal.proposal_id;
+        system.push_proposal(proposal);
+        system.execute_proposal(proposal_id).unwrap();
+        for _ in 0..2 {
+            push_balances(&mut system, &[]);
+        }
+        assert_eq!(system.activate_scheduled_proposals(), 1);
+        assert_eq!(system.config.epoch_at(7).stamp_difficulty, 0);
+        assert_eq!(system.config.epoch_at(8).stamp_difficulty, 1);
+
+        std::fs::remove_file(io::format_config_dir("network_olympia_scheduled.json")).unwrap();
+
+        // Transactions are appended as soon as they pass, so they can't be scheduled
+        let proposal = Proposal::new(
+            "test".to_owned(),
+            ProposalData::new(
+                "ledger::transactions".to_owned(),
+                Operation::Append {
+                    value_to_append: TransactionBuilder::new()
+                        .nonce(1)
+                        .sender(Address::default())
+                        .recipient(Address::default())
+                        .build()
+                        .unwrap()
+                        .to_bytes(),
+                },
+            ),
+        )
+        .with_activation(ActivationPoint::Height(10));
+        let proposal_id = proposal.proposal_id;
+        system.push_proposal(proposal);
+        assert!(!system.validate_proposal(&proposal_id));
+    }
+
+    #[test]
+    fn test_deposits() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut system = test_system();
+
+        let proposer = Keypair::generate(&mut csprng);
+        let opponent = Keypair::generate(&mut csprng);
+        let proposer_address = Address::from_key_pair(&proposer);
+
+        push_balances(
+            &mut system,
+            &[
+                (proposer_address, 100),
+                (Address::from_key_pair(&opponent), 400),
+            ],
+        );
+
+        // Proposers can't lock more than their balances
+        let snapshot = system.ledger.obtain_executed_head().unwrap().hash;
+        let propose = |deposit: u64| {
+            Proposal::new(
+                "test".to_owned(),
+                ProposalData::new("config::network_name".to_owned(), Operation::Remove),
+            )
+            .with_snapshot(snapshot)
+            .with_deposit(BigUint::from(deposit))
+            .sign(&proposer, "olympia")
+        };
+        assert!(system.check_deposit(&propose(101)).is_err());
+
+        let proposal = propose(30);
+        let proposal_id = proposal.proposal_id;
+        assert!(system.check_deposit(&proposal).is_ok());
+        system.push_proposal(proposal);
+
+        assert_eq!(
+            system.locked_balances().get(&proposer_address),
+            Some(&BigUint::from(30u64))
+        );
+
+        // The deposit is burned once the ledger records an overwhelming rejection of the proposal
+        system.record_vote(&vote_transaction(
+            &opponent,
+            proposal_id,
+            false,
+            chrono::Utc::now(),
+        ));
+
+        assert!(system.pending_proposals.is_empty());
+        assert_eq!(
+            system
+                .governance_history()
+                .unwrap()
+                .iter()
+                .map(|decision| (decision.proposal_id, decision.outcome))
+                .collect::<Vec<_>>(),
+            vec![(proposal_id, ProposalState::Rejected)]
+        );
+        assert_eq!(
+            system.locked_balances().get(&proposer_address),
+            Some(&BigUint::from(30u64))
+        );
+        assert_eq!(
+            system.voting_balance_of(&proposer_address, &snapshot),
+            BigUint::from(70u64)
+        );
     }
 }
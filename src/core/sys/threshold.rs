@@ -0,0 +1,435 @@
+use num::{bigint::BigUint, Zero}; // Add support for large unsigned integers
+
+use serde::{Deserialize, Serialize}; // Import serde serialization
+
+use super::super::types::encoding::Canonical; // Import the canonical encoding
+
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// A fraction of some amount of stake (e.g. 2/3).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Share {
+    /// The numerator of the fraction
+    pub numerator: u32,
+
+    /// The denominator of the fraction
+    pub denominator: u32,
+}
+
+impl Share {
+    /// Initializes a new share.
+    ///
+    /// # Arguments
+    ///
+    /// * `numerator` - The numerator of the fraction
+    /// * `denominator` - The denominator of the fraction
+    pub fn new(numerator: u32, denominator: u32) -> Self {
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Initializes a share of nothing at all, which any amount of stake reaches.
+    pub fn none() -> Self {
+        Self::new(0, 1)
+    }
+
+    /// Checks whether or not the share is a fraction between 0 and 1 (inclusive).
+    pub fn is_valid(self) -> bool {
+        self.denominator != 0 && self.numerator <= self.denominator
+    }
+
+    /// Gets the share of the total that this share leaves over (e.g. 1/3 for 2/3).
+    pub fn complement(self) -> Self {
+        Self::new(
+            self.denominator.saturating_sub(self.numerator),
+            self.denominator,
+        )
+    }
+
+    /// Checks whether or not the given part of the given total makes up at least this share of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `part` - The part of the total
+    /// * `total` - The total
+    pub fn reached_by(self, part: &BigUint, total: &BigUint) -> bool {
+        part * BigUint::from(self.denominator) >= total * BigUint::from(self.numerator)
+    }
+
+    /// Checks whether or not the given part of the given total makes up more than this share of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `part` - The part of the total
+    /// * `total` - The total
+    pub fn exceeded_by(self, part: &BigUint, total: &BigUint) -> bool {
+        part * BigUint::from(self.denominator) > total * BigUint::from(self.numerator)
+    }
+
+    /// Gets the larger of the two shares.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The share that this share should be compared to
+    pub fn max(self, other: Self) -> Self {
+        match (u64::from(self.numerator) * u64::from(other.denominator))
+            .cmp(&(u64::from(other.numerator) * u64::from(self.denominator)))
+        {
+            Ordering::Less => other,
+            _ => self,
+        }
+    }
+}
+
+impl fmt::Display for Share {
+    /// Formats the share as a fraction (e.g. 2/3).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for Share {
+    type Err = String;
+
+    /// Parses a share written as a fraction (e.g. 2/3).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '/');
+
+        let (numerator, denominator) = match (parts.next(), parts.next()) {
+            (Some(numerator), Some(denominator)) => (numerator, denominator),
+            _ => return Err(format!("expected a fraction (e.g. 2/3), found {}", s)),
+        };
+
+        let share = Self::new(
+            u32::from_str(numerator.trim()).map_err(|e| e.to_string())?,
+            u32::from_str(denominator.trim()).map_err(|e| e.to_string())?,
+        );
+
+        if share.is_valid() {
+            Ok(share)
+        } else {
+            Err(format!("{} isn't a fraction between 0 and 1", share))
+        }
+    }
+}
+
+/// The stake against which the approval of a proposal is measured.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ThresholdBase {
+    /// The stake of the accounts that voted on the proposal
+    Participating,
+    /// Each of the coins in circulation
+    Issuance,
+}
+
+impl fmt::Display for ThresholdBase {
+    /// Formats the base according to its name (i.e. participating, issuance).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Participating => "participating",
+                Self::Issuance => "issuance",
+            }
+        )
+    }
+}
+
+impl FromStr for ThresholdBase {
+    type Err = String;
+
+    /// Parses a base from its name (i.e. participating, issuance).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "participating" => Ok(Self::Participating),
+            "issuance" => Ok(Self::Issuance),
+            _ => Err(format!("unknown threshold base: {}", s)),
+        }
+    }
+}
+
+/// The conditions under which a proposal is decided: the share of the coins in circulation that must vote on it
+/// (its quorum), and the share of the base stake that must vote in favor of it for it to pass.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Threshold {
+    /// The share of the coins in circulation that must vote on a proposal before it can be decided
+    pub quorum: Share,
+
+    /// The share of the base stake that the votes in favor of a proposal must exceed for it to pass
+    pub approval: Share,
+
+    /// The stake against which the approval of a proposal is measured
+    pub base: ThresholdBase,
+}
+
+impl Threshold {
+    /// Initializes a threshold requiring a simple majority of the stake participating in a vote, once the given
+    /// share of the coins in circulation has voted.
+    ///
+    /// # Arguments
+    ///
+    /// * `quorum` - The share of the coins in circulation that must vote on a proposal
+    pub fn simple_majority(quorum: Share) -> Self {
+        Self {
+            quorum,
+            approval: Share::new(1, 2),
+            base: ThresholdBase::Participating,
+        }
+    }
+
+    /// Initializes a threshold requiring votes in favor from more than the given share of the coins in circulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `approval` - The share of the coins in circulation that must vote in favor of a proposal
+    pub fn of_issuance(approval: Share) -> Self {
+        Self {
+            quorum: Share::none(),
+            approval,
+            base: ThresholdBase::Issuance,
+        }
+    }
+
+    /// Checks whether or not the threshold can be met: each of its shares must be a fraction between 0 and 1, and
+    /// its approval share must be less than 1.
+    pub fn is_valid(self) -> bool {
+        self.quorum.is_valid()
+            && self.approval.is_valid()
+            && self.approval.numerator < self.approval.denominator
+    }
+
+    /// Gets the threshold that a proposal meets only if it meets both this threshold and the given one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The threshold that should be combined with this one
+    pub fn stricter(self, other: Self) -> Self {
+        Self {
+            quorum: self.quorum.max(other.quorum),
+            approval: self.approval.max(other.approval),
+            base: if self.base == ThresholdBase::Issuance || other.base == ThresholdBase::Issuance {
+                ThresholdBase::Issuance
+            } else {
+                ThresholdBase::Participating
+            },
+        }
+    }
+
+    /// Decides a proposal according to the weight of the votes cast on it: returns true if the proposal has passed,
+    /// false if it can no longer pass, or none if it hasn't been decided yet. Until voting closes, a proposal is only
+    /// decided once the coins that haven't voted yet can no longer change the outcome, however they vote.
+    ///
+    /// # Arguments
+    ///
+    /// * `in_favor` - The total weight of the votes in favor of the proposal
+    /// * `against` - The total weight of the votes against the proposal
+    /// * `issuance` - The number of coins in circulation
+    /// * `closed` - Whether or not the proposal's voting deadline has passed
+    pub fn decide(
+        self,
+        in_favor: &BigUint,
+        against: &BigUint,
+        issuance: &BigUint,
+        closed: bool,
+    ) -> Option<bool> {
+        let participating = in_favor + against;
+
+        // Nothing can be decided until enough of the coins in circulation have voted
+        if participating.is_zero() || !self.quorum.reached_by(&participating, issuance) {
+            return None;
+        }
+
+        // Any of the coins that haven't voted could still join a vote among those participating, so the outcome of
+        // such a vote is only fixed once it holds against each of the coins in circulation, or once voting closes
+        let base = match self.base {
+            ThresholdBase::Participating if closed => &participating,
+            _ => issuance,
+        };
+
+        if self.approval.exceeded_by(in_favor, base) {
+            Some(true)
+        } else if self.approval.complement().reached_by(against, base) {
+            // The votes in favor can no longer exceed the approval share
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Threshold {
+    /// Initializes a threshold requiring votes in favor from more than half of the coins in circulation.
+    fn default() -> Self {
+        Self::of_issuance(Share::new(1, 2))
+    }
+}
+
+impl fmt::Display for Threshold {
+    /// Formats the threshold in the same manner as it is parsed (e.g. 1/10 1/2 participating).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.quorum, self.approval, self.base)
+    }
+}
+
+impl FromStr for Threshold {
+    type Err = String;
+
+    /// Parses a threshold written as its quorum, approval share and base (e.g. 1/10 1/2 participating).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+
+        if parts.len() != 3 {
+            return Err(format!(
+                "expected a quorum, approval share and base (e.g. 1/10 1/2 participating), found {}",
+                s
+            ));
+        }
+
+        let threshold = Self {
+            quorum: Share::from_str(parts[0])?,
+            approval: Share::from_str(parts[1])?,
+            base: ThresholdBase::from_str(parts[2])?,
+        };
+
+        if threshold.is_valid() {
+            Ok(threshold)
+        } else {
+            Err(format!(
+                "approval share {} can never be exceeded",
+                threshold.approval
+            ))
+        }
+    }
+}
+
+/// The threshold that proposals targeting a parameter must meet, as carried by a proposal to change it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ParameterThreshold {
+    /// The name of the parameter
+    pub param_name: String,
+
+    /// The threshold that proposals targeting the parameter must meet
+    pub threshold: Threshold,
+}
+
+/// Threshold changes are carried by proposals in their canonical encoding.
+impl Canonical for ParameterThreshold {}
+
+impl FromStr for ParameterThreshold {
+    type Err = String;
+
+    /// Parses the name of a parameter followed by a threshold (e.g. config::reward_per_gas 1/10 2/3 issuance).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(2, char::is_whitespace);
+
+        match (parts.next(), parts.next()) {
+            (Some(param_name), Some(threshold)) => Ok(Self {
+                param_name: param_name.to_owned(),
+                threshold: Threshold::from_str(threshold)?,
+            }),
+            _ => Err(format!(
+                "expected a parameter name followed by a threshold, found {}",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide() {
+        let issuance = BigUint::from(100u64);
+        let decide = |threshold: Threshold, in_favor: u64, against: u64| {
+            threshold.decide(
+                &BigUint::from(in_favor),
+                &BigUint::from(against),
+                &issuance,
+                false,
+            )
+        };
+        let decide_closed = |threshold: Threshold, in_favor: u64, against: u64| {
+            threshold.decide(
+                &BigUint::from(in_favor),
+                &BigUint::from(against),
+                &issuance,
+                true,
+            )
+        };
+
+        // A simple majority is decided by those who vote once voting closes, provided that the quorum was reached
+        let majority = Threshold::simple_majority(Share::new(1, 10));
+        assert_eq!(decide_closed(majority, 5, 0), None);
+        assert_eq!(decide_closed(majority, 6, 4), Some(true));
+        assert_eq!(decide_closed(majority, 5, 5), Some(false));
+
+        // Until then, the coins that haven't voted yet could still reverse the outcome
+        assert_eq!(decide(majority, 6, 4), None);
+        assert_eq!(decide(majority, 5, 5), None);
+        assert_eq!(decide(majority, 51, 0), Some(true));
+        assert_eq!(decide(majority, 0, 50), Some(false));
+
+        // A supermajority of issuance is decided once the outcome can no longer change
+        let supermajority = Threshold::of_issuance(Share::new(2, 3));
+        assert_eq!(decide(supermajority, 60, 0), None);
+        assert_eq!(decide(supermajority, 67, 0), Some(true));
+        assert_eq!(decide(supermajority, 60, 33), None);
+        assert_eq!(decide(supermajority, 60, 34), Some(false));
+        assert_eq!(decide_closed(supermajority, 60, 33), None);
+
+        // Nothing is decided without any votes
+        assert_eq!(
+            decide(Threshold::simple_majority(Share::none()), 0, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_stricter() {
+        let majority = Threshold::simple_majority(Share::new(1, 10));
+        let supermajority = Threshold::of_issuance(Share::new(2, 3));
+
+        assert_eq!(
+            majority.stricter(supermajority),
+            Threshold {
+                quorum: Share::new(1, 10),
+                approval: Share::new(2, 3),
+                base: ThresholdBase::Issuance,
+            }
+        );
+        assert_eq!(
+            majority.stricter(supermajority),
+            supermajority.stricter(majority)
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let change =
+            ParameterThreshold::from_str("config::reward_per_gas 1/10 2/3 issuance").unwrap();
+        assert_eq!(change.param_name, "config::reward_per_gas");
+        assert_eq!(
+            change.threshold,
+            Threshold {
+                quorum: Share::new(1, 10),
+                approval: Share::new(2, 3),
+                base: ThresholdBase::Issuance,
+            }
+        );
+        assert_eq!(
+            Threshold::from_str(&change.threshold.to_string()),
+            Ok(change.threshold)
+        );
+
+        // Thresholds that can never be met aren't accepted
+        assert!(Threshold::from_str("1/10 1/1 issuance").is_err());
+        assert!(Threshold::from_str("1/10 3/2 participating").is_err());
+        assert!(Threshold::from_str("1/10 1/0 participating").is_err());
+        assert!(ParameterThreshold::from_str("config::reward_per_gas").is_err());
+    }
+}
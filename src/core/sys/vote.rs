@@ -1,17 +1,17 @@
 use ed25519_dalek; // Import the edwards25519 digital signature library
 
-use bincode; // Import serde bincode
 use serde::{Deserialize, Serialize}; // Import serde serialization
-use crate::ed25519_dalek::Signer;
 
 use super::super::super::{
     common::address::Address,
-    crypto::{
-        blake3,
-        hash::{self, Hash as HashPrim},
-    },
+    crypto::hash::{self, Hash as HashPrim},
 }; // Import the hash primitive
-use super::super::types::signature; // Import the signature primitive
+use super::super::types::{
+    encoding::Canonical,
+    payload::ContentType,
+    signature::{self, Domain, SigningContext},
+    transaction::Transaction,
+}; // Import the signature primitive, canonical encoding & transaction types
 
 use std::fmt;
 
@@ -26,13 +26,24 @@ pub struct Vote {
     pub signature: Option<signature::Signature>,
 }
 
+/// Votes are hashed and shipped between nodes in their canonical encoding.
+impl Canonical for Vote {}
+
 /// Implement a set of voting helper methods.
 impl Vote {
     /// Initialize and sign a new vote instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The hash of the proposal that the vote is targeting
+    /// * `in_favor` - Whether the voter is in favor of the proposal or not
+    /// * `signature_keypair` - The keypair of the voter
+    /// * `network` - The name of the network on which the vote is being cast
     pub fn new(
         proposal_id: hash::Hash,
         in_favor: bool,
         signature_keypair: ed25519_dalek::Keypair,
+        network: &str,
     ) -> Vote {
         let mut vote: Vote = Vote {
             target_proposal: proposal_id, // Set proposal ID
@@ -40,12 +51,10 @@ impl Vote {
             signature: None,              // No signature yet
         }; // Initialize vote
 
-        // Serialize vote
-        vote.signature = Some(signature::Signature {
-            public_key_bytes: bincode::serialize(&signature_keypair.public).unwrap_or_default(),
-            signature_bytes: bincode::serialize(&signature_keypair.sign(&*vote.hash()))
-                .unwrap_or_default(),
-        }); // Set signature
+        // Sign the vote's hash, in the context of a vote on the given network
+        vote.signature = Some(
+            SigningContext::new(Domain::Vote, network).sign(&signature_keypair, &*vote.hash()),
+        ); // Set signature
 
         vote // Return initialized vote
     }
@@ -60,13 +69,13 @@ impl Vote {
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let acc: Account = Account::new();
-    /// let v: Vote = Vote::new(blake3::hash_slice(b"test"), true, acc.keypair()?);
+    /// let v: Vote = Vote::new(blake3::hash_slice(b"test"), true, acc.keypair()?, "olympia");
     ///
-    /// assert_eq!(v.valid(), true);
+    /// assert_eq!(v.valid("olympia"), true);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn valid(&self) -> bool {
+    pub fn valid(&self, network: &str) -> bool {
         // Ensure that the vote has a signature attached to it
         let sig = if let Some(signature) = &self.signature {
             signature
@@ -76,7 +85,58 @@ impl Vote {
         };
 
         // Ensure that the signature is valid, considering the vote's hash
-        sig.verify(&*self.hash())
+        sig.verify(&SigningContext::new(Domain::Vote, network), &*self.hash())
+    }
+
+    /// Ensures that the signatures associated with each of the provided votes are authentic, verifying them in a
+    /// batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `votes` - The votes whose signatures should be verified
+    /// * `network` - The name of the network on which the votes should have been cast
+    pub fn verify_batch(
+        votes: &[Vote],
+        network: &str,
+    ) -> Result<(), signature::BatchVerificationError> {
+        let mut invalid_indices: Vec<usize> = Vec::new();
+
+        // The signed votes, and the index of each such vote
+        let mut indices: Vec<usize> = Vec::new();
+        let mut signatures: Vec<&signature::Signature> = Vec::new();
+        let mut hashes: Vec<HashPrim> = Vec::new();
+
+        for (i, vote) in votes.iter().enumerate() {
+            // The vote must be invalid if it doesn't even have a signature
+            if let Some(sig) = &vote.signature {
+                indices.push(i);
+                signatures.push(sig);
+                hashes.push(vote.hash());
+            } else {
+                invalid_indices.push(i);
+            }
+        }
+
+        let messages: Vec<&[u8]> = hashes.iter().map(|hash| &hash[..]).collect();
+
+        // Translate the indices of any invalid signatures back into indices of votes
+        if let Err(signature::BatchVerificationError::InvalidSignatures {
+            invalid_indices: invalid_signatures,
+        }) = signature::verify_batch(
+            &SigningContext::new(Domain::Vote, network),
+            &signatures,
+            &messages,
+        ) {
+            invalid_indices.extend(invalid_signatures.into_iter().map(|i| indices[i]));
+        }
+
+        if invalid_indices.is_empty() {
+            Ok(())
+        } else {
+            invalid_indices.sort_unstable();
+
+            Err(signature::BatchVerificationError::InvalidSignatures { invalid_indices })
+        }
     }
 
     /// Hashes the contents of the vote, excluding any signature.
@@ -86,7 +146,7 @@ impl Vote {
         to_be_hashed.signature = None;
 
         // Hash the vote's contents
-        self::blake3::hash_slice(&bincode::serialize(&to_be_hashed).unwrap_or_default())
+        to_be_hashed.canonical_hash()
     }
 
     /// Derives an address from the signature associated with the vote.
@@ -99,6 +159,34 @@ impl Vote {
             None
         }
     }
+
+    /// Gets the vote carried by the given transaction, if the transaction's payload is a vote. Votes can only be
+    /// cast by the transaction's sender.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction whose payload may contain a vote
+    /// * `network` - The name of the network on which the vote should have been cast
+    pub fn from_transaction(tx: &Transaction, network: &str) -> Option<Result<Vote, String>> {
+        if tx.transaction_data.payload.content_type != ContentType::Vote {
+            return None;
+        }
+
+        Some(
+            Vote::decode(&tx.transaction_data.payload.data)
+                .map_err(|e| e.to_string())
+                .and_then(|vote| {
+                    if !vote.valid(network) {
+                        return Err("signature is invalid".to_owned());
+                    }
+                    if vote.voter_address() != Some(tx.transaction_data.sender) {
+                        return Err("not cast by the transaction's sender".to_owned());
+                    }
+
+                    Ok(vote)
+                }),
+        )
+    }
 }
 
 impl fmt::Display for Vote {
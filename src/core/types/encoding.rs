@@ -0,0 +1,226 @@
+use serde::{de::DeserializeOwned, Serialize}; // Import serde serialization
+
+use super::super::super::crypto::{blake3, hash::Hash}; // Import the hash modules
+
+/// The version of the canonical encoding produced by this node.
+///
+/// Version 1 encodes a value as a single version byte, followed by its bincode representation: integers are
+/// fixed-width and little-endian, sequences and strings are prefixed with their length as a u64, enum variants are
+/// prefixed with their index as a u32, and options are prefixed with a 0 (none) or 1 (some) byte. Struct fields are
+/// encoded in declaration order, so any change to a canonically-encoded type must be accompanied by a new version of
+/// that type's encoding (see `Canonical::VERSION`).
+pub const CURRENT_VERSION: u8 = 1;
+
+/// An error encountered while decoding a canonically-encoded value.
+#[derive(Debug, Fail)]
+pub enum EncodingError {
+    #[fail(display = "the encoded value is empty")]
+    Empty,
+    #[fail(display = "unsupported encoding version: {}", version)]
+    UnsupportedVersion {
+        version: u8, // The version of the encoding
+    },
+    #[fail(display = "malformed version {} encoding: {}", version, error)]
+    Malformed {
+        version: u8,   // The version of the encoding
+        error: String, // The error
+    },
+}
+
+/// A type with a deterministic, versioned binary encoding, from which its hash is derived.
+pub trait Canonical: Serialize + DeserializeOwned {
+    /// The version of the canonical encoding produced for the type. Types whose layout changes must increment their
+    /// version, and migrate values encoded with older versions in `decode_version`.
+    const VERSION: u8 = CURRENT_VERSION;
+
+    /// Encodes the value according to the type's current version of the canonical encoding.
+    fn encode(&self) -> Vec<u8> {
+        let mut encoded = vec![Self::VERSION];
+        encoded.append(&mut bincode::serialize(self).unwrap_or_default());
+
+        encoded
+    }
+
+    /// Decodes a value encoded with a version of the canonical encoding that the type is able to decode.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The encoded value, including its version byte
+    fn decode(b: &[u8]) -> Result<Self, EncodingError> {
+        match b.split_first() {
+            Some((version, body)) if (CURRENT_VERSION..=Self::VERSION).contains(version) => {
+                Self::decode_version(*version, body)
+            }
+            Some((version, _)) => Err(EncodingError::UnsupportedVersion { version: *version }),
+            None => Err(EncodingError::Empty),
+        }
+    }
+
+    /// Decodes the body of a value encoded with the given version of the canonical encoding. Types whose layout
+    /// changes between versions should override this to migrate values encoded with older versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the encoding
+    /// * `body` - The encoded value, excluding its version byte
+    fn decode_version(version: u8, body: &[u8]) -> Result<Self, EncodingError> {
+        decode_body(version, body)
+    }
+
+    /// Hashes the canonical encoding of the value.
+    fn canonical_hash(&self) -> Hash {
+        blake3::hash_slice(&self.encode())
+    }
+}
+
+/// Decodes the body of a value encoded with the given version of the canonical encoding, assuming that the value's
+/// layout is that of the given type.
+///
+/// # Arguments
+///
+/// * `version` - The version of the encoding
+/// * `body` - The encoded value, excluding its version byte
+pub fn decode_body<T: Serialize + DeserializeOwned>(
+    version: u8,
+    body: &[u8],
+) -> Result<T, EncodingError> {
+    let malformed = |error: String| EncodingError::Malformed { version, error };
+
+    let value: T = bincode::deserialize(body).map_err(|e| malformed(e.to_string()))?;
+
+    // Refuse trailing bytes, since they would allow a single value to have several encodings
+    if bincode::serialized_size(&value).map_err(|e| malformed(e.to_string()))? != body.len() as u64
+    {
+        return Err(malformed(
+            "trailing bytes after the encoded value".to_owned(),
+        ));
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // Import names from the parent module
+
+    use super::super::{
+        super::sys::{
+            proposal::{Operation, Proposal, ProposalData},
+            vote::Vote,
+        },
+        payload::Payload,
+        transaction::{Transaction, TransactionData},
+    }; // Import the canonically-encoded types
+
+    use chrono::{TimeZone, Utc}; // Import the time library
+
+    use num::bigint::BigUint; // Add support for large unsigned integers
+
+    impl Canonical for (u64, String) {}
+
+    /// Gets a transaction whose contents never change, so that its encoding can be locked down.
+    fn golden_transaction() -> Transaction {
+        let transaction_data = TransactionData {
+            nonce: 1,
+            sender: Hash::new(vec![1; 32]),
+            recipient: Hash::new(vec![2; 32]),
+            value: BigUint::from(1_000_000u64),
+            payload: Payload::memo("golden"),
+            parents: vec![Hash::new(vec![3; 32])],
+            parent_receipts: None,
+            parent_state_hash: Some(Hash::new(vec![4; 32])),
+            timestamp: Utc.timestamp(1_600_000_000, 0),
+            not_before: None,
+            after: None,
+        }; // Initialize the transaction's contents
+
+        Transaction {
+            hash: transaction_data.canonical_hash(),
+            transaction_data,
+            signature: None,
+            deployed_contract_address: None,
+            contract_creation: false,
+            genesis: false,
+            stamp: None,
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let value = (1u64, "test".to_owned()); // Make a value to encode
+        let encoded = value.encode(); // Encode the value
+
+        assert_eq!(encoded[0], CURRENT_VERSION); // Ensure the encoding is prefixed with its version
+        assert_eq!(<(u64, String)>::decode(&encoded).unwrap(), value); // Ensure the value survives a round trip
+
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert!(<(u64, String)>::decode(&trailing).is_err()); // Ensure trailing bytes are rejected
+
+        let mut unsupported = encoded;
+        unsupported[0] = 0;
+        assert!(<(u64, String)>::decode(&unsupported).is_err()); // Ensure unknown versions are rejected
+        unsupported[0] = 2;
+        assert!(<(u64, String)>::decode(&unsupported).is_err()); // Ensure versions newer than the type's are rejected
+        assert!(<(u64, String)>::decode(&[]).is_err()); // Ensure empty encodings are rejected
+    }
+
+    #[test]
+    fn test_golden_vectors() {
+        let tx = golden_transaction(); // Get the golden transaction
+
+        assert_eq!(
+            tx.transaction_data.canonical_hash().to_str(),
+            "pdMwqmTd16Z1w1tgoX41kqAtYm18X17H849te4yDqmZ"
+        ); // Ensure the transaction's hash hasn't changed
+        assert_eq!(
+            tx.canonical_hash().to_str(),
+            "3uHe82ULyiQVFntUMVjCdfnpqKZoH7qssRtQaQWr6RA9"
+        ); // Ensure the transaction's encoding hasn't changed
+
+        let vote = Vote {
+            target_proposal: Hash::new(vec![5; 32]),
+            in_favor: true,
+            signature: None,
+        }; // Initialize an unsigned vote
+
+        // Lock down the exact bytes of the vote's encoding
+        let mut expected = vec![1]; // The version of the encoding
+        expected.extend_from_slice(&[43, 0, 0, 0, 0, 0, 0, 0]); // The length of the base58-encoded target proposal
+        expected.extend_from_slice(b"LbUiWL3xVV8hTFYBVdbTNrpDo41NKS6o3LHHuDzjfcY"); // The target proposal
+        expected.push(1); // In favor
+        expected.push(0); // No signature
+        assert_eq!(vote.encode(), expected); // Ensure the vote's encoding hasn't changed
+        assert_eq!(
+            Vote::decode(&expected).unwrap().target_proposal,
+            vote.target_proposal
+        ); // Ensure the fixed encoding can be decoded
+
+        let proposal = Proposal::with_voting_period(
+            "golden".to_owned(),
+            ProposalData::new(
+                "ledger::transactions".to_owned(),
+                Operation::Append {
+                    value_to_append: tx.encode(),
+                },
+            ),
+            Utc.timestamp(1_600_000_000, 0),
+            Utc.timestamp(1_600_086_400, 0),
+        )
+        .with_snapshot(tx.hash); // Initialize a proposal to append the golden transaction
+        assert_eq!(
+            proposal.proposal_id.to_str(),
+            "jqmF22N8SC3YzJauhER7ftjLYuhMUwZCAcpR4ySpCEX"
+        ); // Ensure the proposal's ID hasn't changed
+        assert_eq!(
+            proposal.canonical_hash().to_str(),
+            "8s2pL3yXk9qpgPhRWzavkzGapieDR5Pt5NArZTsysaiB"
+        ); // Ensure the proposal's encoding hasn't changed
+
+        assert_eq!(Transaction::decode(&tx.encode()).unwrap().hash, tx.hash); // Ensure the transaction survives a round trip
+        assert_eq!(
+            Proposal::decode(&proposal.encode()).unwrap().proposal_id,
+            proposal.proposal_id
+        ); // Ensure the proposal survives a round trip
+    }
+}
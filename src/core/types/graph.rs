@@ -1,3 +1,6 @@
+use super::encoding::{Canonical, EncodingError}; // Import the canonical encoding
+use super::logs::IndexedLog; // Import the indexed log type
+use super::receipt::Receipt; // Import the receipt type
 use super::state::{self, Entry}; // Import state module
 use super::transaction; // Import transaction types
 
@@ -11,6 +14,15 @@ use super::super::super::{
     crypto::hash::{self, Hash},
 }; // Import address, hash types
 
+/// The name of the tree in which the receipt of each executed transaction is stored.
+pub const RECEIPTS_TREE: &str = "receipts";
+
+/// The name of the tree in which each emitted log is indexed by its topics.
+pub const LOGS_TREE: &str = "logs";
+
+/// The greatest number of ledger indexes that a single query for logs may span.
+pub const MAX_LOG_RANGE: u64 = 1024;
+
 /// An error encountered while signing a tx.
 #[derive(Debug, Fail)]
 pub enum OperationError {
@@ -31,6 +43,34 @@ pub enum OperationError {
     },
 }
 
+/// An error encountered while querying the logs emitted by executed transactions.
+#[derive(Debug, Fail)]
+pub enum LogQueryError {
+    #[fail(
+        display = "the range of ledger indexes {} to {} spans more than {} indexes",
+        from, to, limit
+    )]
+    RangeTooLarge {
+        from: u64,  // The first index in the range
+        to: u64,    // The last index in the range
+        limit: u64, // The greatest number of indexes that a range may span
+    },
+    #[fail(display = "failed to read the log index: {}", error)]
+    StorageFailure {
+        error: String, // The error
+    },
+}
+
+/// Implement conversion from a sled error for the LogQueryError enum.
+impl From<sled::Error> for LogQueryError {
+    /// Converts the given sled error into a LogQueryError.
+    fn from(e: sled::Error) -> Self {
+        Self::StorageFailure {
+            error: e.to_string(),
+        }
+    }
+}
+
 /// A node in any particular state-entry/transaction-based DAG.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Node {
@@ -42,6 +82,27 @@ pub struct Node {
     pub hash: hash::Hash,
 }
 
+/// The layout of a node in releases that predate the canonical encoding, when nodes were persisted as raw bincode.
+#[derive(Serialize, Deserialize)]
+struct LegacyNode {
+    transaction: transaction::LegacyTransaction,
+    state_entry: Option<state::LegacyEntry>,
+    hash: hash::Hash,
+}
+
+impl From<LegacyNode> for Node {
+    fn from(node: LegacyNode) -> Self {
+        Self {
+            transaction: node.transaction.into(),
+            state_entry: node.state_entry.map(Entry::from),
+            hash: node.hash,
+        }
+    }
+}
+
+/// Nodes are persisted in their canonical encoding.
+impl Canonical for Node {}
+
 /// A generic DAG used to store state entries, as well as transactions.
 #[derive(Clone)]
 pub struct Graph {
@@ -162,20 +223,26 @@ impl Node {
     ///
     /// let node = graph::Node::new(tx, None); // Initialize node
     ///
-    /// let is_valid = node.perform_validity_checks(); // False, since state entry is None
+    /// let is_valid = node.perform_validity_checks("olympia"); // False, since state entry is None
     /// ```
-    pub fn perform_validity_checks(&self) -> bool {
-        self.verify_contents() && self.transaction.verify_signature() // Verify contents of self
+    pub fn perform_validity_checks(&self, network: &str) -> bool {
+        self.verify_contents() && self.transaction.verify_signature(network) // Verify contents of self
     }
 
-    /// Serialize a graph node instance to vector.
+    /// Serialize a graph node instance to vector via its canonical encoding.
     pub fn to_bytes(&self) -> Vec<u8> {
-        bincode::serialize(&self).unwrap() // Serialize self
+        self.encode() // Serialize self
     }
 
-    /// Deserialize a graph node instance from a vector.
-    pub fn from_bytes(b: &[u8]) -> Node {
-        bincode::deserialize(b).unwrap()
+    /// Deserialize a graph node instance from a vector produced by its canonical encoding, or by a release that
+    /// persisted nodes as raw bincode.
+    pub fn from_bytes(b: &[u8]) -> Result<Node, EncodingError> {
+        Node::decode(b).or_else(|e| {
+            // Fall back to the layout of older releases
+            bincode::deserialize::<LegacyNode>(b)
+                .map(Node::from)
+                .map_err(|_| e)
+        })
     }
 }
 
@@ -265,12 +332,13 @@ impl Graph {
     /// ```
     pub fn new_with_db_path(root_transaction: transaction::Transaction, db_path: &str) -> Graph {
         let root_transaction_hash = root_transaction.hash; // Clone transaction hash
-        let root_transaction_state_entry = root_transaction.execute(None); // Execute root transaction
+        let (root_transaction_state_entry, root_receipt) =
+            root_transaction.execute_with_receipt(None); // Execute root transaction
 
         let mut hash_routes = collections::HashMap::new(); // Initialize address routes map
         hash_routes.insert(root_transaction_hash, 0); // Set root transaction route
 
-        Graph {
+        let graph = Graph {
             nodes: vec![Node {
                 transaction: root_transaction,                   // Set transaction
                 state_entry: Some(root_transaction_state_entry), // Set state entry
@@ -279,7 +347,11 @@ impl Graph {
             hash_routes,                                // Set address routes
             node_children: collections::HashMap::new(), // Set node children
             db: Some(sled::open(db_path).unwrap()),     // Set db
-        } // Return initialized dag
+        }; // Initialize the dag
+
+        graph.record_receipt(0, &root_receipt).unwrap(); // Store the root transaction's receipt
+
+        graph // Return initialized dag
     }
 
     /// Push a new item to the graph.
@@ -417,7 +489,8 @@ impl Graph {
                         // Success!
                         Some(bytes_encoded_node) => {
                             let deserialized_node: Node =
-                                Node::from_bytes(&bytes_encoded_node.to_vec()[..]); // Deserialize node
+                                Node::from_bytes(&bytes_encoded_node.to_vec()[..])
+                                    .map_err(|e| sled::Error::Unsupported(e.to_string()))?; // Deserialize node
                             node.state_entry = deserialized_node.state_entry; // Set state entry
 
                             return Ok(Some(node)); // Return deserialized node
@@ -486,7 +559,8 @@ impl Graph {
                         // Success!
                         Some(bytes_encoded_node) => {
                             let deserialized_node: Node =
-                                Node::from_bytes(&bytes_encoded_node.to_vec()[..]); // Deserialize node
+                                Node::from_bytes(&bytes_encoded_node.to_vec()[..])
+                                    .map_err(|e| sled::Error::Unsupported(e.to_string()))?; // Deserialize node
                             node.state_entry = deserialized_node.state_entry; // Set state entry
 
                             return Ok(Some(node)); // Return deserialized node
@@ -551,6 +625,19 @@ impl Graph {
         }
     }
 
+    /// Gets the newest timestamp among the transactions with the given hashes that are in the graph, if there are any.
+    ///
+    /// # Arguments
+    ///
+    /// * `hashes` - The hashes of the transactions whose timestamps should be compared
+    pub fn latest_timestamp(&self, hashes: &[Hash]) -> Option<chrono::DateTime<chrono::Utc>> {
+        hashes
+            .iter()
+            .filter_map(|hash| self.get_with_hash(*hash).ok())
+            .map(|node| node.transaction.transaction_data.timestamp)
+            .max()
+    }
+
     /// Get the last recorded balance of the provided account.
     ///
     /// # Arguments
@@ -575,6 +662,43 @@ impl Graph {
         BigUint::zero()
     }
 
+    /// Gets the balance of the given account in the state resulting from the execution of the node with the given
+    /// hash. If the node isn't in the graph, the account is considered to have a balance of zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account whose balance should be determined
+    /// * `snapshot` - The hash of the node at whose state the balance should be determined
+    pub fn get_balance_of_account_at(&self, account: &Address, snapshot: &Hash) -> BigUint {
+        if !self.hash_routes.contains_key(snapshot) {
+            return BigUint::zero();
+        }
+
+        // Resolve the state at the node
+        self.resolve_parent_nodes(vec![*snapshot])
+            .ok()
+            .and_then(|(state, _)| state.data.balances.get(&account.to_str()).cloned())
+            .unwrap_or_else(BigUint::zero)
+    }
+
+    /// Gets the total balance of every account in the state resulting from the execution of the node with the given
+    /// hash. If the node isn't in the graph, nothing is considered to have been issued.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The hash of the node at whose state the balances should be summed
+    pub fn issuance_at(&self, snapshot: &Hash) -> BigUint {
+        if !self.hash_routes.contains_key(snapshot) {
+            return BigUint::zero();
+        }
+
+        self.resolve_parent_nodes(vec![*snapshot])
+            .ok()
+            .map_or_else(BigUint::zero, |(state, _)| {
+                state.data.balances.values().sum()
+            })
+    }
+
     /// Gets the number of finks issued in the genesis block.
     pub fn overall_issuance(&self) -> BigUint {
         // Get the genesis transaction
@@ -617,7 +741,15 @@ impl Graph {
 
         iter.for_each(|key_val_pair| {
             if let Ok(val) = key_val_pair {
-                let mut current_node: Node = Node::from_bytes(&val.1.to_vec()[..]); // Deserialize node
+                // Deserialize node
+                let mut current_node: Node = match Node::from_bytes(&val.1.to_vec()[..]) {
+                    Ok(node) => node,
+                    Err(e) => {
+                        warn!("Failed to read persisted node: {}", e);
+
+                        return;
+                    }
+                };
 
                 if !read_all {
                     // Check should disregard state data
@@ -738,6 +870,147 @@ impl Graph {
         Ok(()) // Done!
     }
 
+    /// Stores the receipt of the transaction at the given index, and indexes each of its logs by topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the executed transaction in the graph
+    /// * `receipt` - The receipt produced by the transaction's execution
+    pub fn record_receipt(&self, index: usize, receipt: &Receipt) -> Result<(), sled::Error> {
+        // Get database instance
+        let db = if let Some(db) = &self.db {
+            db
+        } else {
+            return Err(sled::Error::Unsupported(
+                "could not open database".to_owned(),
+            ));
+        };
+
+        let transaction_hash = self.nodes[index].hash;
+
+        // Store the receipt under the hash of its transaction
+        db.open_tree(RECEIPTS_TREE)?.insert(
+            transaction_hash.to_vec(),
+            bincode::serialize(receipt).unwrap_or_default(),
+        )?;
+
+        let logs = db.open_tree(LOGS_TREE)?;
+
+        // Index each of the logs under each of their topics
+        for (log_index, log) in receipt.logs.iter().enumerate() {
+            let indexed = IndexedLog {
+                transaction_hash,
+                ledger_index: index as u64,
+                log_index: log_index as u32,
+                log: log.clone(),
+            };
+            let encoded = bincode::serialize(&indexed).unwrap_or_default();
+
+            for topic in &log.topics {
+                logs.insert(
+                    log_key(topic, index as u64, log_index as u32),
+                    encoded.clone(),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the receipt of the transaction with the given hash, if it has been executed.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the transaction
+    pub fn get_receipt(&self, hash: &Hash) -> Result<Option<Receipt>, sled::Error> {
+        if let Some(db) = &self.db {
+            // Look for a receipt stored under the transaction's hash
+            Ok(db
+                .open_tree(RECEIPTS_TREE)?
+                .get(hash.to_vec())?
+                .and_then(|encoded| bincode::deserialize(&encoded).ok()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Gets each of the logs emitted by transactions between the given ledger indexes (inclusive) with any of the
+    /// given topics. If no topics are provided, logs with any topic are returned. Logs are returned in the order they
+    /// were emitted. Ranges spanning more than `MAX_LOG_RANGE` indexes are refused.
+    ///
+    /// # Arguments
+    ///
+    /// * `topics` - The topics that the logs should have (at least one of)
+    /// * `from` - The index of the first transaction that should be considered
+    /// * `to` - The index of the last transaction that should be considered
+    pub fn get_logs(
+        &self,
+        topics: &[String],
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<IndexedLog>, LogQueryError> {
+        if to.saturating_sub(from) >= MAX_LOG_RANGE {
+            return Err(LogQueryError::RangeTooLarge {
+                from,
+                to,
+                limit: MAX_LOG_RANGE,
+            });
+        }
+
+        let db = if let Some(db) = &self.db {
+            db
+        } else {
+            return Ok(Vec::new());
+        };
+
+        if topics.is_empty() {
+            // Any topic will do, so look at the receipt of each transaction in the range
+            let mut logs = Vec::new();
+
+            for (ledger_index, node) in self
+                .nodes
+                .iter()
+                .enumerate()
+                .take(to.saturating_add(1) as usize)
+                .skip(from as usize)
+            {
+                if let Some(receipt) = self.get_receipt(&node.hash)? {
+                    logs.extend(
+                        receipt
+                            .logs
+                            .into_iter()
+                            .enumerate()
+                            .map(|(log_index, log)| IndexedLog {
+                                transaction_hash: node.hash,
+                                ledger_index: ledger_index as u64,
+                                log_index: log_index as u32,
+                                log,
+                            }),
+                    );
+                }
+            }
+
+            return Ok(logs);
+        }
+
+        let tree = db.open_tree(LOGS_TREE)?;
+
+        // Logs with several topics are indexed several times; deduplicate them by their position in the ledger
+        let mut matching: collections::BTreeMap<(u64, u32), IndexedLog> =
+            collections::BTreeMap::new();
+
+        // Only look at the requested range of each topic
+        for topic in topics {
+            for pair in tree.range(log_key(topic, from, 0)..=log_key(topic, to, u32::MAX)) {
+                if let Ok(indexed) = bincode::deserialize::<IndexedLog>(&pair?.1) {
+                    matching.insert((indexed.ledger_index, indexed.log_index), indexed);
+                }
+            }
+        }
+
+        Ok(matching.into_iter().map(|(_, log)| log).collect())
+    }
+
     /// Gets a reference to the head node in the graph--that which contains a resolved state.
     pub fn obtain_executed_head(&self) -> Option<Node> {
         // Start with the last node added to the graph, and move backwards.
@@ -873,7 +1146,9 @@ impl Graph {
                         .is_empty()
                     {
                         // Execute the node, without any prior metadata, since the transaction is the root
-                        let executed = self.nodes[*index].transaction.execute(None);
+                        let (executed, receipt) =
+                            self.nodes[*index].transaction.execute_with_receipt(None);
+                        self.record_receipt(*index, &receipt)?;
 
                         // Keep the executed state in mind for later computation
                         self.nodes[*index].state_entry = Some(executed.clone());
@@ -884,9 +1159,10 @@ impl Graph {
 
                     if let Ok(prev_state_entry) = self.execute_parent_nodes(*index) {
                         // Execute the node with the given prior metadata / state
-                        let executed = self.nodes[*index]
+                        let (executed, receipt) = self.nodes[*index]
                             .transaction
-                            .execute(Some(prev_state_entry));
+                            .execute_with_receipt(Some(prev_state_entry));
+                        self.record_receipt(*index, &receipt)?;
 
                         // Store the calculated state in the tree, and in the collection vector
                         self.nodes[*index].state_entry = Some(executed.clone());
@@ -904,6 +1180,18 @@ impl Graph {
     }
 }
 
+/// Derives the key under which a log with the given topic, emitted at the given position in the ledger, is indexed.
+/// Keys are ordered by topic, and then by position.
+fn log_key(topic: &str, ledger_index: u64, log_index: u32) -> Vec<u8> {
+    let mut key = topic.as_bytes().to_vec();
+    key.push(0); // Separate the topic from the position, so that topics can't run into each other
+
+    key.extend_from_slice(&ledger_index.to_be_bytes());
+    key.extend_from_slice(&log_index.to_be_bytes());
+
+    key
+}
+
 #[cfg(test)]
 mod tests {
     use crate::rand::Rng; // Import rand
@@ -917,6 +1205,8 @@ mod tests {
 
     use super::super::super::super::common::address; // Import address module
 
+    use super::super::logs::Log; // Import the log type
+
     use super::*; // Import names from parent module
 
     #[test]
@@ -947,7 +1237,7 @@ mod tests {
         ); // Initialize graph
 
         assert_eq!(
-            dag.nodes[0].transaction.transaction_data.payload,
+            dag.nodes[0].transaction.transaction_data.payload.data,
             b"test transaction payload"
         ); // Ensure transaction payload retained
 
@@ -1039,7 +1329,8 @@ mod tests {
                 .unwrap()
                 .transaction
                 .transaction_data
-                .payload,
+                .payload
+                .data,
             b"test transaction payload 2"
         ); // Ensure has updated transaction
 
@@ -1076,7 +1367,7 @@ mod tests {
         let found_root_tx = dag.get(0).unwrap().unwrap(); // Get root tx
 
         assert_eq!(
-            found_root_tx.transaction.transaction_data.payload,
+            found_root_tx.transaction.transaction_data.payload.data,
             b"test transaction payload"
         ); // Ensure is same transaction
 
@@ -1114,10 +1405,152 @@ mod tests {
         let found_root_tx = dag.get_with_hash(root_tx_hash).unwrap(); // Get root tx
 
         assert_eq!(
-            found_root_tx.transaction.transaction_data.payload,
+            found_root_tx.transaction.transaction_data.payload.data,
             b"test transaction payload"
         ); // Ensure is same transaction
 
         assert_eq!(dag.write_to_disk(), Ok(())); // Close dag
     }
+
+    #[test]
+    fn test_record_receipt() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let mut rng: rand::prelude::ThreadRng = rand::thread_rng(); // Generate source of randomness
+
+        let rand: u16 = rng.gen(); // Generate random number
+
+        let sender_keypair: Keypair = Keypair::generate(&mut csprng); // Generate sender key pair
+        let recipient_keypair: Keypair = Keypair::generate(&mut csprng); // Generate recipient key pair
+
+        let sender = address::Address::from_key_pair(&sender_keypair); // Derive sender from sender key pair
+        let recipient = address::Address::from_key_pair(&recipient_keypair); // Derive recipient from recipient key pair
+
+        let root_tx = transaction::Transaction::new(
+            0,
+            sender,
+            recipient,
+            BigUint::from_i64(0).unwrap(),
+            b"test transaction payload",
+            vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
+        ); // Initialize root transaction
+        let root_tx_hash = root_tx.hash;
+
+        let dag: Graph = Graph::new_with_db_path(
+            root_tx,
+            &path_clean::clean(&format!("{}/.tests/{}", io::db_dir(), rand.to_string())),
+        ); // Initialize graph
+
+        assert!(dag.get_receipt(&root_tx_hash).unwrap().is_some()); // Ensure the root receipt was stored
+
+        let receipt = Receipt {
+            logs: vec![
+                Log {
+                    topics: vec!["transfer".to_owned()],
+                    message: b"first".to_vec(),
+                },
+                Log {
+                    topics: vec!["approval".to_owned(), "transfer".to_owned()],
+                    message: b"second".to_vec(),
+                },
+            ],
+            ..Default::default()
+        }; // Initialize a receipt with a few logs
+        dag.record_receipt(0, &receipt).unwrap(); // Store the receipt
+
+        assert_eq!(
+            dag.get_logs(&["transfer".to_owned()], 0, 0).unwrap().len(),
+            2
+        ); // Ensure both transfer logs are found
+        assert_eq!(
+            dag.get_logs(&["approval".to_owned()], 0, 0).unwrap()[0]
+                .log
+                .message,
+            b"second"
+        ); // Ensure only the approval log is found
+        assert_eq!(dag.get_logs(&[], 0, 0).unwrap().len(), 2); // Ensure logs with several topics aren't duplicated
+        assert!(dag
+            .get_logs(&["transfer".to_owned()], 1, 10)
+            .unwrap()
+            .is_empty()); // Ensure the range is respected
+        assert!(dag.get_logs(&[], 1, 10).unwrap().is_empty()); // Ensure the range is respected for any topic
+        assert!(dag.get_logs(&[], 0, MAX_LOG_RANGE).is_err()); // Ensure ranges that are too large are refused
+        assert!(dag.get_logs(&["transfer".to_owned()], 0, u64::MAX).is_err()); // Ensure ranges that are too large are refused for any topic
+
+        assert_eq!(dag.write_to_disk(), Ok(())); // Close dag
+    }
+
+    #[test]
+    fn test_read_legacy_nodes() {
+        let mut rng: rand::prelude::ThreadRng = rand::thread_rng(); // Generate source of randomness
+
+        let rand: u16 = rng.gen(); // Generate random number
+
+        let mut balances = collections::HashMap::new();
+        balances.insert(Address::default().to_str(), BigUint::from(10u32)); // Give somebody a balance
+
+        // A node persisted by a release that predates the canonical encoding
+        let legacy = LegacyNode {
+            transaction: transaction::LegacyTransaction {
+                transaction_data: transaction::LegacyTransactionData {
+                    nonce: 0,
+                    sender: Address::default(),
+                    recipient: Address::default(),
+                    value: BigUint::from(10u32),
+                    payload: b"test transaction payload".to_vec(),
+                    parents: vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
+                    parent_receipts: None,
+                    parent_state_hash: None,
+                    timestamp: chrono::Utc::now(),
+                },
+                hash: hash::Hash::new(vec![1; hash::HASH_SIZE]),
+                signature: None,
+                deployed_contract_address: None,
+                contract_creation: false,
+                genesis: true,
+            },
+            state_entry: Some(state::LegacyEntry {
+                data: state::LegacyEntryData {
+                    balances,
+                    nonces: collections::HashMap::new(),
+                },
+                hash: hash::Hash::new(vec![2; hash::HASH_SIZE]),
+            }),
+            hash: hash::Hash::new(vec![1; hash::HASH_SIZE]),
+        };
+        let legacy_bytes = bincode::serialize(&legacy).unwrap();
+
+        let node = Node::from_bytes(&legacy_bytes).unwrap(); // Read the old node
+        assert_eq!(node.hash, hash::Hash::new(vec![1; hash::HASH_SIZE])); // Ensure the node kept its hash
+        assert_eq!(
+            node.transaction.transaction_data.payload.data,
+            b"test transaction payload"
+        ); // Ensure the payload survived the migration
+        assert_eq!(
+            node.state_entry.as_ref().map(|entry| entry.hash),
+            Some(hash::Hash::new(vec![2; hash::HASH_SIZE]))
+        ); // Ensure the state entry survived the migration
+
+        assert_eq!(Node::from_bytes(&node.to_bytes()).unwrap().hash, node.hash); // Ensure migrated nodes survive a round trip
+        assert!(Node::from_bytes(b"garbage").is_err()); // Ensure nodes that can't be read are reported
+
+        let directory = path_clean::clean(&format!("{}/.tests/{}", io::db_dir(), rand.to_string()));
+
+        // Persist the old node as an older release would have
+        {
+            let db = sled::open(&directory).unwrap();
+            db.insert(b"0", legacy_bytes).unwrap();
+            db.flush().unwrap();
+        }
+
+        let dag = Graph::read_some_from_disk_with_data_dir(false, &directory); // Read the old ledger
+        assert_eq!(dag.nodes.len(), 1); // Ensure the old node was read
+        assert_eq!(
+            dag.get_pure(0)
+                .unwrap()
+                .unwrap()
+                .state_entry
+                .map(|entry| entry.hash),
+            Some(hash::Hash::new(vec![2; hash::HASH_SIZE]))
+        ); // Ensure its state can still be loaded
+    }
 }
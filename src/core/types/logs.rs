@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize}; // Import serde serialization
 
+use super::super::super::crypto::hash::Hash; // Import the hash type
+
 /// A log emitted during contract execution.
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Log {
@@ -8,3 +10,16 @@ pub struct Log {
     /// The message of the log
     pub message: Vec<u8>,
 }
+
+/// A log, alongside the location in the ledger at which it was emitted.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct IndexedLog {
+    /// The hash of the transaction that emitted the log
+    pub transaction_hash: Hash,
+    /// The index of the transaction in the ledger
+    pub ledger_index: u64,
+    /// The index of the log in the transaction's receipt
+    pub log_index: u32,
+    /// The log itself
+    pub log: Log,
+}
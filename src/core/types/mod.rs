@@ -1,7 +1,10 @@
+pub mod encoding; // Export the canonical encoding
 pub mod genesis;
 pub mod graph; // Export the graph module
 pub mod logs; // Export the log types
+pub mod payload; // Export the payload types
 pub mod receipt; // Export the receipt types
 pub mod signature; // Export the signature type
+pub mod stamp; // Export the proof-of-work stamp type
 pub mod state; // Export the state module
 pub mod transaction; // Export the transaction type
@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize}; // Import serde serialization
+
+use std::{fmt, ops::Deref, str::FromStr};
+
+/// The default maximum number of bytes that a transaction's payload may contain.
+pub const DEFAULT_MAX_PAYLOAD_SIZE: usize = 128 * 1024;
+
+/// An error encountered while interpreting a payload.
+#[derive(Debug, Fail)]
+pub enum PayloadError {
+    #[fail(display = "unknown payload content type: {}", content_type)]
+    UnknownContentType {
+        content_type: String, // The unknown content type
+    },
+    #[fail(display = "payload is not valid {}: {}", content_type, error)]
+    MalformedContents {
+        content_type: ContentType, // The content type that the payload claims to have
+        error: String,             // The error
+    },
+}
+
+/// The manner in which the contents of a payload should be interpreted.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ContentType {
+    /// A human-readable, UTF-8-encoded note
+    Memo,
+    /// Arbitrary binary data
+    Binary,
+    /// A UTF-8-encoded JSON document
+    Json,
+    /// The input to a contract
+    ContractCall,
+    /// The WebAssembly module of a contract that should be deployed
+    ContractCode,
+    /// A delegation of the sender's votes, in its canonical encoding
+    Delegation,
+    /// A vote cast by the sender, in its canonical encoding
+    Vote,
+}
+
+impl Default for ContentType {
+    /// Gets the default content type of a payload (Binary).
+    fn default() -> Self {
+        ContentType::Binary
+    }
+}
+
+impl fmt::Display for ContentType {
+    /// Formats the ContentType according to its name (i.e. memo, binary, json, contract_call, contract_code, delegation, vote).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Memo => "memo",
+                Self::Binary => "binary",
+                Self::Json => "json",
+                Self::ContractCall => "contract_call",
+                Self::ContractCode => "contract_code",
+                Self::Delegation => "delegation",
+                Self::Vote => "vote",
+            }
+        )
+    }
+}
+
+impl FromStr for ContentType {
+    type Err = PayloadError;
+
+    /// Parses a content type from its name (i.e. memo, binary, json, contract_call, contract_code, delegation, vote).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "memo" => Ok(Self::Memo),
+            "binary" => Ok(Self::Binary),
+            "json" => Ok(Self::Json),
+            "contract_call" => Ok(Self::ContractCall),
+            "contract_code" => Ok(Self::ContractCode),
+            "delegation" => Ok(Self::Delegation),
+            "vote" => Ok(Self::Vote),
+            _ => Err(PayloadError::UnknownContentType {
+                content_type: s.to_owned(),
+            }),
+        }
+    }
+}
+
+/// The data sent along with a transaction, and the manner in which it should be interpreted.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct Payload {
+    /// The manner in which the data should be interpreted
+    pub content_type: ContentType,
+    /// The data itself
+    pub data: Vec<u8>,
+}
+
+/// Implement a set of payload helper methods.
+impl Payload {
+    /// Initializes a new payload with the given content type and data.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The manner in which the data should be interpreted
+    /// * `data` - The contents of the payload
+    pub fn new(content_type: ContentType, data: &[u8]) -> Self {
+        Self {
+            content_type,
+            data: data.to_vec(),
+        }
+    }
+
+    /// Initializes a new human-readable memo payload.
+    pub fn memo(memo: &str) -> Self {
+        Self::new(ContentType::Memo, memo.as_bytes())
+    }
+
+    /// Initializes a new binary payload.
+    pub fn binary(data: &[u8]) -> Self {
+        Self::new(ContentType::Binary, data)
+    }
+
+    /// Initializes a new contract call payload, containing the input to the contract.
+    pub fn contract_call(input: &[u8]) -> Self {
+        Self::new(ContentType::ContractCall, input)
+    }
+
+    /// Initializes a new contract code payload, containing the WebAssembly module of a contract that should be
+    /// deployed.
+    pub fn contract_code(code: &[u8]) -> Self {
+        Self::new(ContentType::ContractCode, code)
+    }
+
+    /// Initializes a new delegation payload, containing the canonical encoding of a delegation.
+    pub fn delegation(encoded: &[u8]) -> Self {
+        Self::new(ContentType::Delegation, encoded)
+    }
+
+    /// Initializes a new vote payload, containing the canonical encoding of a vote.
+    pub fn vote(encoded: &[u8]) -> Self {
+        Self::new(ContentType::Vote, encoded)
+    }
+
+    /// Parses a payload from its textual representation: memos and JSON documents are taken as-is, while binary data,
+    /// contract calls, contract code, delegations and votes must be base58-encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The manner in which the data should be interpreted
+    /// * `s` - The textual representation of the payload
+    pub fn from_str_with_content_type(
+        content_type: ContentType,
+        s: &str,
+    ) -> Result<Self, PayloadError> {
+        // Binary data can't be represented as a string directly
+        let data = match content_type {
+            ContentType::Memo | ContentType::Json => s.as_bytes().to_vec(),
+            ContentType::Binary
+            | ContentType::ContractCall
+            | ContentType::ContractCode
+            | ContentType::Delegation
+            | ContentType::Vote => {
+                bs58::decode(s)
+                    .into_vec()
+                    .map_err(|e| PayloadError::MalformedContents {
+                        content_type,
+                        error: e.to_string(),
+                    })?
+            }
+        };
+
+        let payload = Self { content_type, data };
+        payload.validate()?;
+
+        Ok(payload)
+    }
+
+    /// Ensures that the payload's data is consistent with its content type.
+    pub fn validate(&self) -> Result<(), PayloadError> {
+        match self.content_type {
+            // Memos must be readable
+            ContentType::Memo => std::str::from_utf8(&self.data).map(|_| ()).map_err(|e| {
+                PayloadError::MalformedContents {
+                    content_type: self.content_type,
+                    error: e.to_string(),
+                }
+            }),
+            // JSON documents must be parseable
+            ContentType::Json => serde_json::from_slice::<serde_json::Value>(&self.data)
+                .map(|_| ())
+                .map_err(|e| PayloadError::MalformedContents {
+                    content_type: self.content_type,
+                    error: e.to_string(),
+                }),
+            // Delegations are checked against their senders when the transactions carrying them are validated, and
+            // contract code when it is deployed
+            ContentType::Binary
+            | ContentType::ContractCall
+            | ContentType::ContractCode
+            | ContentType::Delegation
+            | ContentType::Vote => Ok(()),
+        }
+    }
+}
+
+impl Deref for Payload {
+    type Target = [u8];
+
+    /// Gets the data contained in the payload.
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl fmt::Display for Payload {
+    /// Renders the payload according to its content type.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.content_type {
+            ContentType::Memo => write!(f, "{}", String::from_utf8_lossy(&self.data)),
+            ContentType::Json => {
+                // Pretty-print the document if we can, since that's the point
+                if let Ok(document) = serde_json::from_slice::<serde_json::Value>(&self.data) {
+                    write!(
+                        f,
+                        "{}",
+                        serde_json::to_string_pretty(&document).map_err(|_| fmt::Error)?
+                    )
+                } else {
+                    write!(f, "{}", String::from_utf8_lossy(&self.data))
+                }
+            }
+            ContentType::Binary
+            | ContentType::ContractCall
+            | ContentType::ContractCode
+            | ContentType::Delegation
+            | ContentType::Vote => {
+                write!(f, "{}", bs58::encode(&self.data).into_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // Import names from the parent module
+
+    #[test]
+    fn test_from_str_with_content_type() {
+        let memo = Payload::from_str_with_content_type(ContentType::Memo, "hello").unwrap(); // Parse a memo
+        assert_eq!(memo.data, b"hello"); // Ensure the memo is stored as-is
+        assert_eq!(memo.to_string(), "hello"); // Ensure the memo is rendered as-is
+
+        let binary = Payload::from_str_with_content_type(
+            ContentType::Binary,
+            &bs58::encode(&[0, 1, 2]).into_string(),
+        )
+        .unwrap(); // Parse some binary data
+        assert_eq!(binary.data, vec![0, 1, 2]); // Ensure the data was decoded
+
+        assert!(Payload::from_str_with_content_type(ContentType::Json, "{\"a\": 1}").is_ok()); // Ensure valid JSON is accepted
+        assert!(Payload::from_str_with_content_type(ContentType::Json, "{\"a\":").is_err()); // Ensure invalid JSON is rejected
+        assert!(Payload::new(ContentType::Memo, &[0xff, 0xfe])
+            .validate()
+            .is_err()); // Ensure invalid memos are rejected
+    }
+
+    #[test]
+    fn test_content_type_from_str() {
+        for content_type in &[
+            ContentType::Memo,
+            ContentType::Binary,
+            ContentType::Json,
+            ContentType::ContractCall,
+            ContentType::ContractCode,
+            ContentType::Delegation,
+            ContentType::Vote,
+        ] {
+            assert_eq!(
+                ContentType::from_str(&content_type.to_string()).unwrap(),
+                *content_type
+            ); // Ensure each content type can be parsed from its name
+        }
+
+        assert!(ContentType::from_str("xml").is_err()); // Ensure unknown types are rejected
+    }
+}
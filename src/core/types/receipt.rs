@@ -4,6 +4,24 @@ use serde::{Deserialize, Serialize}; // Import serde serialization
 
 use super::super::super::crypto::hash::Hash; // Import the address utility
 
+/// The outcome of a transaction's execution.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Status {
+    /// The transaction was executed in its entirety
+    Succeeded,
+    /// The transaction's value was transferred, but the contract it deployed or called failed
+    Failed {
+        error: String, // The error
+    },
+}
+
+impl Default for Status {
+    /// Gets the default status of a receipt (Succeeded).
+    fn default() -> Self {
+        Status::Succeeded
+    }
+}
+
 /// A receipt of a transaction's execution.
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Receipt {
@@ -11,6 +29,12 @@ pub struct Receipt {
     pub state_hash: Hash,
     /// Logs emitted at run time
     pub logs: Vec<logs::Log>,
+    /// The outcome of the transaction's execution
+    #[serde(default)]
+    pub status: Status,
+    /// The amount of gas consumed by any contract deployed or called by the transaction
+    #[serde(default)]
+    pub gas_used: u64,
 }
 
 /// A mapping between a set of tx hashes and transaction receipts.
@@ -22,6 +46,41 @@ pub struct ReceiptMap {
     pub receipts: Vec<Receipt>,
 }
 
+/// The layout of a receipt in releases that predate the canonical encoding, before receipts recorded the outcome of a
+/// transaction's execution.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LegacyReceipt {
+    pub(crate) state_hash: Hash,
+    pub(crate) logs: Vec<logs::Log>,
+}
+
+impl From<LegacyReceipt> for Receipt {
+    fn from(receipt: LegacyReceipt) -> Self {
+        Self {
+            state_hash: receipt.state_hash,
+            logs: receipt.logs,
+            status: Status::Succeeded,
+            gas_used: 0,
+        }
+    }
+}
+
+/// The layout of a receipt map in releases that predate the canonical encoding.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LegacyReceiptMap {
+    pub(crate) associated_transactions: Vec<Hash>,
+    pub(crate) receipts: Vec<LegacyReceipt>,
+}
+
+impl From<LegacyReceiptMap> for ReceiptMap {
+    fn from(map: LegacyReceiptMap) -> Self {
+        Self {
+            associated_transactions: map.associated_transactions,
+            receipts: map.receipts.into_iter().map(Receipt::from).collect(),
+        }
+    }
+}
+
 impl ReceiptMap {
     /// Gets a receipt stored in the receiptmap by its hash.
     ///
@@ -1,9 +1,140 @@
 use ed25519_dalek; // Import the edwards25519 digital signature library
 
 use super::super::super::common::address::Address;
+use super::encoding::Canonical;
 use super::transaction::Transaction;
 use serde::{Deserialize, Serialize}; // Import serde serialization
-use crate::ed25519_dalek::Verifier;
+use crate::ed25519_dalek::{Keypair, Signer, Verifier};
+
+use std::fmt;
+
+/// The version of the signing context under which signatures are produced and accepted. Signatures over the raw
+/// message, without any context, are never accepted, since nothing stops them from being replayed in another domain or
+/// on another network.
+pub const CURRENT_SIGNATURE_VERSION: u8 = 1;
+
+/// A prefix included in every domain-separated message, so that SummerCash signatures can't be confused with those of
+/// other applications using the same key.
+pub const SIGNING_CONTEXT_PREFIX: &[u8] = b"summercash";
+
+/// An error encountered while verifying a batch of signatures.
+#[derive(Debug, Fail)]
+pub enum BatchVerificationError {
+    #[fail(
+        display = "the signatures at the following indices are invalid: {:?}",
+        invalid_indices
+    )]
+    InvalidSignatures {
+        invalid_indices: Vec<usize>, // The indices of the invalid signatures in the batch, in ascending order
+    },
+}
+
+/// The kind of object that a signature applies to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Domain {
+    /// A transaction
+    Transaction,
+    /// A vote on a proposal
+    Vote,
+    /// A proposal
+    Proposal,
+    /// An arbitrary message
+    Message,
+    /// The withdrawal of a pending transaction
+    Cancellation,
+    /// The delegation of an account's voting weight
+    Delegation,
+}
+
+impl Domain {
+    /// Gets the tag identifying the domain in a signing context.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Domain::Transaction => "tx",
+            Domain::Vote => "vote",
+            Domain::Proposal => "proposal",
+            Domain::Message => "message",
+            Domain::Cancellation => "cancel",
+            Domain::Delegation => "delegate",
+        }
+    }
+}
+
+impl fmt::Display for Domain {
+    /// Formats the Domain according to its tag (i.e. tx, vote, proposal, message, cancel, delegate).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tag())
+    }
+}
+
+/// The context in which a message is signed. Binding a signature to a context ensures that a signature over one kind of
+/// object on one network can't be replayed as a signature over another kind of object, or on another network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SigningContext {
+    /// The kind of object being signed
+    pub domain: Domain,
+    /// The identifier of the chain on which the object is valid (see `Config::chain_id`), which, unlike the network's
+    /// name, never changes
+    pub network: String,
+}
+
+/// Implement a set of signing context helper methods.
+impl SigningContext {
+    /// Initializes a new signing context.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The kind of object being signed
+    /// * `network` - The identifier of the chain on which the object is valid
+    pub fn new(domain: Domain, network: &str) -> Self {
+        Self {
+            domain,
+            network: network.to_owned(),
+        }
+    }
+
+    /// Derives the bytes that are actually signed for the given message in this context.
+    ///
+    /// Signed messages consist of the SIGNING_CONTEXT_PREFIX, the version byte, the domain tag, and the chain
+    /// identifier, each followed by a zero byte, and then the message itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message being signed
+    pub fn signed_message(&self, message: &[u8]) -> Vec<u8> {
+        let mut signed = Vec::with_capacity(
+            SIGNING_CONTEXT_PREFIX.len() + self.network.len() + message.len() + 16,
+        );
+
+        for component in &[
+            SIGNING_CONTEXT_PREFIX,
+            &[CURRENT_SIGNATURE_VERSION],
+            self.domain.tag().as_bytes(),
+            self.network.as_bytes(),
+        ] {
+            signed.extend_from_slice(component);
+            signed.push(0);
+        }
+
+        signed.extend_from_slice(message);
+
+        signed
+    }
+
+    /// Signs the given message in this context.
+    ///
+    /// # Arguments
+    ///
+    /// * `keypair` - The keypair that should be used to sign the message
+    /// * `message` - The message that should be signed
+    pub fn sign(&self, keypair: &Keypair, message: &[u8]) -> Signature {
+        Signature {
+            public_key_bytes: bincode::serialize(&keypair.public).unwrap_or_default(),
+            signature_bytes: bincode::serialize(&keypair.sign(&self.signed_message(message)))
+                .unwrap_or_default(),
+        }
+    }
+}
 
 /// An edwards25519 signature.
 #[derive(Serialize, Deserialize, Clone)]
@@ -16,8 +147,13 @@ pub struct Signature {
 
 /// Implement a set of signature helper methods.
 impl Signature {
-    /// Verify the signature (self).
-    pub fn verify(&self, message: &[u8]) -> bool {
+    /// Verify the signature (self) over the given message, in the given context.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The context in which the message should have been signed
+    /// * `message` - The message that should have been signed
+    pub fn verify(&self, context: &SigningContext, message: &[u8]) -> bool {
         // Get the signature's public key
         let pub_key = if let Ok(pk) = self.public_key() {
             pk
@@ -32,16 +168,26 @@ impl Signature {
             return false;
         };
 
-        pub_key.verify(message, &sig).is_ok() // Return is valid
+        pub_key
+            .verify(&context.signed_message(message), &sig)
+            .is_ok() // Return is valid
     }
 
     /// Verify the signature against a target transaction that has this signature attached to it.
-    pub fn verify_tx(&self, transaction: &Transaction) -> bool {
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - The transaction that the signature should belong to
+    /// * `network` - The name of the network on which the transaction should be valid
+    pub fn verify_tx(&self, transaction: &Transaction, network: &str) -> bool {
         // Get the public key of the sender of the transaction
         if let Ok(sender_kp) = self.public_key() {
             // Make sure that the sender has the same keypair as that in the tx
             transaction.transaction_data.sender == Address::from_public_key(&sender_kp)
-                && self.verify(&*transaction.hash)
+                && self.verify(
+                    &SigningContext::new(Domain::Transaction, network),
+                    &*transaction.hash,
+                )
         } else {
             // If the sender doesn't have a keypair, the tx can't be valid
             false
@@ -67,3 +213,236 @@ impl Signature {
         Ok(Address::from_public_key(&pub_key))
     }
 }
+
+/// Verifies each of the provided signatures against the message at the same index, using ed25519 batch verification.
+/// If the batch fails to verify, it is repeatedly split in half in order to locate the invalid signatures.
+///
+/// # Arguments
+///
+/// * `context` - The context in which each of the messages should have been signed
+/// * `signatures` - The signatures that should be verified
+/// * `messages` - The message signed by each of the signatures
+pub fn verify_batch(
+    context: &SigningContext,
+    signatures: &[&Signature],
+    messages: &[&[u8]],
+) -> Result<(), BatchVerificationError> {
+    let mut invalid_indices: Vec<usize> = Vec::new();
+
+    // Decode each of the signatures, since a signature that can't be decoded is certainly invalid
+    let mut candidates: Vec<BatchCandidate> = Vec::new();
+    for (i, signature) in signatures.iter().enumerate() {
+        match (
+            signature.public_key(),
+            signature.signature(),
+            messages.get(i),
+        ) {
+            (Ok(public_key), Ok(sig), Some(message)) => candidates.push(BatchCandidate {
+                index: i,
+                public_key,
+                signature: sig,
+                message: context.signed_message(message),
+            }),
+            _ => invalid_indices.push(i),
+        }
+    }
+
+    // Verify the decoded signatures in batches
+    locate_invalid_signatures(&candidates, &mut invalid_indices);
+
+    if invalid_indices.is_empty() {
+        Ok(())
+    } else {
+        invalid_indices.sort_unstable();
+
+        Err(BatchVerificationError::InvalidSignatures { invalid_indices })
+    }
+}
+
+/// Verifies the signatures of each of the provided transactions in a batch, ensuring that each transaction was signed
+/// by its sender. Since signatures cover a transaction's hash, rather than its contents, transactions whose contents
+/// don't match their hashes are also treated as invalid.
+///
+/// # Arguments
+///
+/// * `network` - The name of the network on which the transactions should be valid
+/// * `transactions` - The transactions whose signatures should be verified
+pub fn verify_tx_batch(
+    network: &str,
+    transactions: &[&Transaction],
+) -> Result<(), BatchVerificationError> {
+    let mut invalid_indices: Vec<usize> = Vec::new();
+
+    // The signatures that belong to the senders of their transactions, and the index of each such transaction
+    let mut indices: Vec<usize> = Vec::new();
+    let mut signatures: Vec<&Signature> = Vec::new();
+    let mut messages: Vec<&[u8]> = Vec::new();
+
+    for (i, tx) in transactions.iter().enumerate() {
+        match &tx.signature {
+            // Make sure that the sender has the same keypair as that in the tx, and that the signed hash is that of the
+            // tx's contents
+            Some(sig)
+                if sig
+                    .address()
+                    .map(|address| address == tx.transaction_data.sender)
+                    .unwrap_or(false)
+                    && tx.hash == tx.transaction_data.canonical_hash() =>
+            {
+                indices.push(i);
+                signatures.push(sig);
+                messages.push(&*tx.hash);
+            }
+            // Nil signatures, signatures from anyone but the sender and signatures over other contents can't be valid
+            _ => invalid_indices.push(i),
+        }
+    }
+
+    // Translate the indices of any invalid signatures back into indices of transactions
+    if let Err(BatchVerificationError::InvalidSignatures {
+        invalid_indices: invalid_signatures,
+    }) = verify_batch(
+        &SigningContext::new(Domain::Transaction, network),
+        &signatures,
+        &messages,
+    ) {
+        invalid_indices.extend(invalid_signatures.into_iter().map(|i| indices[i]));
+    }
+
+    if invalid_indices.is_empty() {
+        Ok(())
+    } else {
+        invalid_indices.sort_unstable();
+
+        Err(BatchVerificationError::InvalidSignatures { invalid_indices })
+    }
+}
+
+/// A decoded signature awaiting batch verification.
+struct BatchCandidate {
+    index: usize,
+    public_key: ed25519_dalek::PublicKey,
+    signature: ed25519_dalek::Signature,
+    message: Vec<u8>,
+}
+
+/// Verifies the provided signatures in a batch, splitting the batch in half and verifying each half on failure until
+/// each of the invalid signatures has been found.
+///
+/// # Arguments
+///
+/// * `candidates` - The signatures that should be verified
+/// * `invalid_indices` - The indices of the signatures found to be invalid
+fn locate_invalid_signatures(candidates: &[BatchCandidate], invalid_indices: &mut Vec<usize>) {
+    match candidates {
+        [] => {}
+        // A lone signature can simply be verified by itself
+        [candidate] => {
+            if candidate
+                .public_key
+                .verify(&candidate.message, &candidate.signature)
+                .is_err()
+            {
+                invalid_indices.push(candidate.index);
+            }
+        }
+        _ => {
+            let messages: Vec<&[u8]> = candidates.iter().map(|c| &c.message[..]).collect();
+            let signatures: Vec<ed25519_dalek::Signature> =
+                candidates.iter().map(|c| c.signature).collect();
+            let public_keys: Vec<ed25519_dalek::PublicKey> =
+                candidates.iter().map(|c| c.public_key).collect();
+
+            // If the batch as a whole doesn't verify, one of its halves must contain an invalid signature
+            if ed25519_dalek::verify_batch(&messages, &signatures, &public_keys).is_err() {
+                let (left, right) = candidates.split_at(candidates.len() / 2);
+
+                locate_invalid_signatures(left, invalid_indices);
+                locate_invalid_signatures(right, invalid_indices);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // Import names from the parent module
+
+    use super::super::transaction::{self, TransactionBuilder}; // Import the transaction builder
+
+    use num::BigUint; // Add support for large unsigned integers
+    use rand::rngs::OsRng; // Import the os's rng
+
+    #[test]
+    fn test_verify_tx_batch() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let recipient = Address::from_key_pair(&Keypair::generate(&mut csprng)); // Derive a recipient address
+
+        // Sign a transaction from each of a number of senders
+        let mut transactions: Vec<Transaction> = (0..9)
+            .map(|nonce| {
+                let keypair = Keypair::generate(&mut csprng);
+
+                TransactionBuilder::new()
+                    .nonce(nonce)
+                    .sender(Address::from_key_pair(&keypair))
+                    .recipient(recipient)
+                    .build_signed(keypair, "olympia")
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(verify_tx_batch(
+            "olympia",
+            &transactions.iter().collect::<Vec<&Transaction>>()
+        )
+        .is_ok()); // Ensure the batch verifies
+
+        transactions[2].hash = transactions[3].hash; // Invalidate a signature by changing the signed message
+        transactions[4].transaction_data.value = BigUint::from(1u8); // Change the contents, but not the signed hash
+        transactions[7].signature = None; // Remove a signature entirely
+        transaction::sign_transaction(
+            Keypair::generate(&mut csprng),
+            &mut transactions[5],
+            "olympia",
+        )
+        .unwrap_err(); // Ensure strangers can't sign for the sender
+
+        match verify_tx_batch(
+            "olympia",
+            &transactions.iter().collect::<Vec<&Transaction>>(),
+        ) {
+            Err(BatchVerificationError::InvalidSignatures { invalid_indices }) => {
+                assert_eq!(invalid_indices, vec![2, 4, 7])
+            } // Ensure the invalid signatures were located
+            Ok(_) => panic!("batch containing invalid signatures verified"),
+        }
+    }
+
+    #[test]
+    fn test_signing_context() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+        let keypair = Keypair::generate(&mut csprng); // Generate a keypair to sign with
+
+        let message = b"test message"; // The message that will be signed
+        let vote_context = SigningContext::new(Domain::Vote, "olympia"); // A vote on the olympia network
+
+        let sig = vote_context.sign(&keypair, message); // Sign the message as a vote
+
+        assert!(sig.verify(&vote_context, message)); // Ensure the signature is valid as a vote
+        assert!(!sig.verify(
+            &SigningContext::new(Domain::Transaction, "olympia"),
+            message
+        )); // Ensure the vote can't be replayed as a tx
+        assert!(!sig.verify(&SigningContext::new(Domain::Vote, "andromeda"), message)); // Ensure the vote can't be replayed on another network
+
+        // Sign the raw message, without any context
+        let raw = Signature {
+            public_key_bytes: bincode::serialize(&keypair.public).unwrap(),
+            signature_bytes: bincode::serialize(&keypair.sign(message)).unwrap(),
+        };
+
+        assert!(!raw.verify(&vote_context, message)); // Ensure raw signatures can't be replayed in any context
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize}; // Import serde serialization
+
+use super::super::super::crypto::{blake3, hash::Hash}; // Import the hash modules
+
+/// The difficulty of the stamps demanded by networks whose configs don't specify one (i.e. no work is required).
+pub const DEFAULT_STAMP_DIFFICULTY: u8 = 0;
+
+/// The greatest difficulty that a network may demand of stamps. Each additional bit doubles the expected amount of
+/// work, so stamps at this difficulty take around 16 million hashes to mint.
+pub const MAX_STAMP_DIFFICULTY: u8 = 24;
+
+/// A hashcash-style proof that work was spent on a particular transaction. A stamp is valid at a given difficulty if
+/// the blake3 hash of the transaction's hash followed by the stamp's nonce (little-endian) begins with at least as
+/// many zero bits as the difficulty.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stamp {
+    /// The nonce that was found to satisfy the difficulty target
+    pub nonce: u64,
+}
+
+impl Stamp {
+    /// Searches for a stamp proving that at least the given amount of work was spent on the transaction with the
+    /// provided hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash` - The hash of the transaction that should be stamped
+    /// * `difficulty` - The number of leading zero bits that the stamp's work hash must have
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use summercash::{core::types::stamp::Stamp, crypto::blake3};
+    ///
+    /// let tx_hash = blake3::hash_slice(b"test");
+    /// let stamp = Stamp::mint(tx_hash, 8); // Spend ~256 hashes on the transaction
+    ///
+    /// assert!(stamp.meets(tx_hash, 8));
+    /// ```
+    pub fn mint(tx_hash: Hash, difficulty: u8) -> Stamp {
+        // Try each nonce until one meets the difficulty target
+        (0..=u64::MAX)
+            .map(|nonce| Stamp { nonce })
+            .find(|stamp| stamp.meets(tx_hash, difficulty))
+            .unwrap_or(Stamp { nonce: u64::MAX })
+    }
+
+    /// Hashes the transaction's hash, followed by the stamp's nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash` - The hash of the transaction that was stamped
+    pub fn work_hash(&self, tx_hash: Hash) -> Hash {
+        let mut preimage = tx_hash.to_vec();
+        preimage.extend_from_slice(&self.nonce.to_le_bytes());
+
+        blake3::hash_slice(&preimage)
+    }
+
+    /// Gets the amount of work proven by the stamp (i.e. the number of leading zero bits of its work hash).
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash` - The hash of the transaction that was stamped
+    pub fn work(&self, tx_hash: Hash) -> u32 {
+        let work_hash = self.work_hash(tx_hash);
+
+        // Count the zero bits at the start of the hash
+        let mut work = 0;
+
+        for byte in work_hash.iter() {
+            work += byte.leading_zeros();
+
+            if *byte != 0 {
+                break;
+            }
+        }
+
+        work
+    }
+
+    /// Checks whether or not the stamp proves that at least the given amount of work was spent on the transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash` - The hash of the transaction that was stamped
+    /// * `difficulty` - The number of leading zero bits that the stamp's work hash must have
+    pub fn meets(&self, tx_hash: Hash, difficulty: u8) -> bool {
+        self.work(tx_hash) >= u32::from(difficulty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // Import names from the parent module
+
+    #[test]
+    fn test_mint() {
+        let tx_hash = blake3::hash_slice(b"test"); // Make a hash to stamp
+
+        let stamp = Stamp::mint(tx_hash, 12); // Spend ~4096 hashes on the hash
+
+        assert!(stamp.work(tx_hash) >= 12); // Ensure the stamp proves the requested amount of work
+        assert!(stamp.meets(tx_hash, 12));
+        assert!(Stamp { nonce: 0 }.meets(tx_hash, DEFAULT_STAMP_DIFFICULTY)); // Ensure no work is required by default
+    }
+}
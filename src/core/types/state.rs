@@ -23,6 +23,43 @@ pub struct EntryData {
 
     /// The last recorded index of each account
     pub nonces: collections::HashMap<String, u64>,
+
+    /// The prepared code of each deployed contract
+    #[serde(default)]
+    pub contracts: collections::HashMap<String, Vec<u8>>,
+
+    /// The storage of each deployed contract
+    #[serde(default)]
+    pub storage: collections::HashMap<String, collections::HashMap<String, Vec<u8>>>,
+}
+
+/// The layout of a state entry in releases that predate contracts.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LegacyEntry {
+    pub(crate) data: LegacyEntryData,
+    pub(crate) hash: hash::Hash,
+}
+
+/// The layout of a state entry's body in releases that predate contracts.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LegacyEntryData {
+    pub(crate) balances: collections::HashMap<String, BigUint>,
+    pub(crate) nonces: collections::HashMap<String, u64>,
+}
+
+impl From<LegacyEntry> for Entry {
+    fn from(entry: LegacyEntry) -> Self {
+        // Keep the hash that the entry was stored with, since it was derived differently back then
+        Self {
+            data: EntryData {
+                balances: entry.data.balances,
+                nonces: entry.data.nonces,
+                contracts: collections::HashMap::new(),
+                storage: collections::HashMap::new(),
+            },
+            hash: entry.hash,
+        }
+    }
 }
 
 /// Implement a set of state entry serialization helper methods.
@@ -40,24 +77,63 @@ impl Entry {
         nonces: collections::HashMap<String, u64>,
         balances: collections::HashMap<String, BigUint>,
     ) -> Entry {
+        Entry::with_contracts(
+            nonces,
+            balances,
+            collections::HashMap::new(),
+            collections::HashMap::new(),
+        )
+    }
+
+    /// Initialize a new Entry instance containing the given contract code & storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonces` - The last recorded index of each account
+    /// * `balances` - The balance of each account
+    /// * `contracts` - The prepared code of each deployed contract
+    /// * `storage` - The storage of each deployed contract
+    pub fn with_contracts(
+        nonces: collections::HashMap<String, u64>,
+        balances: collections::HashMap<String, BigUint>,
+        contracts: collections::HashMap<String, Vec<u8>>,
+        storage: collections::HashMap<String, collections::HashMap<String, Vec<u8>>>,
+    ) -> Entry {
+        // Sort the nonces & balances by account, since the order of a hash map isn't reproducible
+        let sorted_nonces: collections::BTreeMap<&String, &u64> = nonces.iter().collect();
+        let sorted_balances: collections::BTreeMap<&String, &BigUint> = balances.iter().collect();
+
+        let mut state_components = vec![
+            bincode::serialize(&sorted_nonces).unwrap_or_default(),
+            bincode::serialize(&sorted_balances).unwrap_or_default(),
+        ];
+
+        // Only commit to contract state if there is any, so that the hashes of plain states stay the same
+        if !contracts.is_empty() || !storage.is_empty() {
+            // Sort the contract state, since the order of a hash map isn't reproducible
+            let sorted_contracts: collections::BTreeMap<&String, &Vec<u8>> =
+                contracts.iter().collect();
+            let sorted_storage: collections::BTreeMap<
+                &String,
+                collections::BTreeMap<&String, &Vec<u8>>,
+            > = storage
+                .iter()
+                .map(|(contract, values)| (contract, values.iter().collect()))
+                .collect();
+
+            state_components.push(bincode::serialize(&sorted_contracts).unwrap_or_default());
+            state_components.push(bincode::serialize(&sorted_storage).unwrap_or_default());
+        }
+
         // Produce a reproducible hash of the state
-        let state_hash = blake3::hash_slice(
-            &bincode::serialize(&vec![
-                bincode::serialize(&nonces.keys().collect::<Vec<&String>>().sort())
-                    .unwrap_or_default(),
-                bincode::serialize(&nonces.values().collect::<Vec<&u64>>().sort())
-                    .unwrap_or_default(),
-                bincode::serialize(&balances.keys().collect::<Vec<&String>>().sort())
-                    .unwrap_or_default(),
-                bincode::serialize(&balances.values().collect::<Vec<&BigUint>>().sort())
-                    .unwrap_or_default(),
-            ])
-            .unwrap_or_default(),
-        );
+        let state_hash =
+            blake3::hash_slice(&bincode::serialize(&state_components).unwrap_or_default());
 
         let entry_data: EntryData = EntryData {
-            balances, // Set balances
-            nonces,   // Set nonces
+            balances,  // Set balances
+            nonces,    // Set nonces
+            contracts, // Set contract code
+            storage,   // Set contract storage
         }; // Initialize entry data
 
         Entry {
@@ -71,33 +147,38 @@ impl Entry {
 pub fn merge_entries(entries: Vec<Entry>) -> Entry {
     let mut balances: collections::HashMap<String, BigUint> = collections::HashMap::new(); // Initialize balances map
     let mut nonces: collections::HashMap<String, u64> = collections::HashMap::new(); // Initialize a collections map
+    let mut contracts: collections::HashMap<String, Vec<u8>> = collections::HashMap::new(); // Initialize a contract code map
+    let mut storage: collections::HashMap<String, collections::HashMap<String, Vec<u8>>> =
+        collections::HashMap::new(); // Initialize a contract storage map
 
     for entry in entries {
         // Iterate through entries
         for (balance_addr, balance) in entry.data.balances.iter() {
-            // Iterate through balances
-            if balances.contains_key(balance_addr) {
-                // Check already exists in balances
-                let balance_difference = balance - balances.get(balance_addr).unwrap(); // Calculate balance difference
-
-                let mut_balance = balances.get_mut(balance_addr).unwrap(); // Get mutable balance
-
-                *mut_balance += balance_difference; // Set balance to difference between balance at old state and balance at tx
-            } else {
-                balances.insert(balance_addr.to_string(), balance.clone()); // Set balance
-            }
+            // Use the balance at the latest state (the balance may have decreased, so it can't be computed as a
+            // difference between unsigned balances)
+            balances.insert(balance_addr.to_string(), balance.clone()); // Set balance
         }
 
         // Synchronize both of the nonce storage locations
         for (nonce_addr, nonce) in entry.data.nonces.iter() {
             // Use the highest nonce for the account
-            if *nonces.entry(nonce_addr.clone()).or_insert(*nonce) > *nonce {
-                nonces.insert(nonce_addr.clone(), *nonce);
+            let merged_nonce = nonces.entry(nonce_addr.clone()).or_insert(*nonce);
+
+            if *merged_nonce < *nonce {
+                *merged_nonce = *nonce;
             }
         }
+
+        // Contracts can't be redeployed at the same address, so their code can simply be unioned
+        contracts.extend(entry.data.contracts);
+
+        // Take the latest storage of each contract
+        for (contract_addr, values) in entry.data.storage {
+            storage.entry(contract_addr).or_default().extend(values);
+        }
     }
 
-    Entry::new(nonces, balances) // Return initialized state entry
+    Entry::with_contracts(nonces, balances, contracts, storage) // Return initialized state entry
 }
 
 #[cfg(test)]
@@ -129,4 +210,29 @@ mod tests {
             BigUint::from_i64(1).unwrap()
         ); // Ensure balance entry correctly written to state entry
     }
+
+    #[test]
+    pub fn test_merge_entries() {
+        let account = blake3::hash_slice(b"test").to_str(); // The account whose state differs between entries
+
+        let entry_with = |nonce: u64, balance: i64| {
+            Entry::new(
+                vec![(account.clone(), nonce)].into_iter().collect(),
+                vec![(account.clone(), BigUint::from_i64(balance).unwrap())]
+                    .into_iter()
+                    .collect(),
+            )
+        };
+
+        assert_ne!(entry_with(3, 10).hash, entry_with(1, 2).hash); // Ensure the state hash commits to the state
+        assert_eq!(entry_with(3, 10).hash, entry_with(3, 10).hash); // Ensure the state hash is reproducible
+
+        let merged = merge_entries(vec![entry_with(3, 10), entry_with(1, 2)]); // Merge a state with a lower balance
+
+        assert_eq!(merged.data.nonces[&account], 3); // Ensure the highest nonce is kept
+        assert_eq!(
+            merged.data.balances[&account],
+            BigUint::from_i64(2).unwrap()
+        ); // Ensure the latest balance is kept
+    }
 }
@@ -7,20 +7,22 @@ use std::{
 }; // Import the collections library
 
 use chrono; // Import time library
-use crate::ed25519_dalek::Signer;
 use num::{bigint::BigUint, Zero}; // Add support for large unsigned integers
 
-use bincode;
 use serde::{Deserialize, Serialize}; // Import serde serialization
 use serde_json; // Import serde json // Import serde bincode
 
+use super::encoding::{Canonical, EncodingError}; // Import the canonical encoding
+use super::payload::{ContentType, Payload}; // Import the payload type
 use super::receipt::{self, Receipt, ReceiptMap}; // Import receipt types
 use super::signature; // Import signature type
+use super::stamp; // Import the proof-of-work stamp type
 use super::state::{self, Entry}; // Import the state entry types
 
+use super::super::vm::runtime; // Import the contract runtime
+
 use super::super::super::{
     common::address,
-    crypto::blake3,
     crypto::hash::{self, Hash},
 }; // Import the hash & address modules
 
@@ -38,6 +40,19 @@ pub enum SignatureError {
     SerializationFailure,
 }
 
+/// An error encountered while building a tx.
+#[derive(Debug, Fail)]
+pub enum BuilderError {
+    #[fail(display = "transaction is missing a {}", field)]
+    MissingField {
+        field: String, // The name of the missing field
+    },
+    #[fail(display = "failed to sign the transaction: {}", error)]
+    SignatureFailure {
+        error: SignatureError, // The error
+    },
+}
+
 /// A transaction between two different addresses on the SummerCash network.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Transaction {
@@ -47,14 +62,27 @@ pub struct Transaction {
     pub hash: hash::Hash,
     /// The transaction's signature
     pub signature: Option<signature::Signature>,
-    /// The address of the deployed contract (if applicable)
+    /// The address of the deployed contract (if applicable). Informational only, since it isn't signed (see
+    /// `creates_contract`)
     pub deployed_contract_address: Option<address::Address>,
-    /// Whether or not this transaction creates a contract
+    /// Whether or not this transaction creates a contract. Informational only, since it isn't signed (see
+    /// `creates_contract`)
     pub contract_creation: bool,
     /// Whether or not this transaction is the network genesis
     pub genesis: bool,
+    /// A proof that work was spent on the transaction, which networks may demand in lieu of a fee
+    #[serde(default)]
+    pub stamp: Option<stamp::Stamp>,
 }
 
+/// The prepared code of a deployed contract, the storage left behind by its deploy function, and the result of
+/// running it.
+type Deployment = (
+    Vec<u8>,
+    collections::HashMap<String, Vec<u8>>,
+    runtime::ExecutionResult,
+);
+
 /// A container representing the contents of a transaction.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct TransactionData {
@@ -67,7 +95,7 @@ pub struct TransactionData {
     /// The amount of finks sent along with the Transaction
     pub value: BigUint,
     /// The data sent to the transaction recipient (i.e. contract call bytecode)
-    pub payload: Vec<u8>,
+    pub payload: Payload,
     /// The hashes of the transaction's parents
     pub parents: Vec<hash::Hash>,
     /// The list of resolved parent receipts
@@ -76,10 +104,78 @@ pub struct TransactionData {
     pub parent_state_hash: Option<hash::Hash>,
     /// The transaction's timestamp
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// The earliest time at which the transaction may be executed (if applicable)
+    #[serde(default)]
+    pub not_before: Option<chrono::DateTime<chrono::Utc>>,
+    /// The hash of a transaction that must be accepted before this one may be executed (if applicable)
+    #[serde(default)]
+    pub after: Option<hash::Hash>,
+}
+
+/// The layout of a transaction in releases that predate the canonical encoding, when transactions were persisted as
+/// raw bincode, or as JSON in the mem directory.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LegacyTransaction {
+    pub(crate) transaction_data: LegacyTransactionData,
+    pub(crate) hash: hash::Hash,
+    pub(crate) signature: Option<signature::Signature>,
+    pub(crate) deployed_contract_address: Option<address::Address>,
+    pub(crate) contract_creation: bool,
+    pub(crate) genesis: bool,
+}
+
+/// The layout of a transaction's contents in releases that predate the canonical encoding, before payloads were typed,
+/// and before transactions could be time-locked or depend on one another.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LegacyTransactionData {
+    pub(crate) nonce: u64,
+    pub(crate) sender: address::Address,
+    pub(crate) recipient: address::Address,
+    pub(crate) value: BigUint,
+    pub(crate) payload: Vec<u8>,
+    pub(crate) parents: Vec<hash::Hash>,
+    pub(crate) parent_receipts: Option<receipt::LegacyReceiptMap>,
+    pub(crate) parent_state_hash: Option<hash::Hash>,
+    pub(crate) timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<LegacyTransaction> for Transaction {
+    fn from(tx: LegacyTransaction) -> Self {
+        let data = tx.transaction_data;
+
+        // Keep the hash that the transaction was shipped with, since it was derived from the old layout
+        Self {
+            transaction_data: TransactionData {
+                nonce: data.nonce,
+                sender: data.sender,
+                recipient: data.recipient,
+                value: data.value,
+                payload: Payload::binary(&data.payload), // Untyped payloads are just bytes
+                parents: data.parents,
+                parent_receipts: data.parent_receipts.map(ReceiptMap::from),
+                parent_state_hash: data.parent_state_hash,
+                timestamp: data.timestamp,
+                not_before: None,
+                after: None,
+            },
+            hash: tx.hash,
+            signature: tx.signature,
+            deployed_contract_address: tx.deployed_contract_address,
+            contract_creation: tx.contract_creation,
+            genesis: tx.genesis,
+            stamp: None,
+        }
+    }
 }
 
 /* BEGIN EXPORTED METHODS */
 
+/// The hash of a transaction is that of the canonical encoding of its contents.
+impl Canonical for TransactionData {}
+
+/// Transactions are shipped between nodes in their canonical encoding.
+impl Canonical for Transaction {}
+
 impl TransactionData {
     /// Serialize a given TransactionData instance into a byte vector.
     pub fn to_bytes(&self) -> Vec<u8> {
@@ -124,29 +220,106 @@ impl Transaction {
         value_finks: BigUint,
         payload: &[u8],
         parents: Vec<hash::Hash>,
+    ) -> Transaction {
+        Transaction::with_payload(
+            nonce,
+            sender,
+            recipient,
+            value_finks,
+            Payload::binary(payload),
+            parents,
+        )
+    }
+
+    /// Initialize a new transaction instance carrying a typed payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The index of the transaction in the sender's set of txs
+    /// * `sender` - The address of the account sending the transaction
+    /// * `recipient` - The address of the account receiving the transaction
+    /// * `value_finks` - The amount of finks that should be sent
+    /// * `payload` - The data sent to the recipient, and the manner in which it should be interpreted
+    /// * `parents` - The hashes of the transaction's parents
+    pub fn with_payload(
+        nonce: u64,
+        sender: address::Address,
+        recipient: address::Address,
+        value_finks: BigUint,
+        payload: Payload,
+        parents: Vec<hash::Hash>,
     ) -> Transaction {
         let transaction_data: TransactionData = TransactionData {
             nonce,                         // Set nonce
             sender,                        // Set sender
             recipient,                     // Set recipient
             value: value_finks,            // Set value (in finks)
-            payload: payload.to_vec(),     // Set payload
+            payload,                       // Set payload
             parents,                       // Set parents
             parent_receipts: None,         // Set parent receipts
             parent_state_hash: None,       // Set parent state hash
             timestamp: chrono::Utc::now(), // Set timestamp
+            not_before: None,              // Set time lock
+            after: None,                   // Set dependency
         }; // Initialize transaction data
 
         Transaction {
-            hash: blake3::hash_slice(&bincode::serialize(&transaction_data).unwrap_or_default()),
+            hash: transaction_data.canonical_hash(),
             transaction_data, // Set transaction data
             signature: None,  // Set signature
             deployed_contract_address: None,
             contract_creation: false, // Set does create contract
             genesis: false,           // Set is genesis
+            stamp: None,              // No work has been done yet
         }
     }
 
+    /// Initialize a new transaction deploying the given WebAssembly module as a contract. The module is carried in a
+    /// contract code payload, and the contract will reside at an address derived from the sender and nonce of the
+    /// transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The index of the transaction in the sender's set of txs
+    /// * `sender` - The address of the account deploying the contract
+    /// * `value_finks` - The amount of finks that should be sent to the contract
+    /// * `code` - The WebAssembly module that should be deployed
+    /// * `parents` - The hashes of the transaction's parents
+    pub fn new_contract_creation(
+        nonce: u64,
+        sender: address::Address,
+        value_finks: BigUint,
+        code: &[u8],
+        parents: Vec<hash::Hash>,
+    ) -> Transaction {
+        let contract_address = runtime::contract_address(&sender, nonce); // Derive the contract's address
+
+        let mut tx = Transaction::with_payload(
+            nonce,
+            sender,
+            contract_address,
+            value_finks,
+            Payload::contract_code(code),
+            parents,
+        ); // Send the code to the contract's address
+        tx.deployed_contract_address = Some(contract_address);
+        tx.contract_creation = true; // Set does create contract
+
+        tx
+    }
+
+    /// Checks whether or not the transaction deploys a contract (i.e. whether its signed payload carries contract
+    /// code).
+    pub fn creates_contract(&self) -> bool {
+        self.transaction_data.payload.content_type == ContentType::ContractCode
+    }
+
+    /// Gets the amount of work proven by the transaction's stamp (i.e. the number of leading zero bits of the stamp's
+    /// work hash). Unstamped transactions don't prove any work.
+    pub fn work(&self) -> u32 {
+        self.stamp.map_or(0, |stamp| stamp.work(self.hash))
+    }
+
     /// Verify the signature attached to a transaction.
     ///
     /// # Example
@@ -174,18 +347,18 @@ impl Transaction {
     /// let recipient = address::Address::from_key_pair(&recipient_keypair); // Derive recipient from recipient key pair
     ///
     /// let tx = &mut transaction::Transaction::new(0, sender, recipient, BigUint::from_i64(0).unwrap(), b"test transaction payload", vec![hash::Hash::new(vec![0; hash::HASH_SIZE])]); // Initialize transaction
-    /// transaction::sign_transaction(sender_keypair, tx).unwrap(); // Sign tx
+    /// transaction::sign_transaction(sender_keypair, tx, "olympia").unwrap(); // Sign tx
     ///
-    /// let sig_valid = tx.verify_signature(); // Verify signature
+    /// let sig_valid = tx.verify_signature("olympia"); // Verify signature
     /// ```
-    pub fn verify_signature(&self) -> bool {
+    pub fn verify_signature(&self, network: &str) -> bool {
         match &self.signature {
-            None => false,                                // Nil signature can't be valid
-            Some(signature) => signature.verify_tx(self), // Verify signature
+            None => false,                                         // Nil signature can't be valid
+            Some(signature) => signature.verify_tx(self, network), // Verify signature
         }
     }
 
-    /// Execute creates a new state entry from the current transaction, regardless of network state. TODO: Support contracts
+    /// Execute creates a new state entry from the current transaction, regardless of network state.
     ///
     /// # Example
     ///
@@ -215,11 +388,142 @@ impl Transaction {
     /// let resulting_state_entry = tx.execute(None); // Must specify a previous state entry if this is not the genesis transaction
     /// ```
     pub fn execute(&self, prev_entry: Option<state::Entry>) -> state::Entry {
+        self.execute_with_receipt(prev_entry).0
+    }
+
+    /// Creates a new state entry from the current transaction, deploying or calling a contract if necessary. A receipt
+    /// describing the execution is returned alongside the state entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `prev_entry` - The state entry preceding the transaction (if not the genesis transaction)
+    pub fn execute_with_receipt(
+        &self,
+        prev_entry: Option<state::Entry>,
+    ) -> (state::Entry, Receipt) {
+        // Carry over all of the contracts deployed so far
+        let (mut contracts, mut storage) = prev_entry
+            .as_ref()
+            .map(|entry| (entry.data.contracts.clone(), entry.data.storage.clone()))
+            .unwrap_or_default();
+
+        let recipient = self.transaction_data.recipient.to_str();
+        let mut execution: Result<runtime::ExecutionResult, runtime::ExecutionError> =
+            Ok(Default::default());
+
+        if self.creates_contract() {
+            // Contracts can only be deployed to the address derived from their deployment
+            if self.transaction_data.recipient
+                != runtime::contract_address(
+                    &self.transaction_data.sender,
+                    self.transaction_data.nonce,
+                )
+                || contracts.contains_key(&recipient)
+            {
+                execution = Err(runtime::ExecutionError::InvalidModule {
+                    error: "invalid contract address".to_owned(),
+                });
+            } else {
+                execution = self
+                    .deploy_contract()
+                    .map(|(code, contract_storage, result)| {
+                        contracts.insert(recipient.clone(), code);
+                        storage.insert(recipient, contract_storage);
+
+                        result
+                    });
+            }
+        } else if let Some(code) = contracts.get(&recipient) {
+            // Call the contract with the payload as its input
+            execution = runtime::execute(
+                code,
+                runtime::CALL_ENTRY_POINT,
+                &self.transaction_data.payload,
+                storage.entry(recipient).or_default(),
+                runtime::DEFAULT_GAS_LIMIT,
+            );
+        }
+
+        // Only move the value of the transaction if its contract ran successfully, but advance the nonce regardless
+        let value = if execution.is_ok() {
+            self.transaction_data.value.clone()
+        } else {
+            BigUint::zero()
+        };
+        let transferred = self.execute_transfer(prev_entry, &value);
+
+        let entry = state::Entry::with_contracts(
+            transferred.data.nonces,
+            transferred.data.balances,
+            contracts,
+            storage,
+        );
+
+        // Describe the outcome of the execution
+        let receipt = match execution {
+            Ok(result) => Receipt {
+                state_hash: entry.hash,
+                logs: result.logs,
+                status: receipt::Status::Succeeded,
+                gas_used: result.gas_used,
+            },
+            Err(e) => {
+                warn!(
+                    "Failed to execute contract in transaction {}: {}",
+                    self.hash, e
+                );
+
+                Receipt {
+                    state_hash: entry.hash,
+                    logs: Vec::new(),
+                    gas_used: if let runtime::ExecutionError::OutOfGas { limit } = e {
+                        limit
+                    } else {
+                        0
+                    },
+                    status: receipt::Status::Failed {
+                        error: e.to_string(),
+                    },
+                }
+            }
+        };
+
+        (entry, receipt)
+    }
+
+    /// Prepares the transaction's payload for deployment, and runs its deploy function, if it has one.
+    fn deploy_contract(&self) -> Result<Deployment, runtime::ExecutionError> {
+        let code = runtime::prepare_code(&self.transaction_data.payload)?; // Validate & meter the contract
+        let mut storage: collections::HashMap<String, Vec<u8>> = collections::HashMap::new();
+
+        // Contracts don't have to define a deploy function
+        let result = match runtime::execute(
+            &code,
+            runtime::DEPLOY_ENTRY_POINT,
+            &[],
+            &mut storage,
+            runtime::DEFAULT_GAS_LIMIT,
+        ) {
+            Ok(result) => result,
+            Err(runtime::ExecutionError::MissingEntryPoint { .. }) => Default::default(),
+            Err(e) => return Err(e),
+        };
+
+        Ok((code, storage, result))
+    }
+
+    /// Moves the given value from the sender of the transaction to its recipient.
+    ///
+    /// # Arguments
+    ///
+    /// * `prev_entry` - The state entry preceding the transaction (if not the genesis transaction)
+    /// * `value` - The amount of finks that should be moved
+    fn execute_transfer(&self, prev_entry: Option<state::Entry>, value: &BigUint) -> state::Entry {
         match prev_entry {
             Some(entry) => {
                 // Execute the transaction, but with no entry data, since there isn't anything in the entry in the first place
                 if entry.data.balances.is_empty() {
-                    return self.execute(None);
+                    return self.execute_transfer(None, value);
                 }
 
                 let mut balances: collections::HashMap<String, BigUint> = entry.data.balances; // Initialize balances map
@@ -230,14 +534,14 @@ impl Transaction {
                     balances
                         .get(&self.transaction_data.sender.to_str())
                         .unwrap_or(&BigUint::zero())
-                        - self.transaction_data.value.clone(),
+                        - value,
                 ); // Subtract transaction value from sender balance
                 balances.insert(
                     self.transaction_data.recipient.to_str(),
                     balances
                         .get(&self.transaction_data.recipient.to_str())
                         .unwrap_or(&BigUint::zero())
-                        + self.transaction_data.value.clone(),
+                        + value,
                 ); // Add transaction value to recipient balance
 
                 // Put the new nonce into the transaction's state
@@ -253,10 +557,7 @@ impl Transaction {
                     collections::HashMap::new(); // Initialize balance map
                 let mut nonces: collections::HashMap<String, u64> = collections::HashMap::new();
 
-                balances.insert(
-                    self.transaction_data.recipient.to_str(),
-                    self.transaction_data.value.clone(),
-                ); // Set recipient balance to tx value
+                balances.insert(self.transaction_data.recipient.to_str(), value.clone()); // Set recipient balance to tx value
 
                 nonces.insert(
                     self.transaction_data.sender.to_str(),
@@ -282,24 +583,45 @@ impl Transaction {
         // Set the transaction's parent state hash so that our peers can verify that we've done some work to get this far
         self.transaction_data.parent_state_hash = Some(merged_parental_state.hash);
 
-        // We'll want to store each of the provided states in some kind of map. A ReceiptMap can be used for this purpose.
-        let mut receipts: ReceiptMap = Default::default();
+        // Use the provided state entries as the parental receipts list
+        self.transaction_data.parent_receipts = Some(parent_receipt_map(parent_entries));
 
-        // Use each of the provided state entries in the transaction
-        for (tx_hash, entry) in parent_entries {
-            receipts.associated_transactions.push(tx_hash);
-            receipts.receipts.push(Receipt {
-                state_hash: entry.hash,
-                logs: Vec::new(),
-            });
-        }
+        // Rehash the transaction
+        self.hash = self.transaction_data.canonical_hash();
+    }
 
-        // Use the generated receipts map as the parental receipts list
-        self.transaction_data.parent_receipts = Some(receipts);
+    /// Locks the transaction until the given time has passed, and / or the transaction with the given hash has been
+    /// accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `not_before` - The earliest time at which the transaction may be executed
+    /// * `after` - The hash of the transaction that must be accepted before this one
+    pub fn register_time_lock(
+        &mut self,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        after: Option<hash::Hash>,
+    ) {
+        // Set the transaction's maturity conditions
+        self.transaction_data.not_before = not_before;
+        self.transaction_data.after = after;
 
         // Rehash the transaction
-        self.hash =
-            blake3::hash_slice(&bincode::serialize(&self.transaction_data).unwrap_or_default());
+        self.hash = self.transaction_data.canonical_hash();
+    }
+
+    /// Checks whether or not the transaction's time lock has elapsed at the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time at which the time lock should be evaluated
+    pub fn time_lock_has_elapsed(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        // If the transaction doesn't have a time lock, it's always mature
+        if let Some(not_before) = self.transaction_data.not_before {
+            now >= not_before
+        } else {
+            true
+        }
     }
 
     /// Persists the transaction to a mem dir in the given data directory.
@@ -320,26 +642,35 @@ impl Transaction {
         Ok(())
     }
 
-    /// Serialize a given transaction instance into a byte vector.
+    /// Serialize a given transaction instance into a byte vector via its canonical encoding.
     pub fn to_bytes(&self) -> Vec<u8> {
-        bincode::serialize(&self).unwrap() // Serialize
+        self.encode() // Serialize
     }
 
-    /// Deserialize a transaction instance from a given byte vector.
-    pub fn from_bytes(b: &[u8]) -> Transaction {
-        bincode::deserialize(b).unwrap() // Deserialize
+    /// Deserialize a transaction instance from a byte vector produced by its canonical encoding, or by a release that
+    /// serialized transactions as raw bincode.
+    pub fn from_bytes(b: &[u8]) -> Result<Transaction, EncodingError> {
+        Transaction::decode(b).or_else(|e| {
+            // Fall back to the layout of older releases
+            bincode::deserialize::<LegacyTransaction>(b)
+                .map(Transaction::from)
+                .map_err(|_| e)
+        }) // Deserialize
     }
 
-    /// Deserialize a transaction from a file stored on the disk.
+    /// Deserialize a transaction from a file stored on the disk, whether it was written by this release or by one that
+    /// predates typed payloads.
     pub fn from_disk_at_data_directory(data_dir: &str, hash: Hash) -> io::Result<Self> {
-        // Open a mem tx json file corresponding to our hash
-        let file = OpenOptions::new().read(true).open(format!(
-            "{}/mem/{}.json",
-            data_dir,
-            hash.to_str()
-        ))?;
+        // Read the mem tx json file corresponding to our hash
+        let contents = fs::read(format!("{}/mem/{}.json", data_dir, hash.to_str()))?;
 
-        Ok(serde_json::from_reader(file)?)
+        match serde_json::from_slice(&contents) {
+            Ok(tx) => Ok(tx),
+            // Fall back to the layout of older releases
+            Err(e) => serde_json::from_slice::<LegacyTransaction>(&contents)
+                .map(Transaction::from)
+                .map_err(|_| e.into()),
+        }
     }
 }
 
@@ -370,11 +701,12 @@ impl Transaction {
 /// let recipient = address::Address::from_key_pair(&recipient_keypair); // Derive recipient from recipient key pair
 ///
 /// let tx = &mut transaction::Transaction::new(0, sender, recipient, BigUint::from_i64(0).unwrap(), b"test transaction payload", vec![hash::Hash::new(vec![0; hash::HASH_SIZE])]); // Initialize transaction
-/// transaction::sign_transaction(sender_keypair, tx).unwrap(); // Sign tx
+/// transaction::sign_transaction(sender_keypair, tx, "olympia").unwrap(); // Sign tx
 /// ```
 pub fn sign_transaction(
     keypair: Keypair,
     transaction: &mut Transaction,
+    network: &str,
 ) -> Result<(), SignatureError> {
     let derived_sender_address = address::Address::from_key_pair(&keypair); // Derive sender address from key pair
 
@@ -385,29 +717,260 @@ pub fn sign_transaction(
         }); // Return error in result
     }
 
-    let signature = signature::Signature {
-        public_key_bytes: bincode::serialize(&keypair.public).unwrap_or_default(),
-        signature_bytes: bincode::serialize(&keypair.sign(&*transaction.hash)).unwrap_or_default(),
-    }; // Initialize signature
+    // Sign the transaction's hash, in the context of a transaction on the given network
+    let signature = signature::SigningContext::new(signature::Domain::Transaction, network)
+        .sign(&keypair, &*transaction.hash);
 
     transaction.signature = Some(signature); // Set signature
 
     Ok(()) // Everything's good, right? I mean, it's not like anyone ever asks or anything. But then, again, in the end, does it really matter? I suppose from the viewpoint that our idea of existence is based purely on perception, this notion would in fact be correct.
 }
 
+/// Assembles a transaction from explicitly provided inputs, without consulting a node or the disk. This allows
+/// transactions to be built and signed entirely offline.
+///
+/// # Example
+///
+/// ```
+/// extern crate num; // Link num library
+/// extern crate rand; // Link rand library
+///
+/// use num::traits::FromPrimitive; // Allow overloading of from_i64()
+/// use num::bigint::BigUint; // Add support for large unsigned integers
+///
+/// use rand::rngs::OsRng; // Import the os's rng
+///
+/// use ed25519_dalek::Keypair; // Import the edwards25519 digital signature library
+///
+/// use summercash::core::types::{payload::Payload, transaction::TransactionBuilder}; // Import the transaction builder
+/// use summercash::{common::address, crypto::hash}; // Import the address library
+///
+/// let mut csprng = OsRng{}; // Generate source of randomness
+///
+/// let sender_keypair: Keypair = Keypair::generate(&mut csprng); // Generate sender key pair
+/// let recipient_keypair: Keypair = Keypair::generate(&mut csprng); // Generate recipient key pair
+///
+/// let tx = TransactionBuilder::new()
+///     .nonce(0)
+///     .sender(address::Address::from_key_pair(&sender_keypair))
+///     .recipient(address::Address::from_key_pair(&recipient_keypair))
+///     .value(BigUint::from_i64(10).unwrap())
+///     .payload(Payload::memo("test transaction payload"))
+///     .parents(vec![hash::Hash::new(vec![0; hash::HASH_SIZE])])
+///     .build_signed(sender_keypair, "olympia")
+///     .unwrap(); // Build & sign the transaction
+/// ```
+#[derive(Clone, Default)]
+pub struct TransactionBuilder {
+    nonce: Option<u64>,
+    sender: Option<address::Address>,
+    recipient: Option<address::Address>,
+    value: BigUint,
+    payload: Payload,
+    parents: Vec<hash::Hash>,
+    parent_receipts: Option<receipt::ReceiptMap>,
+    parent_state_hash: Option<hash::Hash>,
+    timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    not_before: Option<chrono::DateTime<chrono::Utc>>,
+    after: Option<hash::Hash>,
+}
+
+/// Implement a set of transaction builder methods.
+impl TransactionBuilder {
+    /// Initializes a new transaction builder, sending no value and no payload.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the index of the transaction in the sender's set of txs.
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+
+        self
+    }
+
+    /// Sets the address of the account sending the transaction.
+    pub fn sender(mut self, sender: address::Address) -> Self {
+        self.sender = Some(sender);
+
+        self
+    }
+
+    /// Sets the address of the account receiving the transaction.
+    pub fn recipient(mut self, recipient: address::Address) -> Self {
+        self.recipient = Some(recipient);
+
+        self
+    }
+
+    /// Sets the amount of finks sent along with the transaction.
+    pub fn value(mut self, value_finks: BigUint) -> Self {
+        self.value = value_finks;
+
+        self
+    }
+
+    /// Sets the data sent to the transaction recipient.
+    pub fn payload(mut self, payload: Payload) -> Self {
+        self.payload = payload;
+
+        self
+    }
+
+    /// Sets the hashes of the transaction's parents.
+    pub fn parents(mut self, parents: Vec<hash::Hash>) -> Self {
+        self.parents = parents;
+
+        self
+    }
+
+    /// Sets the combined parent state and parent receipts of the transaction directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_state_hash` - The hash of the combined parent state
+    /// * `parent_receipts` - The receipts of each of the transaction's parents
+    pub fn parent_receipts(
+        mut self,
+        parent_state_hash: hash::Hash,
+        parent_receipts: receipt::ReceiptMap,
+    ) -> Self {
+        self.parent_state_hash = Some(parent_state_hash);
+        self.parent_receipts = Some(parent_receipts);
+
+        self
+    }
+
+    /// Derives the combined parent state and parent receipts of the transaction from the states of its parents.
+    ///
+    /// # Arguments
+    ///
+    /// * `merged_parental_state` - A combined state entry representing the total past state
+    /// * `parent_entries` - A list of the transaction's parental state entries
+    pub fn parental_state(
+        self,
+        merged_parental_state: Entry,
+        parent_entries: Vec<(hash::Hash, Entry)>,
+    ) -> Self {
+        let receipts = parent_receipt_map(parent_entries);
+
+        self.parent_receipts(merged_parental_state.hash, receipts)
+    }
+
+    /// Sets the transaction's timestamp. If no timestamp is provided, the time at which the transaction is built is
+    /// used.
+    pub fn timestamp(mut self, timestamp: chrono::DateTime<chrono::Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+
+        self
+    }
+
+    /// Locks the transaction until the given time has passed, and / or the transaction with the given hash has been
+    /// accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `not_before` - The earliest time at which the transaction may be executed
+    /// * `after` - The hash of the transaction that must be accepted before this one
+    pub fn time_lock(
+        mut self,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        after: Option<hash::Hash>,
+    ) -> Self {
+        self.not_before = not_before;
+        self.after = after;
+
+        self
+    }
+
+    /// Assembles an unsigned transaction from the provided inputs.
+    pub fn build(self) -> Result<Transaction, BuilderError> {
+        let missing = |field: &str| BuilderError::MissingField {
+            field: field.to_owned(),
+        };
+
+        let transaction_data = TransactionData {
+            nonce: self.nonce.ok_or_else(|| missing("nonce"))?,
+            sender: self.sender.ok_or_else(|| missing("sender"))?,
+            recipient: self.recipient.ok_or_else(|| missing("recipient"))?,
+            value: self.value,
+            payload: self.payload,
+            parents: self.parents,
+            parent_receipts: self.parent_receipts,
+            parent_state_hash: self.parent_state_hash,
+            timestamp: self.timestamp.unwrap_or_else(chrono::Utc::now),
+            not_before: self.not_before,
+            after: self.after,
+        }; // Initialize transaction data
+
+        Ok(Transaction {
+            hash: transaction_data.canonical_hash(),
+            transaction_data, // Set transaction data
+            signature: None,  // Set signature
+            deployed_contract_address: None,
+            contract_creation: false, // Set does create contract
+            genesis: false,           // Set is genesis
+            stamp: None,              // No work has been done yet
+        })
+    }
+
+    /// Assembles a transaction from the provided inputs, and signs it with the sender's keypair.
+    ///
+    /// # Arguments
+    ///
+    /// * `keypair` - The keypair of the sender of the transaction
+    /// * `network` - The name of the network on which the transaction should be valid
+    pub fn build_signed(
+        self,
+        keypair: Keypair,
+        network: &str,
+    ) -> Result<Transaction, BuilderError> {
+        let mut transaction = self.build()?;
+
+        // Sign the transaction
+        sign_transaction(keypair, &mut transaction, network)
+            .map_err(|e| BuilderError::SignatureFailure { error: e })?;
+
+        Ok(transaction)
+    }
+}
+
 /* END EXPORTED METHODS */
 
+/// Summarizes each of the provided parent states as a receipt, so that they can be stored in a transaction.
+///
+/// # Arguments
+///
+/// * `parent_entries` - A list of the transaction's parental state entries
+fn parent_receipt_map(parent_entries: Vec<(hash::Hash, Entry)>) -> ReceiptMap {
+    // We'll want to store each of the provided states in some kind of map. A ReceiptMap can be used for this purpose.
+    let mut receipts: ReceiptMap = Default::default();
+
+    // Use each of the provided state entries in the transaction
+    for (tx_hash, entry) in parent_entries {
+        receipts.associated_transactions.push(tx_hash);
+        receipts.receipts.push(Receipt {
+            state_hash: entry.hash,
+            ..Default::default()
+        });
+    }
+
+    receipts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*; // Import names from the parent module
 
     use rand::rngs::OsRng; // Import the os's rng
 
+    use super::super::encoding; // Import the canonical encoding
+
     use num::BigRational; // Import the big rational type
 
     use std::{str, str::FromStr}; // Let the bigint library implement from_str
 
-    use super::super::super::super::common::fink; // Import the fink conversion utility
+    use super::super::super::super::common::{self, fink}; // Import the fink conversion & io utilities
 
     #[test]
     fn test_new() {
@@ -426,7 +989,7 @@ mod tests {
         ); // Initialize transaction
 
         assert_eq!(
-            str::from_utf8(transaction.transaction_data.payload.as_slice()).unwrap(),
+            str::from_utf8(&transaction.transaction_data.payload).unwrap(),
             "test transaction payload"
         ); // Ensure payload intact
     }
@@ -447,7 +1010,7 @@ mod tests {
             vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
         ); // Initialize transaction
 
-        sign_transaction(sender_keypair, transaction).unwrap(); // Sign transaction
+        sign_transaction(sender_keypair, transaction, "olympia").unwrap(); // Sign transaction
     }
 
     #[test]
@@ -466,8 +1029,295 @@ mod tests {
             vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
         ); // Initialize transaction
 
-        sign_transaction(sender_keypair, transaction).unwrap(); // Sign transaction
+        sign_transaction(sender_keypair, transaction, "olympia").unwrap(); // Sign transaction
+
+        assert!(transaction.verify_signature("olympia")); // Ensure signature valid
+    }
+
+    #[test]
+    fn test_register_time_lock() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender_keypair: Keypair = Keypair::generate(&mut csprng); // Generate sender key pair
+        let recipient_keypair: Keypair = Keypair::generate(&mut csprng); // Generate recipient key pair
+
+        let transaction = &mut Transaction::new(
+            0,
+            address::Address::from_key_pair(&sender_keypair),
+            address::Address::from_key_pair(&recipient_keypair),
+            fink::convert_smc_to_finks(BigRational::from_str("10/1").unwrap()),
+            b"test transaction payload",
+            vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
+        ); // Initialize transaction
+        let unlocked_hash = transaction.hash;
+
+        let not_before = chrono::Utc::now() + chrono::Duration::days(1);
+        transaction.register_time_lock(Some(not_before), None); // Lock the transaction for a day
+
+        assert_ne!(transaction.hash, unlocked_hash); // Ensure the lock is committed to by the hash
+        assert!(!transaction.time_lock_has_elapsed(chrono::Utc::now())); // Ensure the lock holds
+        assert!(transaction.time_lock_has_elapsed(not_before)); // Ensure the lock releases
+    }
+
+    #[test]
+    fn test_transaction_builder() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender_keypair: Keypair = Keypair::generate(&mut csprng); // Generate sender key pair
+        let recipient_keypair: Keypair = Keypair::generate(&mut csprng); // Generate recipient key pair
+
+        let builder = TransactionBuilder::new()
+            .nonce(0)
+            .sender(address::Address::from_key_pair(&sender_keypair))
+            .recipient(address::Address::from_key_pair(&recipient_keypair))
+            .value(fink::convert_smc_to_finks(
+                BigRational::from_str("10/1").unwrap(),
+            ))
+            .payload(Payload::binary(b"test transaction payload"))
+            .parents(vec![hash::Hash::new(vec![0; hash::HASH_SIZE])])
+            .timestamp(chrono::Utc::now())
+            .parental_state(Entry::default(), vec![]); // Describe the transaction
+
+        let expected = builder.clone().build().unwrap(); // Build an unsigned transaction
+        assert!(expected.signature.is_none()); // Ensure the transaction hasn't been signed
+
+        let built = builder.build_signed(sender_keypair, "olympia").unwrap(); // Build a signed transaction
+        assert!(built.verify_signature("olympia")); // Ensure the signature is valid
+
+        // Assemble the same transaction the way the RPC server would
+        let mut assembled = Transaction::new(
+            0,
+            expected.transaction_data.sender,
+            expected.transaction_data.recipient,
+            expected.transaction_data.value.clone(),
+            b"test transaction payload",
+            vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
+        );
+        assembled.transaction_data.timestamp = expected.transaction_data.timestamp;
+        assembled.register_parental_state(Entry::default(), vec![]);
+
+        assert_eq!(built.hash, expected.hash); // Ensure signing doesn't change the transaction's hash
+        assert_eq!(assembled.hash, expected.hash); // Ensure the builder agrees with the RPC server
+
+        assert!(TransactionBuilder::new()
+            .sender(expected.transaction_data.sender)
+            .recipient(expected.transaction_data.recipient)
+            .build()
+            .is_err()); // Ensure the nonce must be provided explicitly
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut transaction = TransactionBuilder::new()
+            .nonce(0)
+            .sender(address::Address::default())
+            .recipient(address::Address::default())
+            .build()
+            .unwrap(); // Make a transaction
+        transaction.stamp = Some(stamp::Stamp::mint(transaction.hash, 4)); // Stamp the transaction
+
+        assert_eq!(transaction.encode()[0], encoding::CURRENT_VERSION); // Ensure the transaction is encoded with the current layout
+        assert_eq!(
+            Transaction::decode(&transaction.encode()).unwrap().stamp,
+            transaction.stamp
+        ); // Ensure the stamp survives a round trip
+        assert_eq!(
+            Transaction::from_bytes(&transaction.to_bytes())
+                .unwrap()
+                .hash,
+            transaction.hash
+        ); // Ensure the transaction survives a round trip
+    }
+
+    #[test]
+    fn test_read_legacy() {
+        let legacy = || LegacyTransaction {
+            transaction_data: LegacyTransactionData {
+                nonce: 0,
+                sender: address::Address::default(),
+                recipient: address::Address::default(),
+                value: BigUint::from(10u32),
+                payload: b"test transaction payload".to_vec(),
+                parents: vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
+                parent_receipts: Some(receipt::LegacyReceiptMap {
+                    associated_transactions: vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
+                    receipts: vec![receipt::LegacyReceipt {
+                        state_hash: hash::Hash::new(vec![2; hash::HASH_SIZE]),
+                        logs: Vec::new(),
+                    }],
+                }),
+                parent_state_hash: None,
+                timestamp: chrono::Utc::now(),
+            },
+            hash: hash::Hash::new(vec![1; hash::HASH_SIZE]),
+            signature: None,
+            deployed_contract_address: None,
+            contract_creation: false,
+            genesis: false,
+        }; // A transaction written by a release that predates typed payloads
+
+        // Ensure transactions serialized as raw bincode can still be read
+        let decoded = Transaction::from_bytes(&bincode::serialize(&legacy()).unwrap()).unwrap();
+        assert_eq!(decoded.hash, hash::Hash::new(vec![1; hash::HASH_SIZE])); // Ensure the transaction kept its hash
+        assert_eq!(
+            decoded.transaction_data.payload,
+            Payload::binary(b"test transaction payload")
+        ); // Ensure the payload was read as binary
+        assert!(decoded.transaction_data.not_before.is_none()); // Ensure the transaction isn't time-locked
+        assert!(decoded.stamp.is_none()); // Ensure the transaction hasn't been stamped
+        assert_eq!(
+            decoded.transaction_data.parent_receipts.unwrap().receipts[0].status,
+            receipt::Status::Succeeded
+        ); // Ensure the parent receipt survived the migration
+
+        // Ensure transactions persisted to the mem directory as JSON can still be read
+        let data_dir = common::io::format_data_dir(".tests/legacy_tx");
+        fs::create_dir_all(format!("{}/mem", data_dir)).unwrap();
+        fs::write(
+            format!(
+                "{}/mem/{}.json",
+                data_dir,
+                hash::Hash::new(vec![1; hash::HASH_SIZE]).to_str()
+            ),
+            serde_json::to_vec_pretty(&legacy()).unwrap(),
+        )
+        .unwrap();
+        let read = Transaction::from_disk_at_data_directory(
+            &data_dir,
+            hash::Hash::new(vec![1; hash::HASH_SIZE]),
+        )
+        .unwrap();
+        assert_eq!(
+            read.transaction_data.payload,
+            Payload::binary(b"test transaction payload")
+        );
+
+        // Ensure transactions written by this release are read as they were written
+        read.to_disk_at_data_directory(&data_dir).unwrap();
+        assert_eq!(
+            Transaction::from_disk_at_data_directory(&data_dir, read.hash)
+                .unwrap()
+                .transaction_data
+                .parent_receipts
+                .map(|receipts| receipts.receipts.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_execute_contract() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender = address::Address::from_key_pair(&Keypair::generate(&mut csprng)); // Generate a sender
+
+        // A contract that logs its input, and remembers it
+        let code = wat::parse_str(
+            r#"
+            (module
+                (import "env" "input_size" (func $input_size (result i32)))
+                (import "env" "input_read" (func $input_read (param i32)))
+                (import "env" "storage_write" (func $storage_write (param i32 i32 i32 i32)))
+                (import "env" "emit_log" (func $emit_log (param i32 i32 i32 i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "last")
+                (func (export "call")
+                    (call $input_read (i32.const 16))
+                    (call $storage_write (i32.const 0) (i32.const 4) (i32.const 16) (call $input_size))
+                    (call $emit_log (i32.const 0) (i32.const 4) (i32.const 16) (call $input_size))))
+            "#,
+        )
+        .unwrap();
+
+        let deployment = Transaction::new_contract_creation(
+            0,
+            sender,
+            BigUint::zero(),
+            &code,
+            vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
+        ); // Initialize the deployment
+        let contract_address = deployment.deployed_contract_address.unwrap();
+
+        let (deployed_state, _) = deployment.execute_with_receipt(None); // Deploy the contract
+        assert!(deployed_state
+            .data
+            .contracts
+            .contains_key(&contract_address.to_str())); // Ensure the contract was deployed
+
+        let call = Transaction::new(
+            1,
+            sender,
+            contract_address,
+            BigUint::zero(),
+            b"hello",
+            vec![deployment.hash],
+        ); // Initialize a call to the contract
+        let (called_state, receipt) = call.execute_with_receipt(Some(deployed_state)); // Call the contract
+
+        assert_eq!(receipt.status, receipt::Status::Succeeded); // Ensure the call succeeded
+        assert_eq!(receipt.logs[0].message, b"hello"); // Ensure the contract emitted a log
+        assert_eq!(
+            called_state
+                .data
+                .storage
+                .get(&contract_address.to_str())
+                .and_then(|storage| storage.get(&bs58::encode(b"last").into_string())),
+            Some(&b"hello".to_vec())
+        ); // Ensure the contract wrote to its storage
+    }
+
+    #[test]
+    fn test_execute_failed_contract() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender = address::Address::from_key_pair(&Keypair::generate(&mut csprng)); // Generate a sender
+
+        let genesis = Transaction::new(
+            0,
+            sender,
+            sender,
+            BigUint::from(100u32),
+            b"genesis",
+            vec![hash::Hash::new(vec![0; hash::HASH_SIZE])],
+        ); // Fund the sender
+        let funded_state = genesis.execute(None);
+
+        let deployment = Transaction::new_contract_creation(
+            1,
+            sender,
+            BigUint::from(10u32),
+            b"not a module",
+            vec![genesis.hash],
+        ); // Initialize a deployment that can't succeed
+        let contract_address = runtime::contract_address(&sender, 1);
+
+        let (failed_state, receipt) = deployment.execute_with_receipt(Some(funded_state)); // Try to deploy the contract
+
+        assert_ne!(receipt.status, receipt::Status::Succeeded); // Ensure the deployment failed
+        assert_eq!(
+            failed_state.data.balances.get(&sender.to_str()),
+            Some(&BigUint::from(100u32))
+        ); // Ensure the value of the deployment was returned to the sender
+        assert_eq!(
+            failed_state.data.balances.get(&contract_address.to_str()),
+            Some(&BigUint::zero())
+        ); // Ensure the contract didn't receive anything
+        assert_eq!(failed_state.data.nonces.get(&sender.to_str()), Some(&1)); // Ensure the nonce was still used
+
+        let mut spoofed = Transaction::new(
+            2,
+            sender,
+            runtime::contract_address(&sender, 2),
+            BigUint::zero(),
+            &wat::parse_str("(module)").unwrap(),
+            vec![deployment.hash],
+        ); // Initialize a binary transaction to a contract address
+        spoofed.contract_creation = true; // Mark the transaction as a deployment, without signing it
 
-        assert!(transaction.verify_signature()); // Ensure signature valid
+        assert!(!spoofed.creates_contract()); // Ensure the unsigned flag is ignored
+        assert!(spoofed
+            .execute(Some(failed_state))
+            .data
+            .contracts
+            .is_empty()); // Ensure nothing was deployed
     }
 }
@@ -0,0 +1 @@
+pub mod runtime; // Export the contract runtime module
@@ -0,0 +1,559 @@
+use std::collections::HashMap; // Import the collections library
+
+use wasm_instrument::{gas_metering, parity_wasm};
+use wasmi::{
+    memory_units::Pages, Externals, FuncInstance, FuncRef, HostError, ImportsBuilder, MemoryRef,
+    Module, ModuleImportResolver, ModuleInstance, RuntimeArgs, RuntimeValue, Signature, Trap,
+    TrapKind, ValueType,
+}; // Import the WebAssembly interpreter
+
+use super::super::{
+    super::{common::address::Address, crypto::blake3},
+    types::logs::Log,
+}; // Import the address & log types
+
+/// The name of the module from which contracts import host functions.
+pub const HOST_MODULE_NAME: &str = "env";
+
+/// The name of the exported function invoked when a contract is called.
+pub const CALL_ENTRY_POINT: &str = "call";
+
+/// The name of the exported function invoked (if present) when a contract is deployed.
+pub const DEPLOY_ENTRY_POINT: &str = "deploy";
+
+/// The name of the memory that each contract must export in order to exchange data with the host.
+pub const MEMORY_EXPORT_NAME: &str = "memory";
+
+/// The maximum number of 64 KiB pages of linear memory that a contract may use.
+pub const MAX_MEMORY_PAGES: u32 = 16;
+
+/// The maximum amount of gas that a single contract execution may consume.
+pub const DEFAULT_GAS_LIMIT: u64 = 10_000_000;
+
+/// The amount of gas charged for each byte written to contract storage, or emitted in a log.
+pub const GAS_PER_WRITTEN_BYTE: u64 = 10;
+
+/// Indices of each of the host functions made available to contracts.
+const GAS_FUNC_INDEX: usize = 0;
+const INPUT_SIZE_FUNC_INDEX: usize = 1;
+const INPUT_READ_FUNC_INDEX: usize = 2;
+const STORAGE_SIZE_FUNC_INDEX: usize = 3;
+const STORAGE_READ_FUNC_INDEX: usize = 4;
+const STORAGE_WRITE_FUNC_INDEX: usize = 5;
+const EMIT_LOG_FUNC_INDEX: usize = 6;
+
+/// An error encountered while deploying or executing a contract.
+#[derive(Debug, Fail)]
+pub enum ExecutionError {
+    #[fail(display = "invalid contract module: {}", error)]
+    InvalidModule {
+        error: String, // The error
+    },
+    #[fail(display = "contract does not export a function named {}", name)]
+    MissingEntryPoint {
+        name: String, // The name of the missing export
+    },
+    #[fail(display = "contract ran out of gas (limit: {})", limit)]
+    OutOfGas {
+        limit: u64, // The gas limit
+    },
+    #[fail(display = "contract execution trapped: {}", error)]
+    Trap {
+        error: String, // The error
+    },
+}
+
+impl HostError for ExecutionError {}
+
+/// The outcome of a successful contract execution.
+#[derive(Clone, Default)]
+pub struct ExecutionResult {
+    /// The logs emitted by the contract
+    pub logs: Vec<Log>,
+    /// The amount of gas consumed by the contract
+    pub gas_used: u64,
+}
+
+/// Resolves imports from the host module to the host functions exposed to contracts.
+struct HostResolver;
+
+impl ModuleImportResolver for HostResolver {
+    /// Resolves the host function with the given name, ensuring that the contract expects the right signature.
+    fn resolve_func(
+        &self,
+        field_name: &str,
+        signature: &Signature,
+    ) -> Result<FuncRef, wasmi::Error> {
+        // Determine which function the contract is asking for, and which signature it must have
+        let (index, expected) = match field_name {
+            "gas" => (GAS_FUNC_INDEX, Signature::new(&[ValueType::I32][..], None)),
+            "input_size" => (
+                INPUT_SIZE_FUNC_INDEX,
+                Signature::new(&[][..], Some(ValueType::I32)),
+            ),
+            "input_read" => (
+                INPUT_READ_FUNC_INDEX,
+                Signature::new(&[ValueType::I32][..], None),
+            ),
+            "storage_size" => (
+                STORAGE_SIZE_FUNC_INDEX,
+                Signature::new(&[ValueType::I32; 2][..], Some(ValueType::I32)),
+            ),
+            "storage_read" => (
+                STORAGE_READ_FUNC_INDEX,
+                Signature::new(&[ValueType::I32; 3][..], None),
+            ),
+            "storage_write" => (
+                STORAGE_WRITE_FUNC_INDEX,
+                Signature::new(&[ValueType::I32; 4][..], None),
+            ),
+            "emit_log" => (
+                EMIT_LOG_FUNC_INDEX,
+                Signature::new(&[ValueType::I32; 4][..], None),
+            ),
+            _ => {
+                return Err(wasmi::Error::Instantiation(format!(
+                    "unknown host function: {}",
+                    field_name
+                )))
+            }
+        };
+
+        // The contract must import the function with the signature we provide
+        if signature != &expected {
+            return Err(wasmi::Error::Instantiation(format!(
+                "host function {} imported with an invalid signature",
+                field_name
+            )));
+        }
+
+        Ok(FuncInstance::alloc_host(expected, index))
+    }
+}
+
+/// The state made available to a contract while it is executing.
+struct ExecutionContext<'a> {
+    /// The input passed to the contract
+    input: &'a [u8],
+    /// The contract's storage
+    storage: &'a mut HashMap<String, Vec<u8>>,
+    /// The memory exported by the contract
+    memory: Option<MemoryRef>,
+    /// The logs emitted so far
+    logs: Vec<Log>,
+    /// The amount of gas consumed so far
+    gas_used: u64,
+    /// The maximum amount of gas that may be consumed
+    gas_limit: u64,
+}
+
+impl<'a> ExecutionContext<'a> {
+    /// Charges the given amount of gas, trapping if the limit has been exceeded.
+    fn charge(&mut self, amount: u64) -> Result<(), Trap> {
+        self.gas_used = self.gas_used.saturating_add(amount);
+
+        if self.gas_used > self.gas_limit {
+            Err(Trap::new(TrapKind::Host(Box::new(
+                ExecutionError::OutOfGas {
+                    limit: self.gas_limit,
+                },
+            ))))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reads a region of the contract's memory.
+    fn read_memory(&self, ptr: i32, len: i32) -> Result<Vec<u8>, Trap> {
+        if let Some(memory) = &self.memory {
+            memory
+                .get(ptr as u32, len as u32 as usize)
+                .map_err(|_| Trap::new(TrapKind::MemoryAccessOutOfBounds))
+        } else {
+            Err(Trap::new(TrapKind::MemoryAccessOutOfBounds))
+        }
+    }
+
+    /// Writes the given bytes to the contract's memory.
+    fn write_memory(&self, ptr: i32, value: &[u8]) -> Result<(), Trap> {
+        if let Some(memory) = &self.memory {
+            memory
+                .set(ptr as u32, value)
+                .map_err(|_| Trap::new(TrapKind::MemoryAccessOutOfBounds))
+        } else {
+            Err(Trap::new(TrapKind::MemoryAccessOutOfBounds))
+        }
+    }
+
+    /// Reads a storage key from the contract's memory, and encodes it such that it can be used in a storage map.
+    fn read_storage_key(&self, ptr: i32, len: i32) -> Result<String, Trap> {
+        Ok(bs58::encode(self.read_memory(ptr, len)?).into_string())
+    }
+}
+
+impl<'a> Externals for ExecutionContext<'a> {
+    /// Invokes the host function with the given index.
+    fn invoke_index(
+        &mut self,
+        index: usize,
+        args: RuntimeArgs,
+    ) -> Result<Option<RuntimeValue>, Trap> {
+        match index {
+            GAS_FUNC_INDEX => {
+                self.charge(args.nth_checked::<i32>(0)? as u32 as u64)?;
+
+                Ok(None)
+            }
+            INPUT_SIZE_FUNC_INDEX => Ok(Some(RuntimeValue::I32(self.input.len() as i32))),
+            INPUT_READ_FUNC_INDEX => {
+                self.write_memory(args.nth_checked(0)?, self.input)?;
+
+                Ok(None)
+            }
+            STORAGE_SIZE_FUNC_INDEX => {
+                let key = self.read_storage_key(args.nth_checked(0)?, args.nth_checked(1)?)?;
+
+                // Missing keys are reported with a negative size
+                Ok(Some(RuntimeValue::I32(
+                    self.storage
+                        .get(&key)
+                        .map_or(-1, |value| value.len() as i32),
+                )))
+            }
+            STORAGE_READ_FUNC_INDEX => {
+                let key = self.read_storage_key(args.nth_checked(0)?, args.nth_checked(1)?)?;
+
+                if let Some(value) = self.storage.get(&key) {
+                    self.write_memory(args.nth_checked(2)?, value)?;
+                }
+
+                Ok(None)
+            }
+            STORAGE_WRITE_FUNC_INDEX => {
+                let key = self.read_storage_key(args.nth_checked(0)?, args.nth_checked(1)?)?;
+                let value = self.read_memory(args.nth_checked(2)?, args.nth_checked(3)?)?;
+
+                // Storage writes are charged per byte, since they persist in the state
+                self.charge((key.len() + value.len()) as u64 * GAS_PER_WRITTEN_BYTE)?;
+                self.storage.insert(key, value);
+
+                Ok(None)
+            }
+            EMIT_LOG_FUNC_INDEX => {
+                let topic = self.read_memory(args.nth_checked(0)?, args.nth_checked(1)?)?;
+                let message = self.read_memory(args.nth_checked(2)?, args.nth_checked(3)?)?;
+
+                // Logs are persisted in receipts, so they're charged per byte, too
+                self.charge((topic.len() + message.len()) as u64 * GAS_PER_WRITTEN_BYTE)?;
+                self.logs.push(Log {
+                    topics: vec![String::from_utf8_lossy(&topic).into_owned()],
+                    message,
+                });
+
+                Ok(None)
+            }
+            _ => Err(Trap::new(TrapKind::UnexpectedSignature)),
+        }
+    }
+}
+
+/// Derives the address at which a contract deployed by the given account with the given nonce will reside.
+///
+/// # Arguments
+///
+/// * `deployer` - The address of the account deploying the contract
+/// * `nonce` - The nonce of the transaction deploying the contract
+pub fn contract_address(deployer: &Address, nonce: u64) -> Address {
+    // Hash the deployer's address alongside the nonce, so that each deployment gets a unique address
+    let mut preimage = deployer.to_vec();
+    preimage.extend_from_slice(&nonce.to_le_bytes());
+
+    blake3::hash_slice(&preimage)
+}
+
+/// Validates the given WebAssembly module, and instruments it such that its execution is metered. The
+/// resulting code is what should be stored in the state.
+///
+/// # Arguments
+///
+/// * `code` - The raw WebAssembly module deployed by a user
+pub fn prepare_code(code: &[u8]) -> Result<Vec<u8>, ExecutionError> {
+    // Parse the module so that we can instrument it
+    let mut module: parity_wasm::elements::Module =
+        parity_wasm::deserialize_buffer(code).map_err(|e: parity_wasm::SerializationError| {
+            ExecutionError::InvalidModule {
+                error: e.to_string(),
+            }
+        })?;
+
+    // Contracts must not run code on instantiation
+    if module.start_section().is_some() {
+        return Err(ExecutionError::InvalidModule {
+            error: "contracts must not declare a start function".to_owned(),
+        });
+    }
+
+    // Contracts must not be able to grow their memory beyond the limit
+    if let Some(memory_section) = module.memory_section_mut() {
+        for memory in memory_section.entries_mut() {
+            let limits = memory.limits();
+            if limits.initial() > MAX_MEMORY_PAGES {
+                return Err(ExecutionError::InvalidModule {
+                    error: format!(
+                        "contracts must not use more than {} pages of memory",
+                        MAX_MEMORY_PAGES
+                    ),
+                });
+            }
+
+            *memory = parity_wasm::elements::MemoryType::new(
+                limits.initial(),
+                Some(
+                    limits
+                        .maximum()
+                        .map_or(MAX_MEMORY_PAGES, |maximum| maximum.min(MAX_MEMORY_PAGES)),
+                ),
+            );
+        }
+    }
+
+    // Charge gas at the start of each metered block
+    let instrumented = gas_metering::inject(
+        module,
+        &gas_metering::ConstantCostRules::default(),
+        HOST_MODULE_NAME,
+    )
+    .map_err(|_| ExecutionError::InvalidModule {
+        error: "failed to instrument contract".to_owned(),
+    })?;
+
+    let prepared =
+        parity_wasm::serialize(instrumented).map_err(|e| ExecutionError::InvalidModule {
+            error: e.to_string(),
+        })?;
+
+    // Make sure the interpreter will actually accept the module
+    let loaded = Module::from_buffer(&prepared).map_err(|e| ExecutionError::InvalidModule {
+        error: e.to_string(),
+    })?;
+
+    // Floating-point results may differ between machines, so contracts must not use them
+    loaded
+        .deny_floating_point()
+        .map_err(|_| ExecutionError::InvalidModule {
+            error: "contracts must not use floating-point instructions".to_owned(),
+        })?;
+
+    Ok(prepared)
+}
+
+/// Executes the given entry point of a prepared contract. Changes to the contract's storage are only applied if the
+/// execution succeeds.
+///
+/// # Arguments
+///
+/// * `code` - The prepared code of the contract (see `prepare_code`)
+/// * `entry_point` - The name of the exported function that should be invoked
+/// * `input` - The input made available to the contract
+/// * `storage` - The contract's storage
+/// * `gas_limit` - The maximum amount of gas that the execution may consume
+pub fn execute(
+    code: &[u8],
+    entry_point: &str,
+    input: &[u8],
+    storage: &mut HashMap<String, Vec<u8>>,
+    gas_limit: u64,
+) -> Result<ExecutionResult, ExecutionError> {
+    // Load the contract's module
+    let module = Module::from_buffer(code).map_err(|e| ExecutionError::InvalidModule {
+        error: e.to_string(),
+    })?;
+
+    // Instantiate the contract, providing it with each of the host functions
+    let not_started = ModuleInstance::new(
+        &module,
+        &ImportsBuilder::new().with_resolver(HOST_MODULE_NAME, &HostResolver),
+    )
+    .map_err(|e| ExecutionError::InvalidModule {
+        error: e.to_string(),
+    })?;
+
+    if not_started.has_start() {
+        return Err(ExecutionError::InvalidModule {
+            error: "contracts must not declare a start function".to_owned(),
+        });
+    }
+
+    let instance = not_started.assert_no_start();
+
+    // The contract must export the entry point
+    if instance.export_by_name(entry_point).is_none() {
+        return Err(ExecutionError::MissingEntryPoint {
+            name: entry_point.to_owned(),
+        });
+    }
+
+    let memory = instance
+        .export_by_name(MEMORY_EXPORT_NAME)
+        .and_then(|export| export.as_memory().cloned());
+
+    // The contract's memory must be limited (see `prepare_code`)
+    if memory.as_ref().map_or(false, |memory| {
+        memory
+            .maximum()
+            .map_or(true, |maximum| maximum > Pages(MAX_MEMORY_PAGES as usize))
+    }) {
+        return Err(ExecutionError::InvalidModule {
+            error: format!(
+                "contracts must not use more than {} pages of memory",
+                MAX_MEMORY_PAGES
+            ),
+        });
+    }
+
+    // Execute the contract against a copy of its storage, so that nothing is written if it fails
+    let mut working_storage = storage.clone();
+    let mut ctx = ExecutionContext {
+        input,
+        storage: &mut working_storage,
+        memory,
+        logs: Vec::new(),
+        gas_used: 0,
+        gas_limit,
+    };
+
+    if let Err(e) = instance.invoke_export(entry_point, &[], &mut ctx) {
+        return Err(if ctx.gas_used > ctx.gas_limit {
+            ExecutionError::OutOfGas { limit: gas_limit }
+        } else {
+            ExecutionError::Trap {
+                error: e.to_string(),
+            }
+        });
+    }
+
+    let result = ExecutionResult {
+        logs: ctx.logs,
+        gas_used: ctx.gas_used,
+    };
+
+    // The execution succeeded; commit the storage changes
+    *storage = working_storage;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // Import names from the parent module
+
+    /// A contract that counts the number of times it has been called, and logs its input.
+    const COUNTER_CONTRACT: &str = r#"
+        (module
+            (import "env" "input_size" (func $input_size (result i32)))
+            (import "env" "input_read" (func $input_read (param i32)))
+            (import "env" "storage_size" (func $storage_size (param i32 i32) (result i32)))
+            (import "env" "storage_read" (func $storage_read (param i32 i32 i32)))
+            (import "env" "storage_write" (func $storage_write (param i32 i32 i32 i32)))
+            (import "env" "emit_log" (func $emit_log (param i32 i32 i32 i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "count")
+            (data (i32.const 8) "input")
+            (func (export "call")
+                (if (i32.ge_s (call $storage_size (i32.const 0) (i32.const 5)) (i32.const 0))
+                    (then (call $storage_read (i32.const 0) (i32.const 5) (i32.const 16))))
+                (i32.store (i32.const 16) (i32.add (i32.load (i32.const 16)) (i32.const 1)))
+                (call $storage_write (i32.const 0) (i32.const 5) (i32.const 16) (i32.const 4))
+                (call $input_read (i32.const 32))
+                (call $emit_log (i32.const 8) (i32.const 5) (i32.const 32) (call $input_size))))
+    "#;
+
+    /// A contract that never terminates.
+    const LOOPING_CONTRACT: &str = r#"
+        (module
+            (func (export "call")
+                (loop $forever (br $forever))))
+    "#;
+
+    /// A contract that tries to grow its memory well beyond the limit, and traps if it succeeds.
+    const GROWING_CONTRACT: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (func (export "call")
+                (if (i32.ne (memory.grow (i32.const 1024)) (i32.const -1))
+                    (then unreachable))))
+    "#;
+
+    #[test]
+    fn test_execute() {
+        let code = prepare_code(&wat::parse_str(COUNTER_CONTRACT).unwrap()).unwrap(); // Deploy the contract
+        let mut storage: HashMap<String, Vec<u8>> = HashMap::new();
+
+        execute(
+            &code,
+            CALL_ENTRY_POINT,
+            b"a",
+            &mut storage,
+            DEFAULT_GAS_LIMIT,
+        )
+        .unwrap(); // Call once
+        let result = execute(
+            &code,
+            CALL_ENTRY_POINT,
+            b"hi",
+            &mut storage,
+            DEFAULT_GAS_LIMIT,
+        )
+        .unwrap(); // Call twice
+
+        assert_eq!(
+            storage.get(&bs58::encode(b"count").into_string()),
+            Some(&vec![2, 0, 0, 0])
+        ); // Ensure the counter was persisted between calls
+        assert_eq!(result.logs[0].topics, vec!["input".to_owned()]); // Ensure the log was emitted
+        assert_eq!(result.logs[0].message, b"hi"); // Ensure the log contains the input
+        assert!(result.gas_used > 0); // Ensure the execution was metered
+    }
+
+    #[test]
+    fn test_execute_out_of_gas() {
+        let code = prepare_code(&wat::parse_str(LOOPING_CONTRACT).unwrap()).unwrap(); // Deploy the contract
+        let mut storage: HashMap<String, Vec<u8>> = HashMap::new();
+
+        match execute(&code, CALL_ENTRY_POINT, &[], &mut storage, 1_000) {
+            Err(ExecutionError::OutOfGas { limit }) => assert_eq!(limit, 1_000),
+            _ => panic!("contract should have run out of gas"),
+        } // Ensure the execution was halted
+    }
+
+    #[test]
+    fn test_execute_memory_limit() {
+        let code = prepare_code(&wat::parse_str(GROWING_CONTRACT).unwrap()).unwrap(); // Deploy the contract
+        let mut storage: HashMap<String, Vec<u8>> = HashMap::new();
+
+        let result = execute(
+            &code,
+            CALL_ENTRY_POINT,
+            &[],
+            &mut storage,
+            DEFAULT_GAS_LIMIT,
+        );
+        assert!(result.is_ok()); // Ensure the contract couldn't grow its memory past the limit
+
+        let oversized = wat::parse_str("(module (memory 1024))").unwrap(); // Start with too much memory
+        assert!(prepare_code(&oversized).is_err()); // Ensure the contract can't be deployed
+    }
+
+    #[test]
+    fn test_prepare_code_floating_point() {
+        let floating = wat::parse_str(
+            r#"(module
+                (func (export "call") (result f32)
+                    f32.const 1.5
+                    f32.const 2.5
+                    f32.add))"#,
+        )
+        .unwrap(); // Add two floats
+        assert!(prepare_code(&floating).is_err()); // Ensure the contract can't be deployed
+    }
+}
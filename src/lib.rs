@@ -38,3 +38,6 @@ extern crate reqwest;
 extern crate ctrlc;
 
 extern crate bs58;
+
+extern crate wasm_instrument;
+extern crate wasmi;
@@ -1,12 +1,16 @@
 use super::super::core::{
     sys::{
         config::{self, Config},
+        policy::VotingPolicy,
         system::{self, System},
+        vote::Vote,
     },
     types::{
-        genesis,
+        encoding::Canonical,
+        genesis, payload,
         receipt::{Receipt, ReceiptMap},
-        transaction::Transaction,
+        stamp,
+        transaction::{sign_transaction, Transaction},
     },
 }; // Import the system module
 use super::super::crypto::blake3; // Import the blake3 hashing module
@@ -21,6 +25,7 @@ use super::{
 };
 use num::Zero;
 use std::{
+    collections::BTreeMap,
     convert::TryInto,
     error::Error,
     io, str,
@@ -191,6 +196,10 @@ pub struct ClientBehavior {
     #[behaviour(ignore)]
     pub(crate) voting_accounts: Vec<Account>,
 
+    /// The policy determining how the client's accounts automatically vote on proposals
+    #[behaviour(ignore)]
+    pub(crate) voting_policy: VotingPolicy,
+
     /// Whether or not the client has completed its publishing process
     #[behaviour(ignore)]
     pub(crate) should_broadcast_dag: bool,
@@ -207,6 +216,14 @@ pub struct ClientBehavior {
     /// The index of the last published node
     #[behaviour(ignore)]
     last_published_tx: usize,
+
+    /// Votes received via pubsub whose signatures have yet to be verified
+    #[behaviour(ignore)]
+    pub(crate) pending_votes: Vec<Vote>,
+
+    /// Transactions downloaded from the network whose signatures have yet to be verified
+    #[behaviour(ignore)]
+    pub(crate) pending_synced_transactions: Vec<Transaction>,
 }
 
 impl ClientBehavior {
@@ -276,28 +293,95 @@ impl ClientBehavior {
 
         // Publish each proposal
         for (i, (id, prop)) in unpublished_proposals.into_iter().enumerate() {
-            // Try to serialize the proposal. If this succeeds, we can try to publish the
-            // proposal.
-            if let Ok(ser) = bincode::serialize(&prop) {
-                // We've got a serialized proposal; publish it
-                self.gossipsub
-                    .publish(Topic::new(floodsub::PROPOSALS_TOPIC.to_owned()), ser);
-
-                // Propose the proposal
-                match rt.propose_proposal(&id) {
-                    Ok(_) => info!("Successfully proposed proposal {}: {}", i, prop.proposal_id),
-                    Err(e) => warn!("Failed to propose proposal {}: {}", prop.proposal_id, e),
-                }
-            } else {
-                warn!(
-                    "Failed to serialize proposal with hash: {}",
-                    prop.proposal_id
-                );
+            // Publish the proposal in its canonical encoding
+            self.gossipsub.publish(
+                Topic::new(floodsub::PROPOSALS_TOPIC.to_owned()),
+                prop.encode(),
+            );
+
+            // Propose the proposal
+            match rt.propose_proposal(&id) {
+                Ok(_) => info!("Successfully proposed proposal {}: {}", i, prop.proposal_id),
+                Err(e) => warn!("Failed to propose proposal {}: {}", prop.proposal_id, e),
             }
         }
 
-        // Clear the runtime of all pending local proposals
+        // Publish each of the cancellations registered locally
+        for cancellation in rt.localized_cancellations.iter() {
+            self.gossipsub.publish(
+                Topic::new(floodsub::CANCELLATIONS_TOPIC.to_owned()),
+                cancellation.encode(),
+            );
+        }
+
+        // Publish each of the votes cast locally, and remember which proposals they were cast on
+        floodsub::publish_votes(rt.localized_votes.clone(), &mut self.gossipsub);
+        let mut affected_proposals = Vec::new();
+
+        for vote in rt.localized_votes.iter() {
+            if !affected_proposals.contains(&vote.target_proposal) {
+                affected_proposals.push(vote.target_proposal);
+            }
+        }
+
+        // Clear the runtime of all pending local proposals, cancellations & votes
         rt.clear_localized_proposals();
+        drop(rt);
+
+        // Try to clear each of the proposals that were voted on
+        for proposal in affected_proposals {
+            let rt = if let Ok(rt) = self.runtime.write() {
+                rt
+            } else {
+                break;
+            };
+
+            if floodsub::potentially_clear_proposal(rt, &proposal) {
+                info!("Successfully cleared proposal {}!", proposal);
+
+                self.publish_dag();
+            }
+        }
+    }
+
+    /// Proposes any held transactions whose time locks or dependencies have since been satisfied.
+    pub fn release_held_transactions(&mut self) {
+        // Get a mutable reference to the client's runtime so that we can move the transactions into the proposal
+        // queue
+        let mut rt = if let Ok(runtime) = self.runtime.write() {
+            runtime
+        } else {
+            return;
+        };
+
+        // Nothing to do if no transactions are being held
+        if rt.held_transactions.is_empty() {
+            return;
+        }
+
+        // Release the matured transactions
+        let n_released = rt.release_mature_transactions();
+
+        if n_released > 0 {
+            info!("Released {} matured transactions", n_released);
+        }
+    }
+
+    /// Closes any proposals whose voting deadlines have passed.
+    pub fn expire_proposals(&mut self) {
+        // Get a mutable reference to the client's runtime so that we can close the proposals
+        let mut rt = if let Ok(runtime) = self.runtime.write() {
+            runtime
+        } else {
+            return;
+        };
+
+        // Close the expired proposals
+        let expired = rt.expire_proposals();
+
+        if !expired.is_empty() {
+            info!("Closed {} proposals whose voting deadlines passed", expired.len());
+        }
     }
 
     /// Publishes a copy of the DAG to the remote.
@@ -392,6 +476,9 @@ pub struct Client {
     /// The list of accounts used to vote on proposals
     pub voting_accounts: Option<Vec<account::Account>>,
 
+    /// The policy determining how the accounts vote on proposals automatically
+    pub voting_policy: VotingPolicy,
+
     /// The client's libp2p peer identity keypair
     pub keypair: identity::Keypair,
 
@@ -496,6 +583,11 @@ impl Client {
             let config = Config {
                 reward_per_gas: config::DEFAULT_REWARD_PER_GAS.into(),
                 network_name: network.into(),
+                chain_id: network.into(),
+                max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+                stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+                approval_thresholds: BTreeMap::new(),
+                history: Vec::new(),
             };
 
             Ok(Client::with_config(keypair, config, data_dir)) // Return initialized client
@@ -525,14 +617,35 @@ impl Client {
     pub fn with_voting_accounts(
         keypair: identity::Keypair,
         cfg: config::Config,
-        voting_accounts: Vec<account::Account>,
+        mut voting_accounts: Vec<account::Account>,
         data_dir: &str,
     ) -> Client {
+        // Read the node's voting policy, falling back to only voting on transactions if there isn't one
+        let voting_policy =
+            VotingPolicy::read_from_disk_at_data_directory(data_dir, &cfg.network_name)
+                .unwrap_or_else(|e| {
+                    if e.kind() != io::ErrorKind::NotFound {
+                        warn!(
+                            "Failed to read the voting policy; only voting on transactions: {}",
+                            e
+                        );
+                    }
+
+                    VotingPolicy::default()
+                });
+
+        // Only vote with the accounts that the policy allows to
+        voting_accounts.retain(|acc| {
+            acc.address()
+                .map_or(false, |address| voting_policy.includes(&address))
+        });
+
         // Return the initialized client inside a result
         Client {
             network: Network::from(&*cfg.network_name),
             runtime: Arc::new(RwLock::new(system::System::with_data_dir(cfg, data_dir))), // Set runtime
             voting_accounts: Some(voting_accounts), // Set voters
+            voting_policy,                          // Set voting policy
             peer_id: PeerId::from_public_key(keypair.public()), // Set peer id
             keypair,
         }
@@ -573,6 +686,9 @@ impl Client {
             return Err(CommunicationError::Custom{error: "DAG is not empty; must not contain any nodes in order to properly generate a genesis block.".to_owned()}.into());
         }
 
+        // The genesis transactions are only valid on this network
+        let network_name = runtime.config.network_name.clone();
+
         // Make the genesis transaction
         let mut root_tx = Transaction::new(
             0,
@@ -587,13 +703,14 @@ impl Client {
         root_tx.genesis = true;
 
         // Execute the root transaction
-        let root_state = root_tx.execute(None);
+        let (root_state, root_receipt) = root_tx.execute_with_receipt(None);
 
         // The hash of the root transaction. We'll update this each time we add a genesis child transaction.
         let (mut last_hash, mut last_state_hash) = (root_tx.hash, root_state.hash);
 
         // Update the global state to reflect the increase in balance
-        runtime.ledger.push(root_tx, Some(root_state));
+        let root_index = runtime.ledger.push(root_tx, Some(root_state));
+        runtime.ledger.record_receipt(root_index, &root_receipt)?;
 
         // The current nonce
         let mut i: usize = 1;
@@ -623,14 +740,17 @@ impl Client {
                 associated_transactions: vec![last_hash],
                 receipts: vec![Receipt {
                     state_hash: last_state_hash,
-                    logs: Vec::new(),
+                    ..Default::default()
                 }],
             });
-            tx.hash =
-                blake3::hash_slice(&bincode::serialize(&tx.transaction_data).unwrap_or_default());
+            tx.hash = tx.transaction_data.canonical_hash();
+
+            // Sign the transaction, since only the root of the ledger may go unsigned
+            sign_transaction(genesis_account.keypair()?, &mut tx, &network_name)?;
 
             // Execute the transaction, and collect its state
-            let state = tx.execute(runtime.ledger.get(i - 1)?.unwrap().state_entry.clone());
+            let (state, receipt) =
+                tx.execute_with_receipt(runtime.ledger.get(i - 1)?.unwrap().state_entry.clone());
 
             // Since we might need to make more transactions, we'll want to keep them as children of this transaction.
             // Update the last_hash & last_state_hash to reflect this.
@@ -638,7 +758,8 @@ impl Client {
             last_state_hash = state.hash;
 
             // Instantly resolve the state for this transaction, since we'll add a finalizing tx next
-            runtime.ledger.push(tx, Some(state));
+            let index = runtime.ledger.push(tx, Some(state));
+            runtime.ledger.record_receipt(index, &receipt)?;
 
             i += 1;
         }
@@ -653,6 +774,8 @@ impl Client {
             vec![last_hash],
         );
         finalization.transaction_data.parent_state_hash = Some(last_state_hash);
+        finalization.hash = finalization.transaction_data.canonical_hash();
+        sign_transaction(genesis_account.keypair()?, &mut finalization, &network_name)?;
 
         // Put the transaction in the ledger. This means we're done!
         runtime.ledger.push(finalization, None);
@@ -675,6 +798,7 @@ impl Client {
         let mut sub = Floodsub::new(self.peer_id.clone());
         sub.subscribe(Topic::new(floodsub::PROPOSALS_TOPIC.to_owned()));
         sub.subscribe(Topic::new(floodsub::VOTES_TOPIC.to_owned()));
+        sub.subscribe(Topic::new(floodsub::CANCELLATIONS_TOPIC.to_owned()));
 
         // Move the accounts stored in the client into the ClientBehavior
         let accounts = if let Some(taken_accounts) = self.voting_accounts.take() {
@@ -703,6 +827,7 @@ impl Client {
             network: self.network,
             runtime: self.runtime.clone(),
             voting_accounts: accounts,
+            voting_policy: self.voting_policy.clone(),
             should_broadcast_dag: false,
             proposal_queue_full: if let Ok(rt) = self.runtime.read() {
                 rt.get_state_ref()
@@ -710,6 +835,8 @@ impl Client {
                 Arc::new(AtomicBool::new(false))
             },
             last_published_tx: 0,
+            pending_votes: Vec::new(),
+            pending_synced_transactions: Vec::new(),
         };
 
         let mut swarm = Swarm::new(
@@ -782,6 +909,19 @@ impl Client {
                             swarm.publish_dag();
                         }
 
+                        // Move any transactions that have matured into the proposal queue
+                        swarm.release_held_transactions();
+
+                        // Stop waiting on votes for proposals whose deadlines have passed
+                        swarm.expire_proposals();
+
+                        // Verify & execute downloaded transactions once a full synchronization round has arrived
+                        if swarm.pending_synced_transactions.len()
+                            >= sync::TRANSACTIONS_PER_SYNCHRONIZATION_ROUND as usize
+                        {
+                            swarm.execute_synced_transactions();
+                        }
+
                         // If there are transactions that we should be publishing, do just that
                         if !swarm.transaction_queue_is_empty() {
                             swarm.clear_transaction_queue();
@@ -792,6 +932,16 @@ impl Client {
                             Poll::Ready(Some(e)) => debug!("{:?}", e),
                             Poll::Ready(None) => return Poll::Ready(Ok(())),
                             Poll::Pending => {
+                                // Now that the burst of incoming messages is over, verify the votes & transactions
+                                // that arrived in it. Doing so may produce more events, so keep polling if it did
+                                // anything.
+                                if swarm.register_pending_votes()
+                                    + swarm.execute_synced_transactions()
+                                    > 0
+                                {
+                                    continue;
+                                }
+
                                 if !listening {
                                     for addr in Swarm::listeners(&swarm) {
                                         // Print out that we're listening on the address
@@ -836,6 +986,11 @@ mod tests {
         let config = config::Config {
             reward_per_gas: BigUint::from_str("10000000000000000000000000000000000000000").unwrap(), // Venezuela style
             network_name: "olympia".to_owned(),
+            chain_id: "olympia".to_owned(),
+            max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+            stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+            approval_thresholds: BTreeMap::new(),
+            history: Vec::new(),
         }; // Initialize config
 
         config.write_to_disk().unwrap(); // Write config to disk
@@ -1,20 +1,18 @@
 use super::{
     super::{
-        core::sys::{
-            proposal::{Operation, Proposal},
-            system::System,
-            vote::Vote,
+        core::{
+            sys::{cancellation::Cancellation, proposal::Proposal, system::System, vote::Vote},
+            types::{encoding::Canonical, signature::BatchVerificationError},
         },
         crypto::hash::Hash,
-        validator::{GraphBoundValidator, Validator},
     },
     client::ClientBehavior,
+    rpc::runtime::propose_vote_transaction,
 };
 use libp2p::{
     floodsub::{Floodsub, FloodsubEvent, Topic},
     swarm::NetworkBehaviourEventProcess,
 };
-use num::{bigint::BigUint, CheckedDiv, Zero};
 use std::sync::RwLockWriteGuard;
 
 /// A topic for all proposals in a network.
@@ -23,6 +21,9 @@ pub const PROPOSALS_TOPIC: &str = "proposals";
 /// A topic for all votes in a network.
 pub const VOTES_TOPIC: &str = "votes";
 
+/// A topic for all cancellations of pending transactions in a network.
+pub const CANCELLATIONS_TOPIC: &str = "cancellations";
+
 impl NetworkBehaviourEventProcess<FloodsubEvent> for ClientBehavior {
     /// Wait for an incoming gossipsub message from a known peer. Handle it somehow.
     fn inject_event(&mut self, message: FloodsubEvent) {
@@ -40,7 +41,7 @@ impl NetworkBehaviourEventProcess<FloodsubEvent> for ClientBehavior {
                 debug!("Message is a proposal message; handling it as such");
 
                 // Try to deserialize a proposal from the provided message data. If this fails, we'll want to print the error to stderr.
-                let proposal: Proposal = match bincode::deserialize(&message.data) {
+                let proposal: Proposal = match Proposal::decode(&message.data) {
                     Ok(deserialized) => deserialized,
                     Err(e) => {
                         warn!("Failed to deserialize proposal received via pubsub: {}", e);
@@ -70,48 +71,136 @@ impl NetworkBehaviourEventProcess<FloodsubEvent> for ClientBehavior {
                     proposal.proposal_id
                 );
 
-                // Copy the name of the parameter that the proposal will be changing so that we can vote on it.
-                let param_name = proposal.proposal_data.param_name.clone();
+                // Copy the ID of the proposal so that we can vote on it.
                 let id = proposal.proposal_id;
-                let data = proposal.proposal_data.clone();
 
-                // Add the proposal to the runtime
-                rt.push_proposal(proposal);
+                // Refuse to vote for transactions that their senders have since replaced or cancelled
+                if rt.proposal_is_superseded(&id) {
+                    info!("Ignoring proposal {}: superseded by its sender", id);
+
+                    return;
+                }
+
+                // Don't reopen proposals that have already been closed
+                if let Some(state) = rt.proposal_state(&id).filter(|state| !state.is_open()) {
+                    info!("Ignoring proposal {}: already {}", id, state);
+
+                    return;
+                }
+
+                // Drop proposals that can't be voted on, or that could be voted on forever
+                if !proposal.voting_period_is_valid() || proposal.is_expired(chrono::Utc::now()) {
+                    warn!(
+                        "Dropping proposal {}: its voting period ({} to {}) is invalid or has ended",
+                        id, proposal.created_at, proposal.voting_deadline
+                    );
+
+                    return;
+                }
+
+                // Drop copies of proposals that claim IDs that weren't derived from their contents
+                if !proposal.id_is_valid() {
+                    warn!(
+                        "Dropping proposal {}: its ID doesn't match its contents",
+                        id
+                    );
+
+                    return;
+                }
+
+                // Derive the transaction that the proposal would append to the ledger, if it is a transaction proposal
+                let tx = proposal.proposed_transaction();
+
+                // Drop transactions that don't prove enough work before resolving any state for them
+                if let Some(tx) = &tx {
+                    if tx.work() < u32::from(rt.config.stamp_difficulty) {
+                        warn!(
+                            "Dropping proposal {}: transaction {} proves insufficient work ({} bits; the minimum is {})",
+                            id,
+                            tx.hash,
+                            tx.work(),
+                            rt.config.stamp_difficulty
+                        );
 
-                // If this is a proposal that we can automatically vote on, do it.
-                if param_name == "ledger::transactions" {
-                    // Get the data stored in the proposal
-                    let tx_bytes = if let Operation::Append { value_to_append } = data.operation {
-                        value_to_append
-                    } else {
                         return;
-                    };
+                    }
+                }
 
-                    // Derive a transaction from the data
-                    let tx = if let Ok(deserialized) = bincode::deserialize(&tx_bytes) {
-                        deserialized
+                // Drop proposals that can't be attributed to anyone
+                let proposer =
+                    if let Some(proposer) = proposal.verified_proposer(rt.config.chain_id()) {
+                        proposer
                     } else {
+                        warn!("Dropping proposal {}: it isn't signed by its proposer", id);
+
                         return;
                     };
 
+                // Drop proposals scheduled to take effect at a point that the ledger has already reached
+                if let Err(e) = rt.check_schedule(&proposal) {
+                    warn!("Dropping proposal {} by {}: {}", id, proposer, e);
+
+                    return;
+                }
+
+                // Drop proposals whose votes would be weighed at a stale or unknown snapshot
+                if let Err(e) = rt.check_snapshot(&proposal) {
+                    warn!("Dropping proposal {} by {}: {}", id, proposer, e);
+
+                    return;
+                }
+
+                // Drop proposals whose proposers can't afford to lock their deposits
+                if let Err(e) = rt.check_deposit(&proposal) {
+                    warn!("Dropping proposal {} by {}: {}", id, proposer, e);
+
+                    return;
+                }
+
+                if let Some(tx) = tx {
+                    // Hold the transaction in the mempool until the proposal is settled
+                    if let Err(e) = rt.admit_transaction(tx) {
+                        warn!("Dropping proposal {}: {}", id, e);
+
+                        return;
+                    }
+                }
+
+                // Add the proposal to the runtime
+                rt.push_proposal(proposal.clone());
+
+                // If this is a proposal that our voting policy says how to vote on, do it.
+                if let Some(decision) = self.voting_policy.evaluate(&rt, &proposal) {
                     // The votes that we've generated for the proposal from each votinig account
                     let mut resultant_votes: Vec<Vote> = Vec::new();
 
                     // Print out the beginning voting process
-                    info!("Automatically verifying, and voting in accordance to the result of the output of the chosen validator with {} accounts", self.voting_accounts.len());
+                    info!("Automatically voting on proposal {} in accordance with the voting policy with {} accounts", id, self.voting_accounts.len());
 
                     // Vote for the proposal with each voting account
                     for i in 0..self.voting_accounts.len() {
                         // Try to get a keypair for the account that we can use to vote with
                         if let Ok(keypair) = self.voting_accounts[i].keypair() {
-                            // Make a validator for the transaction
-                            let validator = GraphBoundValidator::new(&rt.ledger);
-
-                            // See if the transaction is valid or not
-                            let reason = validator.transaction_is_valid(&tx);
-
                             // Make the vote
-                            let vote = Vote::new(id, reason.is_ok(), keypair);
+                            let vote =
+                                Vote::new(id, decision.in_favor, keypair, rt.config.chain_id());
+
+                            // Votes that only count once they're on the ledger are published in a transaction
+                            if proposal.is_voted_on_ledger() {
+                                let tx_keypair = match self.voting_accounts[i].keypair() {
+                                    Ok(k) => k,
+                                    Err(_) => continue,
+                                };
+
+                                match propose_vote_transaction(&mut rt, &vote, tx_keypair) {
+                                    Ok(_) => info!(
+                                        "Successfully submitted vote for proposal {}: {} because {}",
+                                        id, vote.in_favor, decision.reason),
+                                    Err(e) => warn!("Failed to vote for proposal {}: {}", id, e.message),
+                                }
+
+                                continue;
+                            }
 
                             // Save the vote for later so we can publish it
                             resultant_votes.push(vote.clone());
@@ -119,9 +208,11 @@ impl NetworkBehaviourEventProcess<FloodsubEvent> for ClientBehavior {
                             // Register the vote
                             match rt.register_vote_for_proposal(id, &vote) {
                                 Ok(_) => {
+                                    rt.record_own_vote(&vote);
+
                                     info!(
                                         "Successfully submitted vote for proposal {}: {} because {}",
-                                        id, vote.in_favor, if let Some(e) = reason.err() {format!("{}", e)} else {"transaction is valid".to_owned()});
+                                        id, vote.in_favor, decision.reason);
                                 }
                                 Err(e) => warn!("Failed to vote for proposal {}: {}", id, e),
                             }
@@ -130,9 +221,7 @@ impl NetworkBehaviourEventProcess<FloodsubEvent> for ClientBehavior {
 
                     // Publish each of the votes that we collected from the unlocked
                     // accounts
-                    if let Err(e) = publish_votes(resultant_votes, &mut self.gossipsub) {
-                        warn!("Failed to publish votes: {}", e);
-                    }
+                    publish_votes(resultant_votes, &mut self.gossipsub);
 
                     // Try to clear the proposal
                     if potentially_clear_proposal(rt, &id) {
@@ -146,8 +235,8 @@ impl NetworkBehaviourEventProcess<FloodsubEvent> for ClientBehavior {
             } else if message.topics[0].id() == VOTES_TOPIC {
                 debug!("Message is a vote message; handling it as such");
 
-                // Deserialize the vote that was sent to us via pubsub, encoded with bincode
-                let vote: Vote = match bincode::deserialize(&message.data) {
+                // Deserialize the vote that was sent to us via pubsub, in its canonical encoding
+                let vote: Vote = match Vote::decode(&message.data) {
                     Ok(v) => v,
                     Err(e) => {
                         warn!("Failed to deserialize vote received via pubsub: {}", e);
@@ -156,22 +245,88 @@ impl NetworkBehaviourEventProcess<FloodsubEvent> for ClientBehavior {
                     }
                 };
 
-                // If the vote isn't valid, we must not continue
-                if !vote.valid() {
-                    warn!("Received invalid vote: {}", vote.hash());
+                // Hold onto the vote until the rest of the votes in this burst arrive, so that they can be verified
+                // together
+                self.pending_votes.push(vote);
+            } else if message.topics[0].id() == CANCELLATIONS_TOPIC {
+                debug!("Message is a cancellation message; handling it as such");
 
-                    return;
-                }
+                // Deserialize the cancellation that was sent to us via pubsub, in its canonical encoding
+                let cancellation: Cancellation = match Cancellation::decode(&message.data) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        warn!(
+                            "Failed to deserialize cancellation received via pubsub: {}",
+                            e
+                        );
 
-                // Eventually, we'll want to register this vote in the runtime
-                let mut rt = if let Ok(rt) = self.runtime.write() {
-                    rt
-                } else {
-                    warn!("Failed to obtain a lock on the client's runtime. Aborting incoming vote registration process.");
+                        return;
+                    }
+                };
 
-                    return;
+                // Get a writing lock on the client's runtime so that we can withdraw the transaction
+                let mut rt = match self.runtime.write() {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        warn!(
+                            "Failed to obtain a writing lock on the client's runtime: {}",
+                            e
+                        );
+
+                        return;
+                    }
                 };
 
+                // Withdraw the transaction, if its sender really did ask for it to be withdrawn
+                match rt.cancel_transaction(&cancellation) {
+                    Ok(tx) => info!("Withdrew transaction {} at its sender's request", tx.hash),
+                    Err(e) => warn!("Refusing {}: {}", cancellation, e),
+                }
+            }
+        }
+    }
+}
+
+impl ClientBehavior {
+    /// Verifies the signatures of each of the votes received since the last call in a batch, and registers the valid
+    /// votes with the runtime. Returns the number of votes that were processed.
+    pub(crate) fn register_pending_votes(&mut self) -> usize {
+        // Take the votes that we've received so far
+        let votes: Vec<Vote> = self.pending_votes.drain(..).collect();
+
+        if votes.is_empty() {
+            return 0;
+        }
+
+        // The proposals that the votes have been cast on, which we may now be able to clear
+        let mut affected_proposals: Vec<Hash> = Vec::new();
+
+        {
+            // Eventually, we'll want to register these votes in the runtime
+            let mut rt = if let Ok(rt) = self.runtime.write() {
+                rt
+            } else {
+                warn!("Failed to obtain a lock on the client's runtime. Aborting incoming vote registration process.");
+
+                return votes.len();
+            };
+
+            // Find any votes that aren't valid on this network, so that we don't register them
+            let invalid_indices = match Vote::verify_batch(&votes, rt.config.chain_id()) {
+                Ok(_) => Vec::new(),
+                Err(BatchVerificationError::InvalidSignatures { invalid_indices }) => {
+                    invalid_indices
+                }
+            };
+
+            for (i, vote) in votes.iter().enumerate() {
+                // If the vote isn't valid, we must not register it
+                if invalid_indices.contains(&i) {
+                    warn!("Received invalid vote: {}", vote.hash());
+
+                    continue;
+                }
+
                 // The proposal that the vote is in favor or against should exist in the runtime.
                 // Otherwise, it is invalid.
                 if !rt.pending_proposals.contains_key(&vote.target_proposal) {
@@ -180,68 +335,64 @@ impl NetworkBehaviourEventProcess<FloodsubEvent> for ClientBehavior {
                         vote.hash()
                     );
 
-                    return;
+                    continue;
                 }
 
-                // Collect metadata regarding the vote so that we can alert the user of the vote in
-                // the future
-                let vote_value: String = format!("{}", vote);
-                let vote_hash = vote.hash();
-
-                // Register the vote, and log any errors that come up along the way
-                if let Err(e) = rt.register_vote_for_proposal(vote.target_proposal, &vote) {
-                    warn!("Failed to register vote {}: {}", vote_hash, e);
+                // Register the vote, and log any errors that come up along the way. We've already verified its
+                // signature.
+                if let Err(e) = rt.register_verified_vote_for_proposal(vote.target_proposal, vote) {
+                    warn!("Failed to register vote {}: {}", vote.hash(), e);
 
-                    return;
+                    continue;
                 }
 
                 // Log the success!
                 info!(
                     "Received a new vote: {}; registered it with the runtime successfully",
-                    vote_value
+                    vote
                 );
 
-                // Try to clear the proposal
-                if potentially_clear_proposal(rt, &vote.target_proposal) {
-                    info!("Successfully cleared proposal {}!", vote.target_proposal);
-
-                    self.publish_dag();
-                } else {
-                    debug!("Proposal {} is not mature enough...", vote.target_proposal);
+                if !affected_proposals.contains(&vote.target_proposal) {
+                    affected_proposals.push(vote.target_proposal);
                 }
             }
         }
+
+        // Try to clear each of the proposals that were voted on
+        for proposal in affected_proposals {
+            let rt = if let Ok(rt) = self.runtime.write() {
+                rt
+            } else {
+                break;
+            };
+
+            if potentially_clear_proposal(rt, &proposal) {
+                info!("Successfully cleared proposal {}!", proposal);
+
+                self.publish_dag();
+            } else {
+                debug!("Proposal {} is not mature enough...", proposal);
+            }
+        }
+
+        votes.len()
     }
 }
 
 /// Publishes each of the provided votes via pubsub, using the provided floodsub adapter.
-/// This helper method terminates execution on the first error.
 ///
 /// # Arguments
 ///
 /// * `votes` - The votes that should be published
 /// * `adapter` - The floodsub instance that the votes will be published with
-pub(crate) fn publish_votes(votes: Vec<Vote>, adapter: &mut Floodsub) -> bincode::Result<()> {
+pub(crate) fn publish_votes(votes: Vec<Vote>, adapter: &mut Floodsub) {
     for vote in votes.iter() {
-        match bincode::serialize(vote) {
-            Ok(serialized) => adapter.publish(Topic::new(VOTES_TOPIC), serialized),
-            Err(e) => {
-                warn!(
-                    "Failed to serialize and publish vote {}: {}",
-                    vote.hash(),
-                    e
-                );
-
-                return Err(e);
-            }
-        }
+        adapter.publish(Topic::new(VOTES_TOPIC), vote.encode());
     }
-
-    Ok(())
 }
 
-/// Attempts to execute the given proposal, on the condition that it has enough vote weight to
-/// pass.
+/// Attempts to execute the given proposal, on the condition that it has met the threshold of the
+/// parameter that it targets, or to reject it, on the condition that it can no longer do so.
 ///
 /// # Arguments
 ///
@@ -252,47 +403,5 @@ pub(crate) fn potentially_clear_proposal(
     mut runtime: RwLockWriteGuard<System>,
     proposal: &Hash,
 ) -> bool {
-    // Get the # of coins that the proposer must have, at least, in order to execute it
-    let acceptable_majority = if let Some(maj) = runtime
-        .ledger
-        .overall_issuance()
-        .checked_div(&BigUint::from(2 as u8))
-    {
-        maj
-    } else {
-        return false;
-    };
-
-    // In order to execute the proposal, we must have a 1/2 majority
-    if runtime
-        .get_coins_in_support_of(proposal)
-        .to_biguint()
-        .unwrap_or_else(BigUint::zero)
-        >= acceptable_majority
-    {
-        // If the proposal is invalid, don't execute it, but clear it, nonetheless
-        if !runtime.validate_proposal(proposal) {
-            runtime.pending_proposals.remove(proposal);
-
-            return false;
-        }
-
-        // Execute the proposal
-        return match runtime.execute_proposal(*proposal) {
-            Ok(()) => true,
-            Err(e) => {
-                warn!("Failed to execute proposal {}: {}", proposal, e);
-
-                false
-            }
-        };
-    } else if (-runtime.get_coins_in_support_of(proposal))
-        .to_biguint()
-        .unwrap_or_else(BigUint::zero)
-        >= acceptable_majority
-    {
-        return runtime.pending_proposals.remove(proposal).is_some();
-    }
-
-    false
+    runtime.clear_proposal(proposal)
 }
@@ -1,11 +1,5 @@
 use super::{
-    super::{
-        core::{
-            sys::proposal::{Operation, Proposal, ProposalData},
-            types::transaction::Transaction,
-        },
-        crypto::hash::Hash,
-    },
+    super::{core::types::transaction::Transaction, crypto::hash::Hash},
     client::ClientBehavior,
     sync,
 };
@@ -67,7 +61,7 @@ impl NetworkBehaviourEventProcess<KademliaEvent> for ClientBehavior {
                                 {
                                     // Deserialize the transaction that the peer responded with
                                     let tx: Transaction =
-                                        if let Ok(val) = bincode::deserialize::<Transaction>(&record.value) {
+                                        if let Ok(val) = Transaction::from_bytes(&record.value) {
                                             // Alert the user that we've obtained a copy of the tx
                                             info!(
                                                 "Obtained a copy of a transaction with the hash: {}",
@@ -80,41 +74,14 @@ impl NetworkBehaviourEventProcess<KademliaEvent> for ClientBehavior {
                                         };
                                     let hash = tx.hash;
 
-                                    // Try to get a lock on the runtime so we can put the tx in the database
-                                    if let Ok(mut rt) = self.runtime.write() {
-                                        // If we haven't a single node in the graph, we'll just treat this node as the root
-                                        if rt.ledger.nodes.is_empty() {
-                                            // Just push the root node onto the graph
-                                            rt.ledger.push(tx, None);
-                                        } else {
-                                            // Make a proposal for the transaction, so we can execute it more effectively
-                                            let proposal = Proposal::new(
-                                                "sync_child".to_owned(),
-                                                ProposalData::new(
-                                                    "ledger::transactions".to_owned(),
-                                                    Operation::Append {
-                                                        value_to_append: record.value,
-                                                    },
-                                                ),
-                                            );
-
-                                            // The ID of the proposal. We need to copy this, since we'll move it into the system through registration
-                                            let id = proposal.proposal_id;
-
-                                            // Put the proposal in the system, so we can execute it
-                                            rt.push_proposal(proposal);
-
-                                            // Execute the proposal so it gets added to the dag
-                                            match rt.execute_proposal(id) {
-                                                Ok(_) => {
-                                                    info!("Successfully executed transaction {}", id)
-                                                }
-                                                Err(e) => warn!(
-                                                    "Failed to execute transaction {}: {}",
-                                                    hash, e
-                                                ),
-                                            }
-                                        }
+                                    // Hold onto the transaction, so that its signature can be verified alongside those of
+                                    // the rest of the transactions in this synchronization round
+                                    if !self
+                                        .pending_synced_transactions
+                                        .iter()
+                                        .any(|pending| pending.hash == hash)
+                                    {
+                                        self.pending_synced_transactions.push(tx);
                                     }
 
                                     // Get a quorum to poll at least 50% of the network
@@ -173,3 +140,26 @@ impl NetworkBehaviourEventProcess<KademliaEvent> for ClientBehavior {
         }
     }
 }
+
+impl ClientBehavior {
+    /// Verifies the signatures of each of the transactions downloaded since the last call in a batch, and adds the
+    /// valid transactions to the DAG in the order in which they were downloaded. Returns the number of transactions
+    /// that were processed.
+    pub(crate) fn execute_synced_transactions(&mut self) -> usize {
+        // Take the transactions that we've downloaded so far
+        let transactions: Vec<Transaction> = self.pending_synced_transactions.drain(..).collect();
+
+        if transactions.is_empty() {
+            return 0;
+        }
+
+        let n_transactions = transactions.len();
+
+        // Try to get a lock on the runtime so we can put the txs in the database
+        if let Ok(mut rt) = self.runtime.write() {
+            rt.execute_synced_transactions(transactions);
+        }
+
+        n_transactions
+    }
+}
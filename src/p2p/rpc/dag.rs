@@ -1,8 +1,6 @@
 use jsonrpc_core::{response::Output, Error, ErrorCode, IoHandler, Result};
 use jsonrpc_derive::rpc;
 
-use walkdir::WalkDir;
-
 use serde::Deserialize;
 
 use super::{
@@ -10,26 +8,29 @@ use super::{
         accounts::account::Account,
         common::address::Address,
         core::{
-            sys::{
-                proposal::{Operation, Proposal, ProposalData},
-                system::System,
-            },
+            sys::{cancellation::Cancellation, system::System},
             types::{
-                graph::Node,
+                graph::{LogQueryError, Node},
+                logs::IndexedLog,
+                payload::{ContentType, Payload},
+                receipt::Receipt,
                 signature::Signature,
-                state::Entry,
-                transaction::{self, Transaction},
+                stamp::Stamp,
+                transaction::{self, Transaction, TransactionBuilder},
             },
         },
         crypto::hash::Hash,
+        validator::{rules, ValidationReport},
     },
     error,
 };
 
+use chrono::{Duration, Utc};
 use num::BigUint;
 
 use std::{
     collections::HashMap,
+    str::FromStr,
     sync::{Arc, RwLock, RwLockWriteGuard},
 };
 
@@ -44,7 +45,10 @@ pub trait Dag {
     #[rpc(name = "list_transactions")]
     fn list(&self) -> Result<Vec<Hash>>;
 
-    /// Creates a new transaction with the provided sender, recipient, value, and payload.
+    /// Creates a new transaction with the provided sender, recipient, value, and payload, interpreting the payload
+    /// according to the given content type (i.e. memo, binary, json, contract_call). If no content type is provided,
+    /// the payload's raw bytes are sent as binary data. If the sender has transactions that haven't been accepted
+    /// yet, the new transaction takes the next free nonce, and waits for them.
     #[rpc(name = "create_transaction")]
     fn create_tx(
         &self,
@@ -52,19 +56,40 @@ pub trait Dag {
         recipient: String,
         value: String,
         payload: String,
+        payload_type: Option<String>,
     ) -> Result<Transaction>;
 
     /// Signs the transaction with the provided hash.
     #[rpc(name = "sign_transaction")]
     fn sign_tx(&self, hash: String, data_dir: String) -> Result<Signature>;
 
-    /// Gets a list of transactions contained in the transaction cache.
+    /// Gets a list of the transactions held in the node's mempool. The data directory is no longer consulted, and is
+    /// only accepted for compatibility with older clients.
     #[rpc(name = "get_mem_transactions")]
     fn get_mem_txs(&self, data_dir: String) -> Result<Vec<Hash>>;
 
     /// Signs a transaction with the provided hash in the provided data directory.
     #[rpc(name = "publish_transaction")]
     fn publish_tx(&self, hash: String, data_dir: String) -> Result<()>;
+
+    /// Withdraws the pending transaction with the provided hash, signing a cancellation with the sender's account
+    /// in the provided data directory, and publishes the cancellation.
+    #[rpc(name = "cancel_transaction")]
+    fn cancel_tx(&self, hash: String, data_dir: String) -> Result<()>;
+
+    /// Checks the provided transaction against each of the node's validation rules, considering the node's current
+    /// ledger, without publishing the transaction.
+    #[rpc(name = "validate_transaction")]
+    fn validate_tx(&self, tx: Transaction) -> Result<ValidationReport>;
+
+    /// Gets the receipt of the executed transaction with the provided hash.
+    #[rpc(name = "get_receipt")]
+    fn get_receipt(&self, hash: String) -> Result<Receipt>;
+
+    /// Gets the logs with any of the provided topics emitted between the provided ledger indexes (inclusive). The
+    /// range may span at most `MAX_LOG_RANGE` indexes.
+    #[rpc(name = "get_logs")]
+    fn get_logs(&self, topics: Vec<String>, from: u64, to: u64) -> Result<Vec<IndexedLog>>;
 }
 
 /// An implementation of the DAG API.
@@ -119,20 +144,41 @@ impl Dag for DagImpl {
         }
     }
 
-    /// Creates a new transaction with the provided sender, recipient, value, and payload.
+    /// Creates a new transaction with the provided sender, recipient, value, and payload, interpreting the payload
+    /// according to the given content type (i.e. memo, binary, json, contract_call).
     fn create_tx(
         &self,
         sender: String,
         recipient: String,
         value: String,
         payload: String,
+        payload_type: Option<String>,
     ) -> Result<Transaction> {
         // Convert the provided sender and recipient values to addresses
         let sender_address = Address::from(sender);
         let recipient_address = Address::from(recipient);
 
-        // Get a lock on the client's runtime
-        let runtime = if let Ok(rt) = self.runtime.read() {
+        // Interpret the payload according to its content type. Callers that predate content types send raw bytes.
+        let payload = match payload_type.map_or(Ok(Payload::binary(payload.as_bytes())), |t| {
+            ContentType::from_str(&t).and_then(|content_type| {
+                Payload::from_str_with_content_type(content_type, &payload)
+            })
+        }) {
+            Ok(p) => p,
+            Err(e) => {
+                debug!(
+                    "Unable to interpret the provided transaction payload: {}",
+                    e
+                );
+
+                // Return an invalid payload error
+                return Err(Error::new(ErrorCode::from(error::ERROR_INVALID_PAYLOAD)));
+            }
+        };
+
+        // Get a lock on the client's runtime. The lock must be exclusive, since we'll be reserving a nonce for the
+        // sender.
+        let mut runtime = if let Ok(rt) = self.runtime.write() {
             rt
         } else {
             debug!("Unable to obtain a lock on the client's runtime");
@@ -143,91 +189,14 @@ impl Dag for DagImpl {
             )));
         };
 
-        // Get a head from the DAG. This is necessary, as we need to determine what nonce we can use for the tx.
-        let (head, head_entry): (Node, Entry) =
-            if let Some(mut h) = runtime.ledger.obtain_executed_head() {
-                // Load the entry's state data
-                if let Some(state_entry) = h.state_entry.take() {
-                    (h, state_entry)
-                } else {
-                    debug!("Best graph node doesn't contain a state entry; terminating");
-
-                    // Return a state ref error
-                    return Err(Error::new(ErrorCode::from(
-                        error::ERROR_UNABLE_TO_OBTAIN_STATE_REF,
-                    )));
-                }
-            } else {
-                debug!("Unable to obtain a lock on the client's runtime");
-
-                // Return a state ref error
-                return Err(Error::new(ErrorCode::from(
-                    error::ERROR_UNABLE_TO_OBTAIN_STATE_REF,
-                )));
-            };
-
-        // Get a list of children associated with the last cleared node.
-        let head_children_opt = runtime.ledger.node_children.get(&head.hash);
-
-        // The parents of the transaction we're about to generate
-        let mut parent_hashes: Vec<Hash> = Vec::new();
-
-        // Only collect the head children if they actually exist
-        if let Some(head_children) = head_children_opt {
-            // We're going to try to resolve each of the children associated with the last cleared transaction
-            for child in head_children {
-                // Only use the child as a parent of the new transaction if it unresolved.
-                if runtime.ledger.hash_routes.contains_key(child)
-                    && runtime.ledger.nodes[*runtime.ledger.hash_routes.get(child).unwrap()]
-                        .state_entry
-                        .is_none()
-                {
-                    // Add the child as a parent of the new transaction
-                    parent_hashes.push(*child);
-                }
-            }
-        }
-
-        // The index of the transaction in the set of user transactions
-        let mut nonce = 0;
-
-        if let Some(last_nonce) = head_entry.data.nonces.get(&sender_address.to_str()) {
-            nonce = last_nonce + 1;
-        }
-
-        // Create a new transaction using the last defined nonce in the global state
-        let mut transaction = Transaction::new(
-            nonce,
+        // Build on the ledger's head, using the next nonce available to the sender
+        build_transaction(
+            &mut runtime,
             sender_address,
             recipient_address,
             BigUint::from_bytes_be(&value.into_bytes()),
-            payload.as_bytes(),
-            parent_hashes,
-        );
-
-        // Calculate a merged state entry for each of the parents of the transaction. We can use this to provide a proof of correctness for this tx.
-        let (merged_state_entry, parent_entries) = if let Ok(res) = runtime
-            .ledger
-            .resolve_parent_nodes(transaction.transaction_data.parents.clone())
-        {
-            res
-        } else {
-            debug!(
-                "Failed to merge the parent entries required to produce transaction {}",
-                transaction.hash
-            );
-
-            // Return a state error
-            return Err(Error::new(ErrorCode::from(
-                error::ERROR_UNABLE_TO_OBTAIN_STATE_REF,
-            )));
-        };
-
-        // Register the parent states
-        transaction.register_parental_state(merged_state_entry, parent_entries);
-
-        // Return the transaction
-        Ok(transaction)
+            payload,
+        )
     }
 
     /// Signs the transaction with the provided hash.
@@ -265,8 +234,20 @@ impl Dag for DagImpl {
             )));
         };
 
+        // Get the identifier of the chain on which the transaction will be published
+        let network = if let Ok(rt) = self.runtime.read() {
+            rt.config.chain_id().to_owned()
+        } else {
+            debug!("Unable to obtain a lock on the client's runtime");
+
+            // Return an error
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )));
+        };
+
         // Sign the transaction, and return it
-        match transaction::sign_transaction(keypair, &mut tx) {
+        match transaction::sign_transaction(keypair, &mut tx, &network) {
             Ok(_) => Ok(if let Some(sig) = tx.signature.clone() {
                 // Persist the tx to the disk, now that it's been signed
                 match tx.to_disk_at_data_directory(&data_dir) {
@@ -289,44 +270,22 @@ impl Dag for DagImpl {
         }
     }
 
-    /// Gets a list of transactions contained in the transaction cache.
-    fn get_mem_txs(&self, data_dir: String) -> Result<Vec<Hash>> {
-        // Make an instance of a directory walker so that we can collect a list of memory-bound transactions
-        let wk = WalkDir::new(format!("{}/mem", data_dir));
-
-        // Make an empty list that we can store each of the hashes
-        let mut transactions: Vec<Hash> = Vec::new();
-
-        // Go through each of the transaction files
-        for file in wk.into_iter().filter_map(|e| e.ok()) {
-            // Get the name of the file so that we can determine its hash
-            if let Ok(meta) = file.metadata() {
-                // Only use the file's info if it isn't a directory
-                if !meta.is_file() {
-                } else {
-                    continue;
-                }
-            } else {
-                continue;
-            }
-
-            // Try to derive a hash from the file's name
-            if let Some(f_name) = file.path().to_str() {
-                if let Some(tx_hash) = f_name.split(".json").collect::<Vec<&str>>().get(0) {
-                    // Add the transaction hash to the list of hashes
-                    transactions.push(Hash::from(*tx_hash));
-                }
-            }
+    /// Gets a list of the transactions held in the node's mempool.
+    fn get_mem_txs(&self, _data_dir: String) -> Result<Vec<Hash>> {
+        if let Ok(rt) = self.runtime.read() {
+            // Collect the hashes of the transactions in the mempool, grouped by sender and ordered by nonce
+            Ok(rt.mempool.hashes())
+        } else {
+            Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )))
         }
-
-        // Return the list of tx hashes
-        Ok(transactions)
     }
 
     /// Signs the transaction with the provided hash in the given data directory.
     fn publish_tx(&self, hash: String, data_dir: String) -> Result<()> {
         // Open the transaction so that we can use it to publish a proposal derived from it on the network
-        let tx: Transaction =
+        let mut tx: Transaction =
             if let Ok(t) = Transaction::from_disk_at_data_directory(&data_dir, Hash::from(hash)) {
                 t
             } else {
@@ -336,16 +295,19 @@ impl Dag for DagImpl {
                 )));
             };
 
-        // Make a proposal to submit the provided transaction
-        let proposal_data = ProposalData::new(
-            "ledger::transactions".to_owned(),
-            Operation::Append {
-                value_to_append: tx.to_bytes(),
-            },
-        );
+        // Find out how much work the network demands, without holding onto the runtime while the work is done
+        let difficulty = if let Ok(rt) = self.runtime.read() {
+            rt.config.stamp_difficulty
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )));
+        };
 
-        // Make a proposal for the transaction
-        let proposal = Proposal::new(format!("new_tx({})", tx.hash.to_str()), proposal_data);
+        // Stamp the transaction if it doesn't already prove the amount of work demanded by the network
+        if tx.work() < u32::from(difficulty) {
+            tx.stamp = Some(Stamp::mint(tx.hash, difficulty));
+        }
 
         // Try to get a lock on the server's runtime
         let mut rt: RwLockWriteGuard<System> = if let Ok(rt) = self.runtime.write() {
@@ -356,11 +318,258 @@ impl Dag for DagImpl {
             )));
         };
 
-        // Register the proposal
-        rt.register_proposal(proposal);
+        // Propose the transaction, or hold it until it can be executed
+        rt.propose_transaction(tx).map_err(|e| {
+            debug!("Unable to add the transaction to the mempool: {}", e);
 
-        Ok(())
+            Error::new(ErrorCode::from(error::ERROR_UNABLE_TO_ADMIT_TRANSACTION))
+        })
     }
+
+    /// Withdraws the pending transaction with the provided hash, signing a cancellation with the sender's account
+    /// in the provided data directory, and publishes the cancellation.
+    fn cancel_tx(&self, hash: String, data_dir: String) -> Result<()> {
+        let tx_hash = Hash::from(hash);
+
+        // Try to get a lock on the server's runtime
+        let mut rt: RwLockWriteGuard<System> = if let Ok(rt) = self.runtime.write() {
+            rt
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )));
+        };
+
+        // Find the sender of the transaction, which must still be pending
+        let sender = if let Some(tx) = rt.mempool.get(&tx_hash) {
+            tx.transaction_data.sender
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_TRANSACTION_NOT_PENDING,
+            )));
+        };
+
+        // Read the sender's account from the disk
+        let acc = if let Ok(a) = Account::read_from_disk_at_data_directory(sender, &data_dir) {
+            a
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OPEN_ACCOUNT,
+            )));
+        };
+
+        // Try to get a keypair for the account, so that we can sign the cancellation
+        let keypair = if let Ok(k) = acc.keypair() {
+            k
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_SIGNATURE_UNDEFINED,
+            )));
+        };
+
+        // Withdraw the transaction, and publish the cancellation
+        let cancellation = Cancellation::new(tx_hash, keypair, rt.config.chain_id());
+
+        rt.register_cancellation(cancellation)
+            .map(|_| ())
+            .map_err(|e| {
+                debug!("Unable to cancel the transaction: {}", e);
+
+                Error::new(ErrorCode::from(error::ERROR_TRANSACTION_NOT_PENDING))
+            })
+    }
+
+    /// Checks the provided transaction against each of the node's validation rules, considering the node's current
+    /// ledger, without publishing the transaction.
+    fn validate_tx(&self, tx: Transaction) -> Result<ValidationReport> {
+        if let Ok(rt) = self.runtime.read() {
+            // Validate the transaction just as we would before voting on it
+            let validator = rt.validator();
+
+            Ok(validator.validation_report(&tx))
+        } else {
+            Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )))
+        }
+    }
+
+    /// Gets the receipt of the executed transaction with the provided hash.
+    fn get_receipt(&self, hash: String) -> Result<Receipt> {
+        if let Ok(rt) = self.runtime.read() {
+            // Look for a receipt stored for the transaction
+            match rt.ledger.get_receipt(&Hash::from(hash)) {
+                Ok(Some(receipt)) => Ok(receipt),
+                Ok(None) => Err(Error::new(ErrorCode::from(error::ERROR_NO_SUCH_RECEIPT))),
+                Err(_) => Err(Error::new(ErrorCode::from(error::ERROR_UNABLE_TO_READ_DAG))),
+            }
+        } else {
+            Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )))
+        }
+    }
+
+    /// Gets the logs with any of the provided topics emitted between the provided ledger indexes (inclusive).
+    fn get_logs(&self, topics: Vec<String>, from: u64, to: u64) -> Result<Vec<IndexedLog>> {
+        if let Ok(rt) = self.runtime.read() {
+            // Look up each of the matching logs in the ledger's log index
+            rt.ledger.get_logs(&topics, from, to).map_err(|e| match e {
+                LogQueryError::RangeTooLarge { .. } => {
+                    Error::new(ErrorCode::from(error::ERROR_LOG_RANGE_TOO_LARGE))
+                }
+                LogQueryError::StorageFailure { .. } => {
+                    Error::new(ErrorCode::from(error::ERROR_UNABLE_TO_READ_DAG))
+                }
+            })
+        } else {
+            Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )))
+        }
+    }
+}
+
+/// Builds an unsigned transaction that builds on the unresolved children of the ledger's executed head, using the next
+/// nonce available to the sender, and reserves that nonce so that concurrent callers don't get the same one.
+///
+/// # Arguments
+///
+/// * `runtime` - The runtime whose ledger the transaction should build on
+/// * `sender` - The sender of the transaction
+/// * `recipient` - The recipient of the transaction
+/// * `value` - The number of finks sent through the transaction
+/// * `payload` - The payload of the transaction
+pub(crate) fn build_transaction(
+    runtime: &mut System,
+    sender: Address,
+    recipient: Address,
+    value: BigUint,
+    payload: Payload,
+) -> Result<Transaction> {
+    // Make sure that the network would actually accept the payload
+    if payload.len() > runtime.config.max_payload_size {
+        debug!(
+            "Transaction payload exceeds the network's maximum payload size ({} > {} bytes)",
+            payload.len(),
+            runtime.config.max_payload_size
+        );
+
+        // Return an invalid payload error
+        return Err(Error::new(ErrorCode::from(error::ERROR_INVALID_PAYLOAD)));
+    }
+
+    // Get a head from the DAG. This is necessary, as the tx must build on the head's unresolved children.
+    let head: Node = if let Some(h) = runtime.ledger.obtain_executed_head() {
+        // The head should have been executed
+        if h.state_entry.is_some() {
+            h
+        } else {
+            debug!("Best graph node doesn't contain a state entry; terminating");
+
+            // Return a state ref error
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_STATE_REF,
+            )));
+        }
+    } else {
+        debug!("Unable to obtain a lock on the client's runtime");
+
+        // Return a state ref error
+        return Err(Error::new(ErrorCode::from(
+            error::ERROR_UNABLE_TO_OBTAIN_STATE_REF,
+        )));
+    };
+
+    // Get a list of children associated with the last cleared node.
+    let head_children_opt = runtime.ledger.node_children.get(&head.hash);
+
+    // The parents of the transaction we're about to generate
+    let mut parent_hashes: Vec<Hash> = Vec::new();
+
+    // Only collect the head children if they actually exist
+    if let Some(head_children) = head_children_opt {
+        // We're going to try to resolve each of the children associated with the last cleared transaction
+        for child in head_children {
+            // Only use the child as a parent of the new transaction if it unresolved.
+            if runtime.ledger.hash_routes.contains_key(child)
+                && runtime.ledger.nodes[*runtime.ledger.hash_routes.get(child).unwrap()]
+                    .state_entry
+                    .is_none()
+            {
+                // Add the child as a parent of the new transaction
+                parent_hashes.push(*child);
+            }
+        }
+    }
+
+    // Calculate a merged state entry for each of the parents of the transaction. We can use this to provide a proof of correctness for this tx.
+    let (merged_state_entry, parent_entries) =
+        if let Ok(res) = runtime.ledger.resolve_parent_nodes(parent_hashes.clone()) {
+            res
+        } else {
+            debug!(
+                "Failed to merge the {} parent entries required to produce a transaction",
+                parent_hashes.len()
+            );
+
+            // Return a state error
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_STATE_REF,
+            )));
+        };
+
+    // The index of the transaction in the set of user transactions, following the last nonce recorded in the
+    // state that the transaction builds on, or the sender's latest pending transaction, which the transaction
+    // will then have to wait for
+    let (nonce, predecessor) = runtime.next_nonce(
+        &sender,
+        merged_state_entry
+            .data
+            .nonces
+            .get(&sender.to_str())
+            .copied(),
+    );
+
+    // Transactions can't be dated too long after their parents, so the time of the ledger only advances so far with
+    // each transaction
+    let max_drift = Duration::seconds(rules::MAX_TIMESTAMP_DRIFT_SECS);
+    let timestamp = runtime
+        .ledger
+        .latest_timestamp(&parent_hashes)
+        .map_or_else(Utc::now, |parent_time| {
+            Utc::now().min(parent_time + max_drift)
+        });
+
+    // Create a new transaction using the reserved nonce, and the resolved parent states
+    let transaction = match TransactionBuilder::new()
+        .nonce(nonce)
+        .sender(sender)
+        .recipient(recipient)
+        .value(value)
+        .payload(payload)
+        .parents(parent_hashes)
+        .parental_state(merged_state_entry, parent_entries)
+        .time_lock(None, predecessor)
+        .timestamp(timestamp)
+        .build()
+    {
+        Ok(tx) => tx,
+        Err(e) => {
+            debug!("Failed to build the transaction: {}", e);
+
+            // Return a build error
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_BUILD_TRANSACTION,
+            )));
+        }
+    };
+
+    // Make sure that concurrent callers don't get the same nonce
+    runtime.reserve_nonce(&transaction);
+
+    // Return the transaction
+    Ok(transaction)
 }
 
 impl DagImpl {
@@ -444,15 +653,17 @@ impl Client {
         recipient: String,
         amount: u128,
         payload: String,
+        payload_type: Option<String>,
     ) -> std::result::Result<Transaction, failure::Error> {
         self.do_request::<Transaction>(
             "create_transaction",
             &format!(
-                r#"[{}, {}, "{}", {}]"#,
+                r#"[{}, {}, "{}", {}, {}]"#,
                 serde_json::to_string(&sender)?,
                 serde_json::to_string(&recipient)?,
                 serde_json::to_string(&amount)?,
-                serde_json::to_string(&payload)?
+                serde_json::to_string(&payload)?,
+                serde_json::to_string(&payload_type)?
             ),
         )
         .await
@@ -475,7 +686,7 @@ impl Client {
         .await
     }
 
-    /// Gets a list of pending transactions stored on the disk.
+    /// Gets a list of the transactions held in the node's mempool.
     pub async fn get_mem_txs(
         &self,
         data_dir: String,
@@ -503,4 +714,56 @@ impl Client {
         )
         .await
     }
+
+    /// Withdraws the pending transaction with the provided hash, signing the cancellation with the sender's account.
+    pub async fn cancel_tx(
+        &self,
+        hash: String,
+        data_dir: String,
+    ) -> std::result::Result<(), failure::Error> {
+        self.do_request::<()>(
+            "cancel_transaction",
+            &format!(
+                "[{}, {}]",
+                &serde_json::to_string(&hash)?,
+                serde_json::to_string(&data_dir)?
+            ),
+        )
+        .await
+    }
+
+    /// Checks the provided transaction against each of the node's validation rules without publishing it.
+    pub async fn validate_tx(
+        &self,
+        tx: &Transaction,
+    ) -> std::result::Result<ValidationReport, failure::Error> {
+        self.do_request::<ValidationReport>(
+            "validate_transaction",
+            &format!("[{}]", &serde_json::to_string(tx)?),
+        )
+        .await
+    }
+
+    /// Gets the receipt of the executed transaction with the provided hash.
+    pub async fn get_receipt(&self, hash: String) -> std::result::Result<Receipt, failure::Error> {
+        self.do_request::<Receipt>(
+            "get_receipt",
+            &format!("[{}]", &serde_json::to_string(&hash)?),
+        )
+        .await
+    }
+
+    /// Gets the logs with any of the provided topics emitted between the provided ledger indexes (inclusive).
+    pub async fn get_logs(
+        &self,
+        topics: Vec<String>,
+        from: u64,
+        to: u64,
+    ) -> std::result::Result<Vec<IndexedLog>, failure::Error> {
+        self.do_request::<Vec<IndexedLog>>(
+            "get_logs",
+            &format!("[{}, {}, {}]", &serde_json::to_string(&topics)?, from, to),
+        )
+        .await
+    }
 }
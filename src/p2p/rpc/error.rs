@@ -49,3 +49,33 @@ pub const ERROR_UNABLE_TO_CREATE_PROPOSAL: i64 = 15;
 
 /// An error code representing the inability of the executor to read the DAG from the disk.
 pub const ERROR_UNABLE_TO_READ_DAG: i64 = 16;
+
+/// An error code representing the absence of a receipt for the given transaction (i.e. it hasn't been executed yet).
+pub const ERROR_NO_SUCH_RECEIPT: i64 = 17;
+
+/// An error code representing a transaction payload that doesn't match its content type, or that is too large.
+pub const ERROR_INVALID_PAYLOAD: i64 = 18;
+
+/// An error code representing the inability of the executor to assemble a transaction from the given parameters.
+pub const ERROR_UNABLE_TO_BUILD_TRANSACTION: i64 = 19;
+
+/// An error code representing a transaction that the node's mempool refused to accept (e.g. because it is full).
+pub const ERROR_UNABLE_TO_ADMIT_TRANSACTION: i64 = 20;
+
+/// An error code representing a transaction that can't be withdrawn, since it isn't pending (e.g. it was accepted).
+pub const ERROR_TRANSACTION_NOT_PENDING: i64 = 21;
+
+/// An error code representing a delegation of votes that the node refused to record (e.g. to an unknown parameter).
+pub const ERROR_INVALID_DELEGATION: i64 = 22;
+
+/// An error code representing the inability of the executor to read the governance history from the disk.
+pub const ERROR_UNABLE_TO_READ_GOVERNANCE_HISTORY: i64 = 23;
+
+/// An error code representing a proposal that the node doesn't know of (e.g. because it has already been decided).
+pub const ERROR_NO_SUCH_PROPOSAL: i64 = 24;
+
+/// An error code representing a vote that the node refused to record (e.g. on a proposal that doesn't exist).
+pub const ERROR_UNABLE_TO_REGISTER_VOTE: i64 = 25;
+
+/// An error code representing a query for logs over a range of ledger indexes that is too large to be served.
+pub const ERROR_LOG_RANGE_TOO_LARGE: i64 = 26;
@@ -3,13 +3,34 @@ use jsonrpc_derive::rpc;
 
 use serde::Deserialize;
 
+use num::{BigUint, Zero};
+
+use ed25519_dalek::Keypair;
+
 use super::{
-    super::super::core::sys::{proposal::Proposal, system::System},
-    error,
+    super::super::{
+        accounts::account::Account,
+        common::address::Address,
+        core::{
+            sys::{
+                config::ActivationPoint,
+                delegation::Delegation,
+                governance::{GovernanceDecision, ProposalTally},
+                parameter::{OperationKind, ParameterDescriptor},
+                proposal::{Proposal, ProposalData},
+                system::System,
+                vote::Vote,
+            },
+            types::{encoding::Canonical, payload::Payload, stamp::Stamp, transaction},
+        },
+        crypto::hash::Hash,
+    },
+    dag, error,
 };
 
 use std::{
     collections::HashMap,
+    str::FromStr,
     sync::{Arc, RwLock},
 };
 
@@ -18,6 +39,58 @@ pub trait Runtime {
     /// Gets a list of proposals contained in the runtime
     #[rpc(name = "list_pending_proposals")]
     fn list_pending_proposals(&self) -> Result<Vec<Proposal>>;
+
+    /// Gets a list of the parameters that may be modified by proposals
+    #[rpc(name = "list_parameters")]
+    fn list_parameters(&self) -> Result<Vec<ParameterDescriptor>>;
+
+    /// Gets a list of the decisions made on proposals to change the network's parameters, oldest first
+    #[rpc(name = "list_governance_history")]
+    fn list_governance_history(&self) -> Result<Vec<GovernanceDecision>>;
+
+    /// Delegates the votes of an account to another account, or revokes the account's delegation
+    #[rpc(name = "delegate_votes")]
+    fn delegate_votes(
+        &self,
+        delegator: String,
+        delegate: Option<String>,
+        param_name: Option<String>,
+        data_dir: String,
+    ) -> Result<()>;
+
+    /// Proposes a change to one of the network's parameters on behalf of an account, optionally taking effect from a
+    /// position in the ledger, returning the proposal's ID
+    #[rpc(name = "propose")]
+    #[allow(clippy::too_many_arguments)]
+    fn propose(
+        &self,
+        proposer: String,
+        proposal_name: String,
+        param_name: String,
+        operation: String,
+        value: Option<String>,
+        deposit: Option<String>,
+        activate_at: Option<String>,
+        data_dir: String,
+    ) -> Result<String>;
+
+    /// Casts a vote on a pending proposal on behalf of an account
+    #[rpc(name = "vote")]
+    fn vote(
+        &self,
+        voter: String,
+        proposal_id: String,
+        in_favor: bool,
+        data_dir: String,
+    ) -> Result<()>;
+
+    /// Gets the live tally of the votes on a proposal, along with the weight that it must reach to be decided
+    #[rpc(name = "get_tally")]
+    fn get_tally(&self, proposal_id: String) -> Result<ProposalTally>;
+
+    /// Gets a list of the votes cast by the accounts held by the node
+    #[rpc(name = "list_own_votes")]
+    fn list_own_votes(&self) -> Result<Vec<Vote>>;
 }
 
 /// An implementation of the runtime API.
@@ -37,6 +110,346 @@ impl Runtime for RuntimeImpl {
             )))
         }
     }
+
+    /// Gets a list of the parameters that may be modified by proposals
+    fn list_parameters(&self) -> Result<Vec<ParameterDescriptor>> {
+        // Describe each of the parameters registered in the runtime
+        if let Ok(rt) = self.runtime.read() {
+            Ok(rt.parameters.descriptors(&rt.config))
+        } else {
+            Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )))
+        }
+    }
+
+    /// Gets a list of the decisions made on proposals to change the network's parameters, oldest first
+    fn list_governance_history(&self) -> Result<Vec<GovernanceDecision>> {
+        // Read the governance history kept by the runtime
+        if let Ok(rt) = self.runtime.read() {
+            rt.governance_history().map_err(|e| {
+                debug!("Unable to read the governance history: {}", e);
+
+                Error::new(ErrorCode::from(
+                    error::ERROR_UNABLE_TO_READ_GOVERNANCE_HISTORY,
+                ))
+            })
+        } else {
+            Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )))
+        }
+    }
+
+    /// Delegates the votes of an account to another account, or revokes the account's delegation, signing the
+    /// delegation with the delegator's account in the provided data directory, and publishes a transaction from the
+    /// delegator carrying the delegation. The delegation takes effect once the transaction is appended to the ledger.
+    fn delegate_votes(
+        &self,
+        delegator: String,
+        delegate: Option<String>,
+        param_name: Option<String>,
+        data_dir: String,
+    ) -> Result<()> {
+        let delegator = Address::from(delegator);
+        let delegate = delegate.map(Address::from);
+
+        // Accounts can't delegate their votes to themselves
+        if delegate == Some(delegator) {
+            return Err(Error::new(ErrorCode::from(error::ERROR_INVALID_DELEGATION)));
+        }
+
+        // Read the delegator's account from the disk
+        let acc = if let Ok(a) = Account::read_from_disk_at_data_directory(delegator, &data_dir) {
+            a
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OPEN_ACCOUNT,
+            )));
+        };
+
+        // Try to get a keypair for the account, so that we can sign the delegation, and the transaction carrying it
+        let (delegation_keypair, tx_keypair) =
+            if let (Ok(k), Ok(tx_k)) = (acc.keypair(), acc.keypair()) {
+                (k, tx_k)
+            } else {
+                return Err(Error::new(ErrorCode::from(
+                    error::ERROR_SIGNATURE_UNDEFINED,
+                )));
+            };
+
+        let (mut tx, difficulty) = {
+            // Try to get a lock on the server's runtime. The lock must be exclusive, since we'll be reserving a nonce
+            // for the delegator.
+            let mut rt = if let Ok(rt) = self.runtime.write() {
+                rt
+            } else {
+                return Err(Error::new(ErrorCode::from(
+                    error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+                )));
+            };
+
+            // Delegations can only apply to known parameters
+            if let Some(param_name) = &param_name {
+                if rt.parameters.get(param_name).is_none() {
+                    return Err(Error::new(ErrorCode::from(error::ERROR_INVALID_DELEGATION)));
+                }
+            }
+
+            let network = rt.config.chain_id().to_owned();
+            let delegation = Delegation::new(delegate, param_name, delegation_keypair, &network);
+
+            // Send nothing to the delegate (or to nobody, for a revocation), other than the delegation itself
+            let mut tx = dag::build_transaction(
+                &mut rt,
+                delegator,
+                delegate.unwrap_or_else(Address::default),
+                BigUint::zero(),
+                Payload::delegation(&delegation.encode()),
+            )?;
+
+            if transaction::sign_transaction(tx_keypair, &mut tx, &network).is_err() {
+                return Err(Error::new(ErrorCode::from(
+                    error::ERROR_SIGNATURE_UNDEFINED,
+                )));
+            }
+
+            (tx, rt.config.stamp_difficulty)
+        };
+
+        // Stamp the transaction without holding onto the runtime while the work is done
+        if tx.work() < u32::from(difficulty) {
+            tx.stamp = Some(Stamp::mint(tx.hash, difficulty));
+        }
+
+        // Try to get a lock on the server's runtime
+        let mut rt = if let Ok(rt) = self.runtime.write() {
+            rt
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )));
+        };
+
+        // Propose the transaction, or hold it until it can be executed
+        rt.propose_transaction(tx).map_err(|e| {
+            debug!(
+                "Unable to add the delegation's transaction to the mempool: {}",
+                e
+            );
+
+            Error::new(ErrorCode::from(error::ERROR_UNABLE_TO_ADMIT_TRANSACTION))
+        })
+    }
+
+    /// Proposes a change to one of the network's parameters, signing the proposal with the proposer's account in the
+    /// provided data directory and locking the given deposit from its balance, and publishes the proposal. Proposals
+    /// given an activation point (either a node count or a transaction hash) take effect once the ledger reaches it.
+    fn propose(
+        &self,
+        proposer: String,
+        proposal_name: String,
+        param_name: String,
+        operation: String,
+        value: Option<String>,
+        deposit: Option<String>,
+        activate_at: Option<String>,
+        data_dir: String,
+    ) -> Result<String> {
+        // Read the proposer's account from the disk
+        let acc = if let Ok(a) =
+            Account::read_from_disk_at_data_directory(Address::from(proposer), &data_dir)
+        {
+            a
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OPEN_ACCOUNT,
+            )));
+        };
+
+        // Try to get a keypair for the account, so that we can sign the proposal
+        let keypair = if let Ok(k) = acc.keypair() {
+            k
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_SIGNATURE_UNDEFINED,
+            )));
+        };
+
+        // Parse the deposit that the proposer wishes to lock, if any
+        let deposit = match deposit.map(|d| BigUint::from_str(&d)) {
+            Some(Ok(d)) => d,
+            Some(Err(_)) => {
+                return Err(Error::new(ErrorCode::from(
+                    error::ERROR_UNABLE_TO_CREATE_PROPOSAL,
+                )))
+            }
+            None => BigUint::zero(),
+        };
+
+        // Parse the point from which the change should take effect, if any
+        let activation = match activate_at.map(|a| ActivationPoint::from_str(&a)) {
+            Some(Ok(a)) => Some(a),
+            Some(Err(e)) => {
+                debug!("Unable to parse the proposal's activation point: {}", e);
+
+                return Err(Error::new(ErrorCode::from(
+                    error::ERROR_UNABLE_TO_CREATE_PROPOSAL,
+                )));
+            }
+            None => None,
+        };
+
+        // Try to get a lock on the server's runtime
+        let mut rt = if let Ok(rt) = self.runtime.write() {
+            rt
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )));
+        };
+
+        // Build the operation that the proposal should apply to the parameter
+        let op = OperationKind::from_str(&operation).and_then(|kind| {
+            rt.parameters
+                .get(&param_name)
+                .ok_or_else(|| format!("no such parameter '{}'", param_name))
+                .and_then(|param| param.operation(kind, value.as_deref()))
+        });
+        let op = match op {
+            Ok(op) => op,
+            Err(e) => {
+                debug!("Unable to build the proposed operation: {}", e);
+
+                return Err(Error::new(ErrorCode::from(
+                    error::ERROR_UNABLE_TO_CREATE_PROPOSAL,
+                )));
+            }
+        };
+
+        let mut proposal =
+            Proposal::new(proposal_name, ProposalData::new(param_name, op)).with_deposit(deposit);
+        if let Some(activation) = activation {
+            proposal = proposal.with_activation(activation);
+        }
+
+        // Changes to the rules for executing transactions must say where they take effect, and can't take effect at
+        // a point that the ledger has already reached
+        if let Err(e) = rt.check_schedule(&proposal) {
+            debug!("Unable to schedule the proposal: {}", e);
+
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_CREATE_PROPOSAL,
+            )));
+        }
+
+        // Fix the proposal's snapshot before signing it, since the snapshot is covered by its signature
+        let proposal = rt
+            .snapshot_proposal(proposal)
+            .sign(&keypair, rt.config.chain_id());
+
+        // Make sure that the proposer can afford the deposit before publishing the proposal
+        if let Err(e) = rt.check_deposit(&proposal) {
+            debug!("Unable to lock the proposal's deposit: {}", e);
+
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_CREATE_PROPOSAL,
+            )));
+        }
+
+        let id = proposal.proposal_id.to_str();
+        rt.register_proposal(proposal);
+
+        Ok(id)
+    }
+
+    /// Casts a vote on a pending proposal, signing the vote with the voter's account in the provided data directory,
+    /// and publishes the vote. Votes on changes to the network's parameters are published in a transaction from the
+    /// voter, and only count once that transaction is appended to the ledger.
+    fn vote(
+        &self,
+        voter: String,
+        proposal_id: String,
+        in_favor: bool,
+        data_dir: String,
+    ) -> Result<()> {
+        // Read the voter's account from the disk
+        let acc = if let Ok(a) =
+            Account::read_from_disk_at_data_directory(Address::from(voter), &data_dir)
+        {
+            a
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OPEN_ACCOUNT,
+            )));
+        };
+
+        // Try to get a keypair for the account, so that we can sign the vote, and any transaction carrying it
+        let (keypair, tx_keypair) = if let (Ok(k), Ok(tx_k)) = (acc.keypair(), acc.keypair()) {
+            (k, tx_k)
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_SIGNATURE_UNDEFINED,
+            )));
+        };
+
+        // Try to get a lock on the server's runtime
+        let mut rt = if let Ok(rt) = self.runtime.write() {
+            rt
+        } else {
+            return Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )));
+        };
+
+        // Record the vote, and publish it
+        let vote = Vote::new(
+            Hash::from(proposal_id),
+            in_favor,
+            keypair,
+            rt.config.chain_id(),
+        );
+
+        // Votes that only count once they're on the ledger are published in a transaction
+        if rt
+            .pending_proposals
+            .get(&vote.target_proposal)
+            .map_or(false, Proposal::is_voted_on_ledger)
+        {
+            return propose_vote_transaction(&mut rt, &vote, tx_keypair);
+        }
+
+        rt.cast_vote(vote).map_err(|e| {
+            debug!("Unable to cast the vote: {}", e);
+
+            Error::new(ErrorCode::from(error::ERROR_UNABLE_TO_REGISTER_VOTE))
+        })
+    }
+
+    /// Gets the live tally of the votes on a proposal, along with the weight that it must reach to be decided
+    fn get_tally(&self, proposal_id: String) -> Result<ProposalTally> {
+        // Tally the votes recorded by the runtime
+        if let Ok(rt) = self.runtime.read() {
+            rt.tally(&Hash::from(proposal_id))
+                .ok_or_else(|| Error::new(ErrorCode::from(error::ERROR_NO_SUCH_PROPOSAL)))
+        } else {
+            Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )))
+        }
+    }
+
+    /// Gets a list of the votes cast by the accounts held by the node
+    fn list_own_votes(&self) -> Result<Vec<Vote>> {
+        // Get the votes that the runtime has recorded as its own
+        if let Ok(rt) = self.runtime.read() {
+            Ok(rt.own_votes())
+        } else {
+            Err(Error::new(ErrorCode::from(
+                error::ERROR_UNABLE_TO_OBTAIN_LOCK,
+            )))
+        }
+    }
 }
 
 impl RuntimeImpl {
@@ -47,6 +460,59 @@ impl RuntimeImpl {
     }
 }
 
+/// Publishes a transaction from the voter carrying the given vote, and remembers the vote as one cast through this node.
+/// The vote is only counted once the transaction is appended to the ledger.
+///
+/// # Arguments
+///
+/// * `runtime` - The runtime that the transaction should be proposed to
+/// * `vote` - The signed vote
+/// * `keypair` - The voter's keypair, used to sign the transaction
+pub(crate) fn propose_vote_transaction(
+    runtime: &mut System,
+    vote: &Vote,
+    keypair: Keypair,
+) -> Result<()> {
+    let voter = if let Some(voter) = vote.voter_address() {
+        voter
+    } else {
+        return Err(Error::new(ErrorCode::from(
+            error::ERROR_SIGNATURE_UNDEFINED,
+        )));
+    };
+
+    // Send nothing to nobody, other than the vote itself
+    let mut tx = dag::build_transaction(
+        runtime,
+        voter,
+        Address::default(),
+        BigUint::zero(),
+        Payload::vote(&vote.encode()),
+    )?;
+
+    let network = runtime.config.chain_id().to_owned();
+    if transaction::sign_transaction(keypair, &mut tx, &network).is_err() {
+        return Err(Error::new(ErrorCode::from(
+            error::ERROR_SIGNATURE_UNDEFINED,
+        )));
+    }
+
+    let difficulty = runtime.config.stamp_difficulty;
+    if tx.work() < u32::from(difficulty) {
+        tx.stamp = Some(Stamp::mint(tx.hash, difficulty));
+    }
+
+    // Propose the transaction, or hold it until it can be executed
+    runtime.propose_transaction(tx).map_err(|e| {
+        debug!("Unable to add the vote's transaction to the mempool: {}", e);
+
+        Error::new(ErrorCode::from(error::ERROR_UNABLE_TO_ADMIT_TRANSACTION))
+    })?;
+    runtime.record_own_vote(vote);
+
+    Ok(())
+}
+
 /// A client for the runtime API.
 pub struct Client {
     /// The address for the server hosting the API
@@ -107,4 +573,111 @@ impl Client {
         self.do_request::<Vec<Proposal>>("list_pending_proposals", "[]")
             .await
     }
+
+    /// Gets a list of the parameters that may be modified by proposals
+    pub async fn list_parameters(
+        &self,
+    ) -> std::result::Result<Vec<ParameterDescriptor>, failure::Error> {
+        self.do_request::<Vec<ParameterDescriptor>>("list_parameters", "[]")
+            .await
+    }
+
+    /// Gets a list of the decisions made on proposals to change the network's parameters, oldest first
+    pub async fn list_governance_history(
+        &self,
+    ) -> std::result::Result<Vec<GovernanceDecision>, failure::Error> {
+        self.do_request::<Vec<GovernanceDecision>>("list_governance_history", "[]")
+            .await
+    }
+
+    /// Delegates the votes of the given account to another account, or revokes the account's delegation if no
+    /// delegate is given.
+    pub async fn delegate_votes(
+        &self,
+        delegator: String,
+        delegate: Option<String>,
+        param_name: Option<String>,
+        data_dir: String,
+    ) -> std::result::Result<(), failure::Error> {
+        self.do_request::<()>(
+            "delegate_votes",
+            &format!(
+                "[{}, {}, {}, {}]",
+                &serde_json::to_string(&delegator)?,
+                serde_json::to_string(&delegate)?,
+                serde_json::to_string(&param_name)?,
+                serde_json::to_string(&data_dir)?
+            ),
+        )
+        .await
+    }
+
+    /// Proposes a change to one of the network's parameters on behalf of the given account, and returns the ID of
+    /// the proposal.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn propose(
+        &self,
+        proposer: String,
+        proposal_name: String,
+        param_name: String,
+        operation: String,
+        value: Option<String>,
+        deposit: Option<String>,
+        activate_at: Option<String>,
+        data_dir: String,
+    ) -> std::result::Result<String, failure::Error> {
+        self.do_request::<String>(
+            "propose",
+            &format!(
+                "[{}, {}, {}, {}, {}, {}, {}, {}]",
+                serde_json::to_string(&proposer)?,
+                serde_json::to_string(&proposal_name)?,
+                serde_json::to_string(&param_name)?,
+                serde_json::to_string(&operation)?,
+                serde_json::to_string(&value)?,
+                serde_json::to_string(&deposit)?,
+                serde_json::to_string(&activate_at)?,
+                serde_json::to_string(&data_dir)?
+            ),
+        )
+        .await
+    }
+
+    /// Casts a vote on the given proposal on behalf of the given account.
+    pub async fn vote(
+        &self,
+        voter: String,
+        proposal_id: String,
+        in_favor: bool,
+        data_dir: String,
+    ) -> std::result::Result<(), failure::Error> {
+        self.do_request::<()>(
+            "vote",
+            &format!(
+                "[{}, {}, {}, {}]",
+                serde_json::to_string(&voter)?,
+                serde_json::to_string(&proposal_id)?,
+                in_favor,
+                serde_json::to_string(&data_dir)?
+            ),
+        )
+        .await
+    }
+
+    /// Gets the live tally of the votes on the given proposal.
+    pub async fn get_tally(
+        &self,
+        proposal_id: String,
+    ) -> std::result::Result<ProposalTally, failure::Error> {
+        self.do_request::<ProposalTally>(
+            "get_tally",
+            &format!("[{}]", serde_json::to_string(&proposal_id)?),
+        )
+        .await
+    }
+
+    /// Gets a list of the votes cast by the accounts held by the node
+    pub async fn list_own_votes(&self) -> std::result::Result<Vec<Vote>, failure::Error> {
+        self.do_request::<Vec<Vote>>("list_own_votes", "[]").await
+    }
 }
@@ -1,9 +1,20 @@
 use super::{
     common::address::Address,
-    core::types::{graph::Graph, transaction::Transaction},
-    crypto::{blake3, hash::Hash},
+    core::{
+        sys::config::Config,
+        types::{graph::Graph, payload, stamp, state::Entry, transaction::Transaction},
+    },
+    crypto::hash::Hash,
 };
+use chrono::{DateTime, Utc};
 use num::{BigUint, Zero};
+use serde::{Deserialize, Serialize};
+
+use std::{cell::RefCell, collections::HashMap, fmt};
+
+pub mod rules; // Export the validation rules module
+
+use rules::ValidationRule;
 
 /// A generic rule-enforcing transactional system.
 pub trait Validator {
@@ -15,11 +26,77 @@ pub trait Validator {
     fn transaction_is_valid(&self, tx: &Transaction) -> Result<(), failure::Error>;
 }
 
-/// A validator that is bound to the confines of a given graph. This validator presents zero cost, and requires
-/// no runtime allocations.
+/// The state against which each validation rule is evaluated.
+pub struct ValidationContext<'a> {
+    /// The graph to which validation is bound
+    pub graph: &'a Graph,
+    /// The identifier of the chain in whose context transactions must have been signed
+    pub network: &'a str,
+    /// The balance assumed for accounts that don't have one yet
+    pub minimum_balance: BigUint,
+    /// The maximum number of bytes that a transaction's payload may contain
+    pub max_payload_size: usize,
+    /// The amount of work that must be proven by each transaction's stamp
+    pub stamp_difficulty: u8,
+    /// The merged state of each set of parents resolved so far, so that each set is only resolved once
+    parent_states: RefCell<HashMap<Vec<Hash>, Option<Entry>>>,
+}
+
+/// A rule violated by a transaction.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RuleViolation {
+    /// The name of the rule that was violated
+    pub rule: String,
+    /// The reason for which the transaction violates the rule
+    pub reason: String,
+}
+
+/// A report describing each of the rules that a transaction was checked against, and each of those that it violates.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValidationReport {
+    /// The hash of the transaction that was checked
+    pub tx_hash: Hash,
+    /// The names of each of the rules that the transaction was checked against, in order
+    pub checked_rules: Vec<String>,
+    /// Each of the rules that the transaction violates
+    pub violations: Vec<RuleViolation>,
+}
+
+impl ValidationReport {
+    /// Checks whether or not the transaction satisfies every one of the rules that it was checked against.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "transaction {} is valid", self.tx_hash);
+        }
+
+        write!(
+            f,
+            "transaction {} violates {} of {} rules",
+            self.tx_hash,
+            self.violations.len(),
+            self.checked_rules.len()
+        )?;
+
+        // Describe each of the violations
+        for violation in &self.violations {
+            write!(f, "; {}: {}", violation.rule, violation.reason)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A validator that is bound to the confines of a given graph. Transactions are checked against each of the
+/// validator's rules, in order.
 pub struct GraphBoundValidator<'a> {
-    graph: &'a Graph,
-    minimum_balance: BigUint,
+    context: ValidationContext<'a>,
+    rules: Vec<Box<dyn ValidationRule>>,
 }
 
 /// A reason provided by a GraphBoundValidator for why a particular transaction is invalid.
@@ -69,175 +146,267 @@ pub enum GraphBoundValidatorReason {
         found: u64,
         target: BigUint,
     },
+    #[fail(
+        display = "transaction {} is dated {}, too long after its parents (the latest allowed is {})",
+        tx_hash, timestamp, latest
+    )]
+    TimestampTooLate {
+        tx_hash: Hash,
+        timestamp: DateTime<Utc>,
+        latest: DateTime<Utc>,
+    },
+    #[fail(
+        display = "transaction {} is time-locked until {}",
+        tx_hash, not_before
+    )]
+    TimeLocked {
+        tx_hash: Hash,
+        not_before: DateTime<Utc>,
+    },
+    #[fail(
+        display = "transaction {} depends on transaction {}, which has not yet been accepted",
+        tx_hash, dependency
+    )]
+    UnmetDependency { tx_hash: Hash, dependency: Hash },
+    #[fail(
+        display = "the payload of transaction {} is too large ({} bytes; the maximum is {})",
+        tx_hash, size, max_size
+    )]
+    PayloadTooLarge {
+        tx_hash: Hash,
+        size: usize,
+        max_size: usize,
+    },
+    #[fail(display = "transaction {} has a malformed payload: {}", tx_hash, error)]
+    MalformedPayload { tx_hash: Hash, error: String },
+    #[fail(
+        display = "transaction {} carries an invalid delegation: {}",
+        tx_hash, reason
+    )]
+    InvalidDelegation { tx_hash: Hash, reason: String },
+    #[fail(
+        display = "transaction {} proves insufficient work ({} bits; the minimum is {})",
+        tx_hash, work, difficulty
+    )]
+    InsufficientWork {
+        tx_hash: Hash,
+        work: u32,
+        difficulty: u8,
+    },
+}
+
+impl<'a> ValidationContext<'a> {
+    /// Gets the time against which the transaction's time lock is checked: the newest timestamp among its parents.
+    /// Unlike the clock of any one node, the time of the transaction's parents is the same on every node, and when
+    /// the ledger is replayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction whose parents' time should be determined
+    pub fn parent_time(&self, tx: &Transaction) -> Option<DateTime<Utc>> {
+        self.graph.latest_timestamp(&tx.transaction_data.parents)
+    }
+
+    /// Resolves the merged state of each of the transaction's declared parents. This is the state that the
+    /// transaction builds on, and to which its parent state hash commits. Each set of parents is only resolved once
+    /// per context, however many rules ask for its state.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction whose parent state should be resolved
+    pub fn parent_state(&self, tx: &Transaction) -> Option<Entry> {
+        let parents = &tx.transaction_data.parents;
+
+        // Use the state resolved by an earlier rule, if there is one
+        if let Some(state) = self.parent_states.borrow().get(parents) {
+            return state.clone();
+        }
+
+        let state = self
+            .graph
+            .resolve_parent_nodes(parents.clone())
+            .ok()
+            .map(|(merged_state, _)| merged_state);
+        self.parent_states
+            .borrow_mut()
+            .insert(parents.clone(), state.clone());
+
+        state
+    }
 }
 
 impl<'a> GraphBoundValidator<'a> {
-    /// Initializes a new validator from the provided graph.
+    /// Initializes a new validator from the provided graph, enforcing each of the default validation rules.
     ///
     /// # Arguments
     ///
     /// * `graph` - The graph to which validation will be bound.
-    pub fn new(graph: &'a Graph) -> Self {
+    /// * `network` - The name of the network in whose context transactions must have been signed.
+    pub fn new(graph: &'a Graph, network: &'a str) -> Self {
         // Make a new validator
         Self {
-            graph,
-            minimum_balance: BigUint::zero(),
+            context: ValidationContext {
+                graph,
+                network,
+                minimum_balance: BigUint::zero(),
+                max_payload_size: payload::DEFAULT_MAX_PAYLOAD_SIZE,
+                stamp_difficulty: stamp::DEFAULT_STAMP_DIFFICULTY,
+                parent_states: RefCell::new(HashMap::new()),
+            },
+            rules: rules::default_rules(),
         }
     }
 
-    /// Checks whether or not the transaction already eists in the graph.
+    /// Initializes a new validator from the provided graph, enforcing each of the default validation rules with the
+    /// limits set by the given network config.
     ///
     /// # Arguments
     ///
-    /// * `tx` - The transaction that should be checked for uniqueness among the graph's txs
-    fn transaction_is_unique(&self, tx: &Transaction) -> bool {
-        // Return whether or not the transaction exists in the graph
-        !self.graph.hash_routes.contains_key(&tx.hash)
+    /// * `graph` - The graph to which validation will be bound.
+    /// * `config` - The config of the network on which transactions will be published
+    pub fn from_config(graph: &'a Graph, config: &'a Config) -> Self {
+        Self::new(graph, config.chain_id())
+            .with_max_payload_size(config.max_payload_size)
+            .with_stamp_difficulty(config.stamp_difficulty)
     }
 
-    /// Checks whether or not the transaction exists along an incomplete head. In other words, the transaction must be
-    /// recent enough in order to be valid.
+    /// Limits the size of the payloads of transactions accepted by the validator to that set by the network.
     ///
     /// # Arguments
     ///
-    /// * `tx` - The transaction that should be checked for uniqueness among the graph's txs
-    fn transaction_is_head(&self, tx: &Transaction) -> (bool, Hash) {
-        // Get all of the parents that the transaction relies on
-        for i in 0..tx.transaction_data.parents.len() {
-            // The hash of the parental node
-            let parent = tx.transaction_data.parents[i];
-
-            // If we know what the index of this parent transaction is in the state graph, we can
-            // try to pull out a fully-formed state matching this parent transaction
-            if let Some(parent_index) = self.graph.hash_routes.get(&parent) {
-                // Check that the parent exists. If it doesen't, return false.
-                if let Ok(Some(parent)) = self.graph.get_pure(*parent_index) {
-                    // The parent node shouldn't have already been resolved. The transaction is, thus, invalid.
-                    if parent.state_entry.is_some() {
-                        return (false, parent.hash);
-                    }
-                } else {
-                    // Since the transaction's parents don't exist, the tx isn't valid
-                    return (false, Hash::new("NILPARENT".to_owned().into_bytes()));
-                }
-            }
-        }
+    /// * `max_payload_size` - The maximum number of bytes that a transaction's payload may contain
+    pub fn with_max_payload_size(mut self, max_payload_size: usize) -> Self {
+        self.context.max_payload_size = max_payload_size;
 
-        (true, Default::default())
+        self
     }
 
-    /// Ensure that the transaction's reported hashes are in fact valid.
+    /// Requires that each transaction's stamp proves at least the given amount of work.
     ///
     /// # Arguments
     ///
-    /// * `tx` - The transaction that should be checked for uniqueness among the graph's txs
-    fn transaction_hash_is_valid(&self, tx: &Transaction) -> (bool, Hash) {
-        // Hash the transaction
-        let target =
-            blake3::hash_slice(&bincode::serialize(&tx.transaction_data).unwrap_or_default());
+    /// * `stamp_difficulty` - The number of leading zero bits that each stamp's work hash must have
+    pub fn with_stamp_difficulty(mut self, stamp_difficulty: u8) -> Self {
+        self.context.stamp_difficulty = stamp_difficulty;
 
-        // Make sure that the transaction's hash can be reproduced
-        (tx.hash == target, target)
+        self
     }
 
-    /// Ensures that that the signature included in the transaction is in fact valid.
+    /// Replaces the rules enforced by the validator with the given rules.
     ///
     /// # Arguments
     ///
-    /// * `tx` - The transaction that should be checked for uniqueness among the graph's txs
-    fn transaction_signature_is_valid(&self, tx: &Transaction) -> bool {
-        // Verify the transaction's signature
-        tx.verify_signature()
+    /// * `rules` - The rules that transactions should be checked against, in order
+    pub fn with_rules(mut self, rules: Vec<Box<dyn ValidationRule>>) -> Self {
+        self.rules = rules;
+
+        self
     }
 
-    /// Ensures that the state transition provided by the transaction can be reproduced, and is valid.
+    /// Enforces an additional rule, after each of the validator's existing rules.
     ///
     /// # Arguments
     ///
-    /// * `tx` - The transaction that should be checked for a valid state transition
-    fn transaction_parent_execution_is_valid(&self, tx: &Transaction) -> bool {
-        // Collect the states for each of the transaction's parents
-        if let Ok(parent_state) = self
-            .graph
-            .resolve_parent_nodes(tx.transaction_data.parents.clone())
-        {
-            // Ensure that the transaction provides a parent state hash that we can compare the reproduced one against
-            if let Some(cited_parent_hash) = tx.transaction_data.parent_state_hash {
-                // Ensure that the parent hash is the same as that provided by the transaction
-                parent_state.0.hash == cited_parent_hash
-            } else {
-                false
-            }
-        } else {
-            // Since we can't execute the parents, this transaction must be invalid
-            false
-        }
+    /// * `rule` - The rule that transactions should also be checked against
+    pub fn with_rule(mut self, rule: Box<dyn ValidationRule>) -> Self {
+        self.rules.push(rule);
+
+        self
     }
 
-    /// Ensures that the sender of the provided transaction has enough SummerCash to perform the transaction.
+    /// Stops enforcing the rule with the given name, if the validator enforces such a rule.
     ///
     /// # Arguments
     ///
-    /// * `tx` - The transaction that should be checked against
-    fn transaction_sender_balance_is_sufficient(
-        &self,
-        tx: &Transaction,
-    ) -> (BigUint, BigUint, bool) {
-        // Check for a latest state entry in the graph. This will serve as the point from where we calculate the account's balance.
-        if let Some(last_state) = self.graph.obtain_executed_head() {
-            // Ensure that the provided transaction has in fact been executed
-            if let Some(state) = last_state.state_entry {
-                // Get the balance of the sender of the transaction
-                let sender_balance = state
-                    .data
-                    .balances
-                    .get(&tx.transaction_data.sender.to_str())
-                    .unwrap_or(&self.minimum_balance);
-
-                // The sender must have at least enough coins to send the transaction
-                return (
-                    sender_balance.clone(),
-                    tx.transaction_data.value.clone(),
-                    *sender_balance >= tx.transaction_data.value,
-                );
-            }
-        }
+    /// * `name` - The name of the rule that should no longer be enforced
+    pub fn without_rule(mut self, name: &str) -> Self {
+        self.rules.retain(|rule| rule.name() != name);
+
+        self
+    }
+
+    /// Gets the names of each of the rules enforced by the validator, in the order in which they are checked.
+    pub fn rule_names(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
+    /// Checks the transaction against every one of the validator's rules, rather than stopping at the first rule that
+    /// the transaction violates, and reports each of the violations.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be validated
+    pub fn validation_report(&self, tx: &Transaction) -> ValidationReport {
+        self.report(tx, true)
+    }
 
-        // If the sender doesn't have any SMC, they can't send any. Therefore, the value of the transaction must be zero.
-        (
-            self.minimum_balance.clone(),
-            tx.transaction_data.value.clone(),
-            tx.transaction_data.value == BigUint::default(),
-        )
+    /// Checks the transaction against the validator's rules in order, stopping at the first rule that the
+    /// transaction violates, and reports that violation (if any). Unlike a full report, this doesn't run the more
+    /// expensive rules for transactions that have already failed a cheaper one.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be validated
+    pub fn first_violation_report(&self, tx: &Transaction) -> ValidationReport {
+        self.report(tx, false)
     }
 
-    /// Ensures that the nonce provided by the transaction matches the target nonce for such a
-    /// transaction.
+    /// Checks the transaction against the validator's rules in order, and reports each of the violations.
     ///
     /// # Arguments
     ///
-    /// * `tx` - The transaction that the nonce should be checked of
-    fn transaction_nonce_is_valid(&self, tx: &Transaction) -> (bool, BigUint) {
-        // Check for a latest state entry in the graph. This will serve as the point from where we calculate the account's nonce.
-        if let Some(last_state) = self.graph.obtain_executed_head() {
-            // Ensure that the provided transaction has in fact been executed
-            if let Some(state) = last_state.state_entry {
-                // Ensure that the current nonce is equal to exactly the last nonce +1
-                if let Some(last_nonce) = state
-                    .data
-                    .balances
-                    .get(&tx.transaction_data.sender.to_str())
-                {
-                    return (
-                        last_nonce.clone() == BigUint::from(tx.transaction_data.nonce - 1),
-                        last_nonce.clone() + (1 as u8),
-                    );
+    /// * `tx` - The transaction that should be validated
+    /// * `exhaustive` - Whether or not the remaining rules should still be checked after one has been violated
+    fn report(&self, tx: &Transaction, exhaustive: bool) -> ValidationReport {
+        let mut report = ValidationReport {
+            tx_hash: tx.hash,
+            checked_rules: Vec::new(),
+            violations: Vec::new(),
+        };
+
+        for rule in &self.rules {
+            report.checked_rules.push(rule.name().to_owned());
+
+            if let Err(reason) = rule.check(&self.context, tx) {
+                report.violations.push(RuleViolation {
+                    rule: rule.name().to_owned(),
+                    reason: reason.to_string(),
+                });
+
+                // Stop at the first violation, unless each of them should be reported
+                if !exhaustive {
+                    break;
                 }
             }
         }
 
-        // The nonce must be 0 since there is not a parent
-        (tx.transaction_data.nonce == 0, BigUint::zero())
+        report
+    }
+
+    /// Checks whether or not the transaction's time lock has elapsed, and its dependency (if any) has been accepted.
+    /// Transactions that aren't yet mature should be held until they are, as long as they can mature.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be checked for maturity
+    pub fn transaction_is_mature(&self, tx: &Transaction) -> bool {
+        rules::TimeLockRule.check(&self.context, tx).is_ok()
+            && rules::DependencyRule.check(&self.context, tx).is_ok()
+    }
+
+    /// Checks whether or not the transaction could ever mature. The time of a transaction's parents never changes, so
+    /// a transaction whose parents are all in the graph, and that is time-locked beyond their time, never matures.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction that should be checked
+    pub fn transaction_can_mature(&self, tx: &Transaction) -> bool {
+        let parents = &tx.transaction_data.parents;
+
+        !parents
+            .iter()
+            .all(|parent| self.context.graph.hash_routes.contains_key(parent))
+            || rules::TimeLockRule.check(&self.context, tx).is_ok()
     }
 }
 
@@ -248,73 +417,11 @@ impl<'a> Validator for GraphBoundValidator<'a> {
     ///
     /// * `tx` - The transaction that should be validated
     fn transaction_is_valid(&self, tx: &Transaction) -> Result<(), failure::Error> {
-        // Ensure that all of the properties of the transaction are in fact valid
-        if !self.transaction_is_unique(tx) {
-            Err(GraphBoundValidatorReason::NotUnique { tx_hash: tx.hash }.into())
-        } else {
-            let (ok, offending_parent_hash) = self.transaction_is_head(tx);
-
-            // Ensure that the transaction is young enough
-            if !ok {
-                Err(GraphBoundValidatorReason::TooOld {
-                    tx_hash: tx.hash,
-                    invalid_parent_hash: offending_parent_hash,
-                }
-                .into())
-            } else if !self.transaction_signature_is_valid(tx) {
-                Err(GraphBoundValidatorReason::InvalidSignature { tx_hash: tx.hash }.into())
-            } else {
-                // Ensure that the transaction's hash can be reproduced
-                let (ok, target_hash) = self.transaction_hash_is_valid(tx);
-
-                // If the hash can't be reproduced, the tx is invalid
-                if !ok {
-                    Err(GraphBoundValidatorReason::InvalidHash {
-                        tx_hash: tx.hash,
-                        desired_hash: target_hash,
-                    }
-                    .into())
-                } else if !self.transaction_parent_execution_is_valid(tx) {
-                    Err(GraphBoundValidatorReason::ParentReceiptInvalid { tx_hash: tx.hash }.into())
-                } else {
-                    // Check that the sender of the transaction has enough coins to send the
-                    // transaction
-                    let (sender_balance, value, ok) =
-                        self.transaction_sender_balance_is_sufficient(tx);
-
-                    // If the user sending the transaction doesn't have enough SMC to actually send this transaction, return an error
-                    if !ok {
-                        Err(GraphBoundValidatorReason::InsufficientSenderBalance {
-                            tx_hash: tx.hash,
-                            sender: tx.transaction_data.sender,
-                            balance: sender_balance,
-                            tx_value: value,
-                        }
-                        .into())
-                    } else if tx.transaction_data.sender == tx.transaction_data.recipient {
-                        Err(GraphBoundValidatorReason::AttemptedIdentityOperation {
-                            tx_hash: tx.hash,
-                            sender: tx.transaction_data.sender,
-                        }
-                        .into())
-                    } else {
-                        // Make sure the transaction's nonce is valid
-                        let (ok, target) = self.transaction_nonce_is_valid(tx);
-
-                        if !ok {
-                            Err(GraphBoundValidatorReason::InvalidNonce {
-                                tx_hash: tx.hash,
-                                found: tx.transaction_data.nonce,
-                                target,
-                                offending_account: tx.transaction_data.sender,
-                            }
-                            .into())
-                        } else {
-                            Ok(())
-                        }
-                    }
-                }
-            }
+        // The transaction is only valid if it satisfies every one of the rules
+        for rule in &self.rules {
+            rule.check(&self.context, tx)?;
         }
+
+        Ok(())
     }
 }
@@ -0,0 +1,886 @@
+use super::{
+    super::{
+        core::{
+            sys::delegation::Delegation,
+            types::{encoding::Canonical, transaction::Transaction},
+        },
+        crypto::hash::Hash,
+    },
+    GraphBoundValidatorReason, ValidationContext,
+};
+use chrono::Duration;
+use num::BigUint;
+
+/// The furthest, in seconds, that a transaction's timestamp may be ahead of the newest of its parents' timestamps.
+pub const MAX_TIMESTAMP_DRIFT_SECS: i64 = 600;
+
+/// A single, independent check that a transaction must pass in order to be valid.
+pub trait ValidationRule {
+    /// Gets a short, unique name for the rule (e.g. "nonce").
+    fn name(&self) -> &'static str;
+
+    /// Checks the transaction against the rule, returning the reason for which the transaction violates the rule, if
+    /// it does.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The state against which the transaction should be checked
+    /// * `tx` - The transaction that should be checked
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason>;
+}
+
+/// Gets each of the rules enforced by a validator by default, in the order in which they should be checked. Cheap
+/// rules (e.g. the stamp rule) are checked before those requiring any state resolution.
+pub fn default_rules() -> Vec<Box<dyn ValidationRule>> {
+    vec![
+        Box::new(StampRule),
+        Box::new(UniquenessRule),
+        Box::new(RecencyRule),
+        Box::new(SignatureRule),
+        Box::new(HashRule),
+        Box::new(PayloadSizeRule),
+        Box::new(PayloadFormatRule),
+        Box::new(DelegationRule),
+        Box::new(TimestampRule),
+        Box::new(TimeLockRule),
+        Box::new(DependencyRule),
+        Box::new(ParentStateRule),
+        Box::new(BalanceRule),
+        Box::new(IdentityRule),
+        Box::new(NonceRule),
+    ]
+}
+
+/// Ensures that the transaction's stamp proves enough work.
+pub struct StampRule;
+
+impl ValidationRule for StampRule {
+    fn name(&self) -> &'static str {
+        "stamp"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        let work = tx.work();
+
+        if work >= u32::from(context.stamp_difficulty) {
+            Ok(())
+        } else {
+            Err(GraphBoundValidatorReason::InsufficientWork {
+                tx_hash: tx.hash,
+                work,
+                difficulty: context.stamp_difficulty,
+            })
+        }
+    }
+}
+
+/// Ensures that the transaction doesn't already exist in the graph.
+pub struct UniquenessRule;
+
+impl ValidationRule for UniquenessRule {
+    fn name(&self) -> &'static str {
+        "unique"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        // Return whether or not the transaction exists in the graph
+        if context.graph.hash_routes.contains_key(&tx.hash) {
+            Err(GraphBoundValidatorReason::NotUnique { tx_hash: tx.hash })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Ensures that the transaction exists along an incomplete head. In other words, the transaction must be recent
+/// enough in order to be valid.
+pub struct RecencyRule;
+
+impl ValidationRule for RecencyRule {
+    fn name(&self) -> &'static str {
+        "recent"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        let too_old = |invalid_parent_hash: Hash| GraphBoundValidatorReason::TooOld {
+            tx_hash: tx.hash,
+            invalid_parent_hash,
+        };
+
+        // Get all of the parents that the transaction relies on
+        for parent in &tx.transaction_data.parents {
+            // If we know what the index of this parent transaction is in the state graph, we can
+            // try to pull out a fully-formed state matching this parent transaction
+            if let Some(parent_index) = context.graph.hash_routes.get(parent) {
+                // Check that the parent exists. If it doesen't, the transaction isn't valid.
+                if let Ok(Some(parent)) = context.graph.get_pure(*parent_index) {
+                    // The parent node shouldn't have already been resolved. The transaction is, thus, invalid.
+                    if parent.state_entry.is_some() {
+                        return Err(too_old(parent.hash));
+                    }
+                } else {
+                    return Err(too_old(Hash::new("NILPARENT".to_owned().into_bytes())));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Ensures that the signature included in the transaction is in fact valid.
+pub struct SignatureRule;
+
+impl ValidationRule for SignatureRule {
+    fn name(&self) -> &'static str {
+        "signature"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        // Verify the transaction's signature
+        if tx.verify_signature(context.network) {
+            Ok(())
+        } else {
+            Err(GraphBoundValidatorReason::InvalidSignature { tx_hash: tx.hash })
+        }
+    }
+}
+
+/// Ensures that the transaction's reported hash can be reproduced.
+pub struct HashRule;
+
+impl ValidationRule for HashRule {
+    fn name(&self) -> &'static str {
+        "hash"
+    }
+
+    fn check(
+        &self,
+        _context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        // Hash the transaction
+        let desired_hash = tx.transaction_data.canonical_hash();
+
+        if tx.hash == desired_hash {
+            Ok(())
+        } else {
+            Err(GraphBoundValidatorReason::InvalidHash {
+                tx_hash: tx.hash,
+                desired_hash,
+            })
+        }
+    }
+}
+
+/// Ensures that the transaction's payload doesn't exceed the maximum payload size.
+pub struct PayloadSizeRule;
+
+impl ValidationRule for PayloadSizeRule {
+    fn name(&self) -> &'static str {
+        "payload_size"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        let size = tx.transaction_data.payload.len();
+
+        if size > context.max_payload_size {
+            Err(GraphBoundValidatorReason::PayloadTooLarge {
+                tx_hash: tx.hash,
+                size,
+                max_size: context.max_payload_size,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Ensures that the transaction's payload matches its declared content type.
+pub struct PayloadFormatRule;
+
+impl ValidationRule for PayloadFormatRule {
+    fn name(&self) -> &'static str {
+        "payload_format"
+    }
+
+    fn check(
+        &self,
+        _context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        tx.transaction_data.payload.validate().map_err(|e| {
+            GraphBoundValidatorReason::MalformedPayload {
+                tx_hash: tx.hash,
+                error: e.to_string(),
+            }
+        })
+    }
+}
+
+/// Ensures that any delegation carried by the transaction was made by the transaction's sender.
+pub struct DelegationRule;
+
+impl ValidationRule for DelegationRule {
+    fn name(&self) -> &'static str {
+        "delegation"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        match Delegation::from_transaction(tx, context.network) {
+            Some(Err(reason)) => Err(GraphBoundValidatorReason::InvalidDelegation {
+                tx_hash: tx.hash,
+                reason,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Ensures that the transaction isn't dated too long after its parents, such that the time of the transactions built on
+/// it can only advance so far with each transaction.
+pub struct TimestampRule;
+
+impl ValidationRule for TimestampRule {
+    fn name(&self) -> &'static str {
+        "timestamp"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        let latest = if let Some(parent_time) = context.parent_time(tx) {
+            parent_time + Duration::seconds(MAX_TIMESTAMP_DRIFT_SECS)
+        } else {
+            return Ok(());
+        };
+
+        if tx.transaction_data.timestamp <= latest {
+            Ok(())
+        } else {
+            Err(GraphBoundValidatorReason::TimestampTooLate {
+                tx_hash: tx.hash,
+                timestamp: tx.transaction_data.timestamp,
+                latest,
+            })
+        }
+    }
+}
+
+/// Ensures that the transaction's time lock (if any) has elapsed, according to the newest timestamp among its parents.
+pub struct TimeLockRule;
+
+impl ValidationRule for TimeLockRule {
+    fn name(&self) -> &'static str {
+        "time_lock"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        let not_before = if let Some(not_before) = tx.transaction_data.not_before {
+            not_before
+        } else {
+            return Ok(());
+        };
+
+        // Nodes' clocks may disagree, so time locks are measured against the transaction's parents instead
+        if context
+            .parent_time(tx)
+            .map_or(false, |time| tx.time_lock_has_elapsed(time))
+        {
+            Ok(())
+        } else {
+            Err(GraphBoundValidatorReason::TimeLocked {
+                tx_hash: tx.hash,
+                not_before,
+            })
+        }
+    }
+}
+
+/// Ensures that the transaction that the transaction depends on (if any) has been accepted.
+pub struct DependencyRule;
+
+impl ValidationRule for DependencyRule {
+    fn name(&self) -> &'static str {
+        "dependency"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        // If the transaction doesn't depend on anything, there's nothing to check
+        match tx.transaction_data.after {
+            Some(dependency) if !context.graph.hash_routes.contains_key(&dependency) => {
+                Err(GraphBoundValidatorReason::UnmetDependency {
+                    tx_hash: tx.hash,
+                    dependency,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Ensures that the parent state cited by the transaction can be reproduced.
+pub struct ParentStateRule;
+
+impl ValidationRule for ParentStateRule {
+    fn name(&self) -> &'static str {
+        "parent_state"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        // Collect the states for each of the transaction's parents. If we can't execute the parents, this transaction
+        // must be invalid.
+        let parent_state_hash = context.parent_state(tx).map(|state| state.hash);
+
+        // Ensure that the parent hash is the same as that provided by the transaction
+        if parent_state_hash.is_some() && tx.transaction_data.parent_state_hash == parent_state_hash
+        {
+            Ok(())
+        } else {
+            Err(GraphBoundValidatorReason::ParentReceiptInvalid { tx_hash: tx.hash })
+        }
+    }
+}
+
+/// Ensures that the sender of the transaction has enough SummerCash to perform the transaction.
+pub struct BalanceRule;
+
+impl ValidationRule for BalanceRule {
+    fn name(&self) -> &'static str {
+        "balance"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        // The state that the transaction builds on serves as the point from which we calculate the sender's balance.
+        // If the sender doesn't have any SMC, they can't send any.
+        let balance = context
+            .parent_state(tx)
+            .and_then(|state| {
+                state
+                    .data
+                    .balances
+                    .get(&tx.transaction_data.sender.to_str())
+                    .cloned()
+            })
+            .unwrap_or_else(|| context.minimum_balance.clone());
+
+        // The sender must have at least enough coins to send the transaction
+        if balance >= tx.transaction_data.value {
+            Ok(())
+        } else {
+            Err(GraphBoundValidatorReason::InsufficientSenderBalance {
+                tx_hash: tx.hash,
+                sender: tx.transaction_data.sender,
+                balance,
+                tx_value: tx.transaction_data.value.clone(),
+            })
+        }
+    }
+}
+
+/// Ensures that the sender of the transaction isn't also its recipient.
+pub struct IdentityRule;
+
+impl ValidationRule for IdentityRule {
+    fn name(&self) -> &'static str {
+        "identity"
+    }
+
+    fn check(
+        &self,
+        _context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        if tx.transaction_data.sender == tx.transaction_data.recipient {
+            Err(GraphBoundValidatorReason::AttemptedIdentityOperation {
+                tx_hash: tx.hash,
+                sender: tx.transaction_data.sender,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Ensures that the nonce of the transaction immediately follows the last nonce recorded for its sender, or the nonce
+/// of the sender's transaction that it depends on.
+pub struct NonceRule;
+
+impl ValidationRule for NonceRule {
+    fn name(&self) -> &'static str {
+        "nonce"
+    }
+
+    fn check(
+        &self,
+        context: &ValidationContext,
+        tx: &Transaction,
+    ) -> Result<(), GraphBoundValidatorReason> {
+        // The state that the transaction builds on records the last nonce used by the sender. If the sender hasn't
+        // sent a transaction yet, the nonce must be 0.
+        let state_target = context
+            .parent_state(tx)
+            .and_then(|state| {
+                state
+                    .data
+                    .nonces
+                    .get(&tx.transaction_data.sender.to_str())
+                    .map(|last_nonce| BigUint::from(*last_nonce) + 1u8)
+            })
+            .unwrap_or_default();
+
+        // A transaction queued behind one of the sender's own transactions that its parents don't know about yet
+        // follows on from that transaction's nonce instead
+        let dependency_target = tx
+            .transaction_data
+            .after
+            .and_then(|dependency| context.graph.get_with_hash(dependency).ok())
+            .map(|dependency| &dependency.transaction.transaction_data)
+            .filter(|dependency| dependency.sender == tx.transaction_data.sender)
+            .map(|dependency| BigUint::from(dependency.nonce) + 1u8);
+
+        let target = dependency_target.map_or(state_target.clone(), |dependency_target| {
+            dependency_target.max(state_target)
+        });
+
+        if BigUint::from(tx.transaction_data.nonce) == target {
+            Ok(())
+        } else {
+            Err(GraphBoundValidatorReason::InvalidNonce {
+                tx_hash: tx.hash,
+                offending_account: tx.transaction_data.sender,
+                found: tx.transaction_data.nonce,
+                target,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        super::{
+            common::{address::Address, io},
+            core::types::{
+                graph::Graph, payload::Payload, stamp::Stamp, state::Entry,
+                transaction::TransactionBuilder,
+            },
+        },
+        GraphBoundValidator, Validator,
+    };
+    use super::*; // Import names from the parent module
+
+    use crate::rand::Rng; // Import rand
+    use ed25519_dalek::Keypair; // Import the edwards25519 digital signature library
+    use rand::rngs::OsRng; // Import the os's rng
+
+    use std::{cell::RefCell, collections::HashMap};
+
+    /// Makes a graph containing only a root transaction.
+    fn root_graph() -> Graph {
+        let root_tx = TransactionBuilder::new()
+            .nonce(0)
+            .sender(Address::default())
+            .recipient(Address::default())
+            .build()
+            .unwrap(); // Make a root transaction
+
+        Graph::new_with_db_path(
+            root_tx,
+            &io::format_db_dir(&format!(".tests/{}", rand::thread_rng().gen::<u16>())),
+        ) // Initialize the graph
+    }
+
+    /// Adds an executed node recording the given nonce and balance for the sender to the graph, and returns its hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `dag` - The graph that the node should be added to
+    /// * `sender` - The account whose nonce and balance should be recorded
+    /// * `last_nonce` - The last nonce used by the sender, if the sender has used one
+    /// * `balance` - The balance of the sender
+    fn push_sender_state(
+        dag: &mut Graph,
+        sender: Address,
+        last_nonce: Option<u64>,
+        balance: u64,
+    ) -> Hash {
+        let tx = TransactionBuilder::new()
+            .nonce(last_nonce.unwrap_or_default())
+            .sender(sender)
+            .recipient(Address::default())
+            .parents(vec![dag.nodes[0].hash])
+            .value(BigUint::from(dag.nodes.len())) // Make each node's hash unique
+            .build()
+            .unwrap(); // Make a transaction to attach the sender's state to
+        let hash = tx.hash;
+
+        dag.push(
+            tx,
+            Some(Entry::new(
+                last_nonce
+                    .map(|nonce| (sender.to_str(), nonce))
+                    .into_iter()
+                    .collect(),
+                vec![(sender.to_str(), BigUint::from(balance))]
+                    .into_iter()
+                    .collect(),
+            )),
+        );
+
+        hash
+    }
+
+    /// Makes a context in which rules can be checked against the given graph.
+    fn context(dag: &Graph) -> ValidationContext<'_> {
+        ValidationContext {
+            graph: dag,
+            network: "olympia",
+            minimum_balance: BigUint::default(),
+            max_payload_size: 0,
+            stamp_difficulty: 0,
+            parent_states: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Makes an unsigned transaction built on the given parents, committing to their merged state.
+    ///
+    /// # Arguments
+    ///
+    /// * `dag` - The graph containing the parents
+    /// * `sender` - The sender of the transaction
+    /// * `nonce` - The nonce of the transaction
+    /// * `value` - The number of finks sent through the transaction
+    /// * `parents` - The parents of the transaction
+    fn tx_on(
+        dag: &Graph,
+        sender: Address,
+        nonce: u64,
+        value: u64,
+        parents: Vec<Hash>,
+    ) -> Transaction {
+        let (merged_state, parent_states) = dag.resolve_parent_nodes(parents.clone()).unwrap();
+
+        TransactionBuilder::new()
+            .nonce(nonce)
+            .sender(sender)
+            .recipient(Address::default())
+            .value(BigUint::from(value))
+            .parents(parents)
+            .parental_state(merged_state, parent_states)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_nonce_rule() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender = Address::from_key_pair(&Keypair::generate(&mut csprng)); // Derive a sender address
+        let stranger = Address::from_key_pair(&Keypair::generate(&mut csprng)); // Derive an address without any txs
+
+        let mut dag = root_graph();
+        let parent = push_sender_state(&mut dag, sender, Some(3), 1); // Record a nonce, and a balance distinct from it
+        let exhausted = push_sender_state(&mut dag, sender, Some(u64::MAX), 1); // Record the greatest possible nonce
+        let queued = push_sender_state(&mut dag, sender, Some(4), 1); // Record a transaction to queue behind
+        let stale = push_sender_state(&mut dag, sender, Some(1), 1); // Record a transaction with an old nonce
+
+        let context = context(&dag); // Make a context for the rule
+        let check = |sender: Address, nonce: u64, parent: Hash| {
+            NonceRule.check(&context, &tx_on(&dag, sender, nonce, 0, vec![parent]))
+        };
+
+        assert!(check(sender, 4, parent).is_ok()); // Ensure the next nonce is accepted
+        assert!(check(sender, 3, parent).is_err()); // Ensure nonces can't be reused
+        assert!(check(sender, 2, parent).is_err()); // Ensure the nonce isn't read from the balance
+        assert!(check(stranger, 0, parent).is_ok()); // Ensure new senders start at 0
+        assert!(check(stranger, 1, parent).is_err()); // Ensure new senders can't skip 0
+
+        // Ensure transactions queued behind another of the sender's transactions follow on from its nonce
+        let after = |nonce: u64, dependency: Hash| {
+            let mut tx = tx_on(&dag, sender, nonce, 0, vec![parent]);
+            tx.transaction_data.after = Some(dependency);
+
+            NonceRule.check(&context, &tx)
+        };
+        assert!(after(5, queued).is_ok());
+        assert!(after(4, queued).is_err());
+        assert!(after(2, stale).is_err()); // Ensure stale dependencies don't lower the target
+
+        match check(sender, u64::MAX, exhausted) {
+            Err(GraphBoundValidatorReason::InvalidNonce { target, .. }) => {
+                assert_eq!(target, BigUint::from(u64::MAX) + 1u8)
+            } // Ensure the target doesn't overflow
+            _ => panic!("exhausted nonce accepted"),
+        }
+
+        assert_eq!(dag.write_to_disk(), Ok(())); // Close the dag
+    }
+
+    #[test]
+    fn test_stamp_rule() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender = Address::from_key_pair(&Keypair::generate(&mut csprng)); // Derive a sender address
+
+        let dag = root_graph();
+        let mut tx = tx_on(&dag, sender, 0, 0, vec![dag.nodes[0].hash]); // Make an unstamped transaction
+
+        let mut context = context(&dag); // Make a context for the rule
+        assert!(StampRule.check(&context, &tx).is_ok()); // Ensure no work is required by default
+
+        context.stamp_difficulty = 8; // Demand ~256 hashes per transaction
+        assert!(StampRule.check(&context, &tx).is_err()); // Ensure unstamped transactions are rejected
+
+        tx.stamp = Some(Stamp::mint(tx.hash, 8)); // Stamp the transaction
+        assert!(StampRule.check(&context, &tx).is_ok()); // Ensure the stamp is accepted
+
+        context.stamp_difficulty = 255; // Demand more work than the stamp proves
+        assert!(StampRule.check(&context, &tx).is_err());
+
+        assert_eq!(dag.write_to_disk(), Ok(())); // Close the dag
+    }
+
+    #[test]
+    fn test_time_lock_rule() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender = Address::from_key_pair(&Keypair::generate(&mut csprng)); // Derive a sender address
+
+        let mut dag = root_graph();
+        let root = dag.nodes[0].hash;
+        let unlocked_at =
+            dag.nodes[0].transaction.transaction_data.timestamp + chrono::Duration::seconds(1);
+
+        // Lock a transaction built on the root until just after the root was made
+        let mut tx = tx_on(&dag, sender, 0, 0, vec![root]);
+        tx.register_time_lock(Some(unlocked_at), None);
+        assert!(TimeLockRule.check(&context(&dag), &tx).is_err()); // Ensure the node's clock isn't consulted
+
+        let validator = GraphBoundValidator::new(&dag, "olympia");
+        assert!(!validator.transaction_can_mature(&tx)); // Ensure the root's time will never change
+
+        // Add a transaction made at the end of the lock
+        let later = TransactionBuilder::new()
+            .nonce(0)
+            .sender(Address::default())
+            .recipient(Address::default())
+            .parents(vec![root])
+            .timestamp(unlocked_at)
+            .build()
+            .unwrap();
+        let later_hash = later.hash;
+        dag.push(later, None);
+
+        // The same lock has elapsed for a transaction built on the later transaction
+        let mut tx = TransactionBuilder::new()
+            .nonce(0)
+            .sender(sender)
+            .recipient(Address::default())
+            .parents(vec![root, later_hash])
+            .build()
+            .unwrap();
+        tx.register_time_lock(Some(unlocked_at), None);
+        assert!(TimeLockRule.check(&context(&dag), &tx).is_ok());
+
+        assert_eq!(dag.write_to_disk(), Ok(())); // Close the dag
+    }
+
+    #[test]
+    fn test_timestamp_rule() {
+        let dag = root_graph();
+        let root_time = dag.nodes[0].transaction.transaction_data.timestamp;
+
+        // Makes a transaction built on the root, dated the given number of seconds after it
+        let dated = |secs: i64| {
+            TransactionBuilder::new()
+                .nonce(0)
+                .sender(Address::default())
+                .recipient(Address::default())
+                .parents(vec![dag.nodes[0].hash])
+                .timestamp(root_time + chrono::Duration::seconds(secs))
+                .build()
+                .unwrap()
+        };
+
+        assert!(TimestampRule
+            .check(&context(&dag), &dated(MAX_TIMESTAMP_DRIFT_SECS))
+            .is_ok());
+        assert!(TimestampRule
+            .check(&context(&dag), &dated(MAX_TIMESTAMP_DRIFT_SECS + 1))
+            .is_err()); // Ensure transactions can't be dated far into the future
+
+        assert_eq!(dag.write_to_disk(), Ok(())); // Close the dag
+    }
+
+    #[test]
+    fn test_delegation_rule() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let delegator = Keypair::generate(&mut csprng);
+        let delegate = Address::from_key_pair(&Keypair::generate(&mut csprng));
+        let delegator_address = Address::from_key_pair(&delegator);
+
+        let dag = root_graph();
+        let context = context(&dag); // Make a context for the rule
+        let check = |sender: Address, delegate: Address| {
+            let delegation = Delegation::new(
+                Some(delegate),
+                None,
+                Keypair::from_bytes(&delegator.to_bytes()).unwrap(),
+                "olympia",
+            );
+            let mut tx = tx_on(&dag, sender, 0, 0, vec![dag.nodes[0].hash]);
+            tx.transaction_data.payload = Payload::delegation(&delegation.encode());
+
+            DelegationRule.check(&context, &tx)
+        };
+
+        assert!(check(delegator_address, delegate).is_ok()); // Ensure delegators can delegate their own votes
+        assert!(check(delegate, delegate).is_err()); // Ensure nobody else can send a delegator's delegation
+        assert!(check(delegator_address, delegator_address).is_err()); // Ensure accounts can't delegate to themselves
+
+        assert_eq!(dag.write_to_disk(), Ok(())); // Close the dag
+    }
+
+    #[test]
+    fn test_concurrent_branches() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender = Address::from_key_pair(&Keypair::generate(&mut csprng)); // Derive a sender address
+
+        // Make two branches off of the root: one in which the sender has spent most of their balance, and one in
+        // which they haven't sent anything yet. The second branch is the most recently executed.
+        let mut dag = root_graph();
+        let spent = push_sender_state(&mut dag, sender, Some(0), 2);
+        let unspent = push_sender_state(&mut dag, sender, None, 10);
+
+        let context = context(&dag); // Make a context for the rules
+
+        // Ensure that each transaction is checked against the branch that it builds on
+        assert!(BalanceRule
+            .check(&context, &tx_on(&dag, sender, 1, 5, vec![spent]))
+            .is_err());
+        assert!(BalanceRule
+            .check(&context, &tx_on(&dag, sender, 1, 2, vec![spent]))
+            .is_ok());
+        assert!(BalanceRule
+            .check(&context, &tx_on(&dag, sender, 0, 5, vec![unspent]))
+            .is_ok());
+        assert!(NonceRule
+            .check(&context, &tx_on(&dag, sender, 1, 0, vec![spent]))
+            .is_ok());
+        assert!(NonceRule
+            .check(&context, &tx_on(&dag, sender, 0, 0, vec![unspent]))
+            .is_ok());
+
+        // Ensure that a transaction building on both branches can't reuse a nonce spent on either of them
+        assert!(NonceRule
+            .check(&context, &tx_on(&dag, sender, 0, 0, vec![unspent, spent]))
+            .is_err());
+        assert!(NonceRule
+            .check(&context, &tx_on(&dag, sender, 1, 0, vec![unspent, spent]))
+            .is_ok());
+
+        // Ensure that a transaction can't commit to the state of a branch that it doesn't build on
+        let mut misattributed = tx_on(&dag, sender, 0, 0, vec![unspent]);
+        misattributed.transaction_data.parent_state_hash = tx_on(&dag, sender, 1, 0, vec![spent])
+            .transaction_data
+            .parent_state_hash;
+        assert!(ParentStateRule
+            .check(&context, &tx_on(&dag, sender, 0, 0, vec![unspent]))
+            .is_ok());
+        assert!(ParentStateRule.check(&context, &misattributed).is_err());
+
+        assert_eq!(dag.write_to_disk(), Ok(())); // Close the dag
+    }
+
+    #[test]
+    fn test_rule_pipeline() {
+        let mut csprng = OsRng {}; // Generate source of randomness
+
+        let sender = Address::from_key_pair(&Keypair::generate(&mut csprng)); // Derive a sender address
+        let dag = root_graph(); // Make a graph to validate against
+
+        let tx = TransactionBuilder::new()
+            .nonce(1)
+            .sender(sender)
+            .recipient(sender)
+            .build()
+            .unwrap(); // Make an unsigned transaction to the sender itself
+
+        let validator = GraphBoundValidator::new(&dag, "olympia");
+        assert_eq!(validator.rule_names().len(), default_rules().len()); // Ensure the default rules are enforced
+        assert!(validator.transaction_is_valid(&tx).is_err()); // Ensure the unsigned tx is rejected
+
+        let report = validator.validation_report(&tx); // Collect every violation, rather than just the first
+        let violated_rules: Vec<&str> = report.violations.iter().map(|v| v.rule.as_str()).collect();
+        assert!(!report.is_valid());
+        assert!(violated_rules.contains(&"signature")); // Ensure the missing signature is reported
+        assert!(violated_rules.contains(&"identity")); // Ensure later violations are also reported
+        assert!(!violated_rules.contains(&"hash")); // Ensure satisfied rules aren't reported
+
+        let report = validator.first_violation_report(&tx); // Stop at the first violation
+        assert_eq!(report.violations.len(), 1); // Ensure later rules weren't checked
+        assert_eq!(
+            report.checked_rules.last(),
+            Some(&report.violations[0].rule)
+        );
+
+        let validator = GraphBoundValidator::new(&dag, "olympia")
+            .with_rules(vec![Box::new(HashRule), Box::new(IdentityRule)]); // Only check the tx's hash & identity
+        assert!(validator.transaction_is_valid(&tx).is_err()); // Ensure the identity operation is rejected
+
+        let validator = validator.without_rule("identity");
+        assert_eq!(validator.rule_names(), vec!["hash"]); // Ensure the rule was removed
+        assert!(validator.transaction_is_valid(&tx).is_ok()); // Ensure only the remaining rules are enforced
+
+        assert_eq!(dag.write_to_disk(), Ok(())); // Close the dag
+    }
+}